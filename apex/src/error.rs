@@ -0,0 +1,189 @@
+use std::rc::Rc;
+
+use crate::helpers::AnyComponent;
+
+/// Key of the route data holding the error boundaries that failed on the server
+const ERRORS_KEY: &str = "apex:errors";
+
+/// Message the client hydrates the fallback with, the message of the error the server caught
+/// isn't sent to the browser as it may hold internal details
+#[cfg(target_arch = "wasm32")]
+const CLIENT_ERROR_MESSAGE: &str = "this part of the page failed to render";
+
+/// Error caught while rendering or hydrating a component, passed to the fallback
+#[derive(Debug, Clone, PartialEq)]
+pub struct RenderError {
+    message: String,
+}
+
+impl RenderError {
+    pub fn new(message: impl Into<String>) -> Self {
+        Self {
+            message: message.into(),
+        }
+    }
+
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+impl std::fmt::Display for RenderError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for RenderError {}
+
+/// Builds the component rendered in place of a subtree that failed, e.g.
+/// `ErrorFallback::new(|error| ErrorMessage::builder().error(error).build())`
+#[derive(Clone)]
+pub struct ErrorFallback {
+    factory: Rc<dyn Fn(RenderError) -> Box<dyn AnyComponent>>,
+}
+
+impl ErrorFallback {
+    pub fn new<C: AnyComponent + 'static>(factory: impl Fn(RenderError) -> C + 'static) -> Self {
+        Self {
+            factory: Rc::new(move |error| Box::new(factory(error))),
+        }
+    }
+
+    pub fn build(&self, error: RenderError) -> Box<dyn AnyComponent> {
+        (self.factory)(error)
+    }
+}
+
+/// Run a render or hydration, turning a panic into an error. Panics can't be caught when
+/// they abort, which is the default for wasm.
+pub fn catch_render<T>(render: impl FnOnce() -> T) -> Result<T, RenderError> {
+    std::panic::catch_unwind(std::panic::AssertUnwindSafe(render)).map_err(|payload| {
+        let message = payload
+            .downcast_ref::<&str>()
+            .map(|message| (*message).to_owned())
+            .or_else(|| payload.downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "component panicked".to_owned());
+
+        RenderError::new(message)
+    })
+}
+
+/// Render `render`, or the fallback if it fails. Counters are restored before rendering
+/// the fallback, and the failed boundary is kept in the route data so the client hydrates
+/// the fallback too. The error is logged, only the server sees its message.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn render_or_fallback(
+    boundary: &str,
+    data: crate::helpers::RenderData,
+    render: impl FnOnce() -> String,
+    fallback: impl FnOnce(RenderError) -> String,
+) -> String {
+    let text_node_counter = apex_utils::get_text_node_counter();
+    let element_counter = apex_utils::get_element_counter();
+    let conditional_counter = apex_utils::get_conditional_counter();
+
+    match catch_render(render) {
+        Ok(html) => html,
+        Err(error) => {
+            apex_utils::reset_text_node_counter(Some(text_node_counter));
+            apex_utils::reset_element_counter(Some(element_counter));
+            apex_utils::reset_conditional_counter(Some(conditional_counter));
+
+            let mut data = data.borrow_mut();
+            let errors = data
+                .entry(ERRORS_KEY.to_owned())
+                .or_insert_with(|| serde_json::Value::Object(Default::default()));
+
+            if let serde_json::Value::Object(errors) = errors {
+                errors.insert(boundary.to_owned(), true.into());
+            }
+
+            drop(data);
+            eprintln!("failed to render `{boundary}`: {error}");
+            fallback(error)
+        }
+    }
+}
+
+/// Hydrate `hydrate`, or the fallback if the server rendered it. A panic while hydrating
+/// is reported to the console, the server markup stays in place.
+#[cfg(target_arch = "wasm32")]
+pub fn hydrate_or_fallback(
+    boundary: &str,
+    state: Rc<std::cell::RefCell<apex_router::client_router::State>>,
+    hydrate: impl FnOnce(Rc<std::cell::RefCell<apex_router::client_router::State>>),
+    fallback: impl FnOnce(RenderError, Rc<std::cell::RefCell<apex_router::client_router::State>>),
+) {
+    let failed_on_server = apex_router::init_data::get_typed_route_data::<
+        std::collections::HashMap<String, bool>,
+    >(ERRORS_KEY)
+    .is_some_and(|errors| errors.contains_key(boundary));
+
+    if failed_on_server {
+        fallback(RenderError::new(CLIENT_ERROR_MESSAGE), state);
+        return;
+    }
+
+    if let Err(error) = catch_render(|| hydrate(state)) {
+        web_sys::console::error_1(&format!("failed to hydrate `{boundary}`: {error}").into());
+    }
+}
+
+/// Identifies an `<ErrorBoundary>`, the same on the server and the client as long as
+/// they render the same components
+pub fn next_boundary_id() -> String {
+    format!("boundary-{}", apex_utils::next_conditional_counter())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn catch_render_keeps_the_panic_message() {
+        let error = catch_render(|| panic!("unknown operator `{}`", "%")).unwrap_err();
+        assert_eq!(error.message(), "unknown operator `%`");
+
+        let error = catch_render(|| panic!("division by zero")).unwrap_err();
+        assert_eq!(error.message(), "division by zero");
+
+        assert_eq!(catch_render(|| 42), Ok(42));
+    }
+
+    #[test]
+    fn render_or_fallback_records_the_error() {
+        let data = Rc::new(std::cell::RefCell::new(std::collections::HashMap::new()));
+
+        let html = render_or_fallback(
+            "calculator",
+            data.clone(),
+            || panic!("unknown operator"),
+            |error| format!("<p>{error}</p>"),
+        );
+
+        assert_eq!(html, "<p>unknown operator</p>");
+        assert_eq!(
+            data.borrow().get(ERRORS_KEY),
+            Some(&serde_json::json!({ "calculator": true }))
+        );
+
+        let html = render_or_fallback(
+            "about",
+            data.clone(),
+            || "<p>About</p>".to_owned(),
+            |_| unreachable!(),
+        );
+
+        assert_eq!(html, "<p>About</p>");
+        assert_eq!(
+            data.borrow()
+                .get(ERRORS_KEY)
+                .unwrap()
+                .as_object()
+                .unwrap()
+                .len(),
+            1
+        );
+    }
+}
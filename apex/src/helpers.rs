@@ -112,11 +112,11 @@ impl IntoHtmlAttributes for bool {
     }
 }
 
-impl<T: Clone + ToString + 'static> IntoHtmlAttributes for Signal<T> {
+/// Reading the signal subscribes the surrounding effect, so `{..attrs}` spreads of a signal
+/// are re-applied when it changes
+impl<T: IntoHtmlAttributes + Clone + 'static> IntoHtmlAttributes for Signal<T> {
     fn into_attrs(self) -> HtmlAttributes {
-        let mut attrs = HtmlAttributes::new();
-        attrs.set("data-signal", self.get().to_string());
-        attrs
+        self.get().into_attrs()
     }
 }
 
@@ -170,6 +170,24 @@ impl HtmlAttributes {
         self.map.into_iter()
     }
 
+    pub fn iter(&self) -> impl Iterator<Item = (&String, &String)> {
+        self.map.iter()
+    }
+
+    /// Writes the attributes as ` name="value"` pairs sorted by name, escaping the values
+    pub fn render_into(&self, buffer: &mut String) {
+        let mut attributes: Vec<_> = self.map.iter().collect();
+        attributes.sort_by_key(|(name, _)| *name);
+
+        for (name, value) in attributes {
+            buffer.push(' ');
+            buffer.push_str(name);
+            buffer.push_str("=\"");
+            escape_attribute_value(value, buffer);
+            buffer.push('"');
+        }
+    }
+
     fn merge_class(&mut self, value: &str) {
         if value.is_empty() {
             return;
@@ -207,3 +225,274 @@ impl HtmlAttributes {
         }
     }
 }
+
+fn escape_attribute_value(value: &str, buffer: &mut String) {
+    for ch in value.chars() {
+        match ch {
+            '&' => buffer.push_str("&amp;"),
+            '"' => buffer.push_str("&quot;"),
+            '<' => buffer.push_str("&lt;"),
+            '>' => buffer.push_str("&gt;"),
+            _ => buffer.push(ch),
+        }
+    }
+}
+
+/// Route data available while rendering, shared by a page and its components
+pub type RenderData = Rc<std::cell::RefCell<HashMap<String, serde_json::Value>>>;
+
+/// Typed slot of a component, declared as `#[slot] row: Slot<Item>`. The component renders
+/// it with a value, `<#slot row {item} />`, which the slot content receives through
+/// `let:`, `<#row let:item>...</#row>`.
+pub struct Slot<T = ()> {
+    render: Rc<SlotRender<T>>,
+    hydrate: Rc<SlotHydrate<T>>,
+    empty: bool,
+}
+
+type SlotRender<T> = dyn Fn(&mut String, RenderData, T);
+type SlotHydrate<T> = dyn Fn(Rc<std::cell::RefCell<apex_router::client_router::State>>, T);
+
+impl<T> Slot<T> {
+    pub fn new(
+        render: impl Fn(&mut String, RenderData, T) + 'static,
+        hydrate: impl Fn(Rc<std::cell::RefCell<apex_router::client_router::State>>, T) + 'static,
+    ) -> Self {
+        Self {
+            render: Rc::new(render),
+            hydrate: Rc::new(hydrate),
+            empty: false,
+        }
+    }
+
+    /// Slot without content, for `#[slot(default)]`
+    pub fn empty() -> Self {
+        Self {
+            empty: true,
+            ..Self::new(|_, _, _| {}, |_, _| {})
+        }
+    }
+
+    /// Whether the slot was left out, what `has_slot!` checks for typed slots
+    pub fn is_empty(&self) -> bool {
+        self.empty
+    }
+
+    pub fn render(&self, buffer: &mut String, data: RenderData, props: T) {
+        (self.render)(buffer, data, props);
+    }
+
+    pub fn hydrate(
+        &self,
+        state: Rc<std::cell::RefCell<apex_router::client_router::State>>,
+        props: T,
+    ) {
+        (self.hydrate)(state, props);
+    }
+}
+
+impl<T> Clone for Slot<T> {
+    fn clone(&self) -> Self {
+        Self {
+            render: self.render.clone(),
+            hydrate: self.hydrate.clone(),
+            empty: self.empty,
+        }
+    }
+}
+
+impl<T> Default for Slot<T> {
+    fn default() -> Self {
+        Self::empty()
+    }
+}
+
+/// Renders the children or a named slot of a component into the buffer
+pub type RenderSlotFn = dyn for<'a> Fn(&'a mut String, RenderData) + 'static;
+pub type RenderSlot = Rc<Box<RenderSlotFn>>;
+
+/// Hydrates the children or a named slot of a component
+pub type HydrateSlotFn =
+    dyn Fn(Rc<std::cell::RefCell<apex_router::client_router::State>>) + 'static;
+pub type HydrateSlot = Rc<Box<HydrateSlotFn>>;
+
+/// Children and named slots given to `{#component}`, passed on to the component the
+/// factory builds
+#[derive(Clone, Default)]
+pub struct DynamicSlots {
+    pub render_children: Option<RenderSlot>,
+    pub named_slots: Option<HashMap<String, RenderSlot>>,
+    pub hydrate_children: Option<HydrateSlot>,
+    pub hydrate_named_slots: Option<HashMap<String, HydrateSlot>>,
+}
+
+impl DynamicSlots {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn render_children(mut self, value: Box<RenderSlotFn>) -> Self {
+        self.render_children = Some(Rc::new(value));
+        self
+    }
+
+    pub fn named_slots(mut self, value: HashMap<String, RenderSlot>) -> Self {
+        self.named_slots = Some(value);
+        self
+    }
+
+    pub fn hydrate_children(mut self, value: Box<HydrateSlotFn>) -> Self {
+        self.hydrate_children = Some(Rc::new(value));
+        self
+    }
+
+    pub fn hydrate_named_slots(mut self, value: HashMap<String, HydrateSlot>) -> Self {
+        self.hydrate_named_slots = Some(value);
+        self
+    }
+}
+
+/// Component behind a trait object, implemented by every `#[component]`
+pub trait AnyComponent {
+    #[cfg(not(target_arch = "wasm32"))]
+    fn render_any(self: Box<Self>, data: RenderData) -> String;
+
+    #[cfg(target_arch = "wasm32")]
+    fn hydrate_any(
+        self: Box<Self>,
+    ) -> Box<dyn FnOnce(Rc<std::cell::RefCell<apex_router::client_router::State>>)>;
+}
+
+/// Component chosen at runtime and rendered with `{#component expr in candidates}`.
+/// Components without required props have `Component::dynamic()`, others are built by a
+/// factory: `DynamicComponent::new(move |slots| Chart::builder().series(series.clone()).build())`.
+///
+/// The server renders a template for each candidate, like the branches of `{#if}`, and
+/// the browser swaps them in when the expression changes:
+/// `{#component widget.get() in [Counter::dynamic(), Chart::dynamic()]}`.
+#[derive(Clone)]
+pub struct DynamicComponent {
+    key: &'static str,
+    factory: Rc<dyn Fn(DynamicSlots) -> Box<dyn AnyComponent>>,
+}
+
+impl DynamicComponent {
+    pub fn new<C: AnyComponent + 'static>(factory: impl Fn(DynamicSlots) -> C + 'static) -> Self {
+        Self {
+            key: std::any::type_name::<C>(),
+            factory: Rc::new(move |slots| Box::new(factory(slots))),
+        }
+    }
+
+    /// Identifies the component type, the markup of two values with the same key is
+    /// swapped in place of each other
+    pub fn key(&self) -> &'static str {
+        self.key
+    }
+
+    pub fn build(&self, slots: DynamicSlots) -> Box<dyn AnyComponent> {
+        (self.factory)(slots)
+    }
+
+    /// Components rendered for `{#component}`, one per key in the order of the candidates.
+    /// This component takes the place of the candidate with its key, or comes last.
+    pub fn candidates(&self, candidates: impl IntoIterator<Item = DynamicComponent>) -> Vec<Self> {
+        let mut components: Vec<Self> = Vec::new();
+
+        for candidate in candidates {
+            if components
+                .iter()
+                .any(|component| component.key == candidate.key)
+            {
+                continue;
+            }
+
+            if candidate.key == self.key {
+                components.push(self.clone());
+            } else {
+                components.push(candidate);
+            }
+        }
+
+        if !components.iter().any(|component| component.key == self.key) {
+            components.push(self.clone());
+        }
+
+        components
+    }
+}
+
+impl PartialEq for DynamicComponent {
+    fn eq(&self, other: &Self) -> bool {
+        self.key == other.key
+    }
+}
+
+impl std::fmt::Debug for DynamicComponent {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("DynamicComponent").field(&self.key).finish()
+    }
+}
+
+/// State of a required component prop that hasn't been set on the builder yet
+pub struct PropUnset;
+
+/// State of a required component prop that has been set on the builder, holding its value
+pub struct PropSet<T>(pub T);
+
+/// Implemented for the state of set props, the component builder only has `build()` once
+/// every required prop is set
+#[diagnostic::on_unimplemented(
+    message = "missing required prop on component",
+    label = "required prop is not set",
+    note = "the bound below points at the prop that needs a value"
+)]
+pub trait PropIsSet<T> {
+    /// Returns the value of the prop
+    fn into_prop(self) -> T;
+}
+
+impl<T> PropIsSet<T> for PropSet<T> {
+    fn into_prop(self) -> T {
+        self.0
+    }
+}
+
+/// One character of a slot name. A slot name is a tuple of them, the type component
+/// builders implement the slot traits for
+pub struct SlotChar<const C: char>;
+
+/// Implemented by a component builder for each of its named slots, the `<#slot name />`s of
+/// its templates. `Name` is a type named after the slot given in `tmpl!`, for the error
+/// message.
+#[diagnostic::on_unimplemented(
+    message = "the component has no slot named `{Name}`",
+    label = "unknown slot",
+    note = "the named slots of a component are the `<#slot name />`s of its templates, its typed `#[slot]` parameters take their content with `let:`"
+)]
+pub trait HasNamedSlot<Chars, Name> {}
+
+/// Checks that the component built by `builder` has the named slot given to it
+pub fn assert_named_slot<Chars, Name, B: HasNamedSlot<Chars, Name>>(_builder: &B) {}
+
+/// Implemented by a component builder for each of its typed slots, set to the content given
+/// with `let:`. `Name` is a type named after the slot given in `tmpl!`, for the error message.
+#[diagnostic::on_unimplemented(
+    message = "the component has no typed slot named `{Name}`",
+    label = "unknown slot",
+    note = "the typed slots of a component are its `#[slot]` parameters"
+)]
+pub trait SetTypedSlot<Chars, Name> {
+    /// Value the slot is rendered with
+    type Value;
+    /// Builder once the slot is set
+    type Output;
+
+    /// Sets the slot to the closures rendering and hydrating its content
+    fn set_typed_slot(
+        self,
+        render: impl Fn(&mut String, RenderData, Self::Value) + 'static,
+        hydrate: impl Fn(Rc<std::cell::RefCell<apex_router::client_router::State>>, Self::Value)
+        + 'static,
+    ) -> Self::Output;
+}
@@ -3,12 +3,20 @@
 pub mod prelude;
 
 pub use apex_router;
+pub use apex_router::{
+    ApexMiddleware, HttpResponse, LoaderResult, Path, RequestContext, ServerFnError, with_base_path,
+};
 pub use apex_utils;
 pub use bytes;
 pub use js_sys;
+#[cfg(not(target_arch = "wasm32"))]
+pub use server::serve;
 pub use wasm_bindgen;
 pub use web_sys;
 
 pub mod action;
+pub mod error;
 pub mod helpers;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod server;
 pub mod signal;
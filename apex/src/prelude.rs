@@ -1,5 +1,17 @@
-pub use crate::helpers::{EventHandler, HtmlAttributes, noop_event};
-pub use crate::{action, derive, effect, signal, signal::Signal};
-pub use apex_macro::{component, loader_data, route, tmpl};
+pub use crate::error::{ErrorFallback, RenderError};
+pub use crate::helpers::{DynamicComponent, EventHandler, HtmlAttributes, Slot, noop_event};
+pub use crate::{
+    action, derive, effect, signal,
+    signal::{MaybeSignal, Signal},
+};
+pub use apex_macro::{
+    action_data, component, has_slot, loader_data, route, route_action, server, tmpl,
+};
 pub use apex_router;
+pub use apex_router::{
+    ApexMiddleware, HttpResponse, LoaderResult, Path, RequestContext, ServerFnError, with_base_path,
+};
 pub use wasm_bindgen::JsCast;
+
+// The route parameters, `HashMap<String, String>`
+pub use std::collections::HashMap;
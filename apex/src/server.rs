@@ -0,0 +1,487 @@
+//! HTTP server running an [`ApexServerRouter`], serving static files next to it.
+//!
+//! ```rust,ignore
+//! apex::server::Server::new(ApexServerRouter::new(&RootPageRoute::new()))
+//!     .bind("0.0.0.0:9999")
+//!     .static_dir("/static", "static")
+//!     .run()
+//!     .await?;
+//! ```
+
+use std::{
+    future::Future,
+    net::SocketAddr,
+    path::{Component, Path, PathBuf},
+    pin::Pin,
+    sync::Arc,
+    time::Duration,
+};
+
+use apex_router::{ApexServerRouter, DEFAULT_MAX_BODY_SIZE, HttpResponse, RequestContext};
+use bytes::Bytes;
+use http_body_util::{BodyExt, Full, LengthLimitError, Limited};
+use hyper::{Request, Response, StatusCode, body::Incoming, service::service_fn};
+use hyper_util::{
+    rt::{TokioExecutor, TokioIo},
+    server::{conn::auto, graceful::GracefulShutdown},
+};
+use tokio::{net::TcpListener, sync::Semaphore};
+
+type ShutdownSignal = Pin<Box<dyn Future<Output = ()> + Send>>;
+
+/// HTTP versions the server accepts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Protocols {
+    Http1,
+    Http2,
+    Both,
+}
+
+/// HTTP server for an [`ApexServerRouter`], configured with builder methods.
+///
+/// Requests under a static directory's prefix are served from the directory, the others
+/// are handled by the router. The server accepts HTTP/1 and HTTP/2, and stops on `SIGTERM`
+/// or `Ctrl-C`, letting the open connections finish.
+pub struct Server {
+    router: Arc<ApexServerRouter>,
+    addr: String,
+    static_dirs: Arc<Vec<(String, PathBuf)>>,
+    max_connections: usize,
+    max_body_size: usize,
+    protocols: Protocols,
+    shutdown_timeout: Duration,
+    shutdown_signal: Option<ShutdownSignal>,
+}
+
+impl std::fmt::Debug for Server {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Server")
+            .field("addr", &self.addr)
+            .field("static_dirs", &self.static_dirs)
+            .field("max_connections", &self.max_connections)
+            .field("max_body_size", &self.max_body_size)
+            .field("protocols", &self.protocols)
+            .field("shutdown_timeout", &self.shutdown_timeout)
+            .finish()
+    }
+}
+
+impl Server {
+    /// Creates a server for the router, listening on `127.0.0.1:3000`.
+    pub fn new(router: ApexServerRouter) -> Self {
+        Self {
+            router: Arc::new(router),
+            addr: "127.0.0.1:3000".to_owned(),
+            static_dirs: Arc::new(Vec::new()),
+            max_connections: 1024,
+            max_body_size: DEFAULT_MAX_BODY_SIZE,
+            protocols: Protocols::Both,
+            shutdown_timeout: Duration::from_secs(30),
+            shutdown_signal: None,
+        }
+    }
+
+    /// Sets the address to listen on, e.g. `0.0.0.0:8080`.
+    pub fn bind(mut self, addr: impl Into<String>) -> Self {
+        self.addr = addr.into();
+        self
+    }
+
+    /// Serves the files of `dir` under the `prefix` path, e.g. `/static`.
+    pub fn static_dir(mut self, prefix: impl Into<String>, dir: impl Into<PathBuf>) -> Self {
+        let prefix = prefix.into().trim_end_matches('/').to_owned();
+        Arc::make_mut(&mut self.static_dirs).push((prefix, dir.into()));
+        self
+    }
+
+    /// Sets the number of connections served at once. Other clients wait to be accepted.
+    pub fn max_connections(mut self, max_connections: usize) -> Self {
+        self.max_connections = max_connections.max(1);
+        self
+    }
+
+    /// Sets the largest request body read, in bytes, 2 MiB by default. Requests with a larger
+    /// body are answered `413 Payload Too Large`.
+    pub fn max_body_size(mut self, max_body_size: usize) -> Self {
+        self.max_body_size = max_body_size;
+        self
+    }
+
+    /// Only accepts HTTP/1 connections.
+    pub fn http1_only(mut self) -> Self {
+        self.protocols = Protocols::Http1;
+        self
+    }
+
+    /// Only accepts HTTP/2 connections, without TLS.
+    pub fn http2_only(mut self) -> Self {
+        self.protocols = Protocols::Http2;
+        self
+    }
+
+    /// Sets how long the open connections may take to finish once the server stops.
+    pub fn shutdown_timeout(mut self, shutdown_timeout: Duration) -> Self {
+        self.shutdown_timeout = shutdown_timeout;
+        self
+    }
+
+    /// Stops the server when `signal` completes, instead of on `SIGTERM` or `Ctrl-C`.
+    pub fn shutdown_signal(mut self, signal: impl Future<Output = ()> + Send + 'static) -> Self {
+        self.shutdown_signal = Some(Box::pin(signal));
+        self
+    }
+
+    /// Listens on the bound address and serves requests until the server stops.
+    pub async fn run(self) -> std::io::Result<()> {
+        let listener = TcpListener::bind(&self.addr).await?;
+        self.run_with_listener(listener).await
+    }
+
+    /// Serves requests accepted by `listener` until the server stops.
+    pub async fn run_with_listener(self, listener: TcpListener) -> std::io::Result<()> {
+        let mut builder = auto::Builder::new(TokioExecutor::new());
+
+        builder = match self.protocols {
+            Protocols::Http1 => builder.http1_only(),
+            Protocols::Http2 => builder.http2_only(),
+            Protocols::Both => builder,
+        };
+
+        let connections = Arc::new(Semaphore::new(self.max_connections));
+        let graceful = GracefulShutdown::new();
+        let mut shutdown_signal = self
+            .shutdown_signal
+            .unwrap_or_else(|| Box::pin(terminate_signal()));
+
+        loop {
+            let permit = tokio::select! {
+                permit = Arc::clone(&connections).acquire_owned() => match permit {
+                    Ok(permit) => permit,
+                    Err(_) => break,
+                },
+                () = &mut shutdown_signal => break,
+            };
+
+            let (stream, remote_addr) = tokio::select! {
+                accepted = listener.accept() => match accepted {
+                    Ok(accepted) => accepted,
+                    Err(err) => {
+                        eprintln!("Error accepting connection: {err}");
+                        continue;
+                    }
+                },
+                () = &mut shutdown_signal => break,
+            };
+
+            let router = Arc::clone(&self.router);
+            let static_dirs = Arc::clone(&self.static_dirs);
+            let max_body_size = self.max_body_size;
+
+            let service = service_fn(move |request| {
+                let router = Arc::clone(&router);
+                let static_dirs = Arc::clone(&static_dirs);
+
+                async move { handle(request, remote_addr, &router, &static_dirs, max_body_size).await }
+            });
+
+            let connection = builder
+                .serve_connection_with_upgrades(TokioIo::new(stream), service)
+                .into_owned();
+            let connection = graceful.watch(connection);
+
+            tokio::spawn(async move {
+                if let Err(err) = connection.await {
+                    eprintln!("Error serving connection: {err}");
+                }
+
+                drop(permit);
+            });
+        }
+
+        drop(listener);
+
+        if tokio::time::timeout(self.shutdown_timeout, graceful.shutdown())
+            .await
+            .is_err()
+        {
+            eprintln!("Timed out waiting for the connections to finish");
+        }
+
+        Ok(())
+    }
+}
+
+/// Creates a server for the router, see [`Server`].
+pub fn serve(router: ApexServerRouter) -> Server {
+    Server::new(router)
+}
+
+/// Completes on `SIGTERM` or `Ctrl-C`.
+async fn terminate_signal() {
+    let ctrl_c = async {
+        let _ = tokio::signal::ctrl_c().await;
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+            Ok(mut signal) => {
+                signal.recv().await;
+            }
+            Err(_) => std::future::pending().await,
+        }
+    };
+
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        () = ctrl_c => {}
+        () = terminate => {}
+    }
+}
+
+async fn handle(
+    request: Request<Incoming>,
+    remote_addr: SocketAddr,
+    router: &ApexServerRouter,
+    static_dirs: &[(String, PathBuf)],
+    max_body_size: usize,
+) -> Result<Response<Full<Bytes>>, hyper::Error> {
+    let path = request.uri().path();
+
+    for (prefix, dir) in static_dirs {
+        if let Some(file_path) = path.strip_prefix(prefix.as_str())
+            && file_path.starts_with('/')
+        {
+            return Ok(serve_static(dir, file_path).await);
+        }
+    }
+
+    let uri = request
+        .uri()
+        .path_and_query()
+        .map_or_else(|| path.to_owned(), ToString::to_string);
+
+    let mut context = RequestContext::new(request.method().as_str(), uri).remote_addr(remote_addr);
+
+    for (name, value) in request.headers() {
+        if let Ok(value) = value.to_str() {
+            context = context.header(name.as_str(), value);
+        }
+    }
+
+    let body = match Limited::new(request.into_body(), max_body_size)
+        .collect()
+        .await
+    {
+        Ok(body) => body.to_bytes(),
+        Err(error) if error.is::<LengthLimitError>() => {
+            return Ok(plain_response(
+                StatusCode::PAYLOAD_TOO_LARGE,
+                "Payload Too Large",
+            ));
+        }
+        Err(error) => match error.downcast::<hyper::Error>() {
+            Ok(error) => return Err(*error),
+            Err(_) => return Ok(plain_response(StatusCode::BAD_REQUEST, "Bad Request")),
+        },
+    };
+
+    let context = context.body(String::from_utf8_lossy(&body));
+
+    let response = router.handle(context).await;
+
+    Ok(into_response(response))
+}
+
+fn into_response(response: HttpResponse) -> Response<Full<Bytes>> {
+    let mut builder = Response::builder().status(response.status);
+
+    for (name, value) in &response.headers {
+        builder = builder.header(name, value);
+    }
+
+    builder
+        .body(Full::new(Bytes::from(response.body)))
+        .unwrap_or_else(|_| plain_response(StatusCode::INTERNAL_SERVER_ERROR, "Invalid response"))
+}
+
+async fn serve_static(dir: &Path, file_path: &str) -> Response<Full<Bytes>> {
+    // Files outside of the directory aren't served
+    let relative_path = Path::new(file_path.trim_start_matches('/'));
+
+    if !relative_path
+        .components()
+        .all(|component| matches!(component, Component::Normal(_)))
+    {
+        return plain_response(StatusCode::NOT_FOUND, "Not Found");
+    }
+
+    let file_path = dir.join(relative_path);
+
+    match tokio::fs::read(&file_path).await {
+        Ok(content) => Response::builder()
+            .header("content-type", content_type(&file_path))
+            .body(Full::new(Bytes::from(content)))
+            .unwrap_or_else(|_| {
+                plain_response(StatusCode::INTERNAL_SERVER_ERROR, "Invalid response")
+            }),
+        Err(_) => plain_response(StatusCode::NOT_FOUND, "Not Found"),
+    }
+}
+
+fn plain_response(status: StatusCode, body: &'static str) -> Response<Full<Bytes>> {
+    let mut response = Response::new(Full::new(Bytes::from_static(body.as_bytes())));
+    *response.status_mut() = status;
+    response.headers_mut().insert(
+        hyper::header::CONTENT_TYPE,
+        hyper::header::HeaderValue::from_static("text/plain; charset=utf-8"),
+    );
+
+    response
+}
+
+/// MIME type of a static file, from its extension.
+fn content_type(path: &Path) -> &'static str {
+    let extension = path
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .map(str::to_ascii_lowercase);
+
+    match extension.as_deref() {
+        Some("html" | "htm") => "text/html; charset=utf-8",
+        Some("css") => "text/css; charset=utf-8",
+        Some("js" | "mjs") => "text/javascript; charset=utf-8",
+        Some("json" | "map") => "application/json",
+        Some("wasm") => "application/wasm",
+        Some("txt") => "text/plain; charset=utf-8",
+        Some("xml") => "application/xml",
+        Some("png") => "image/png",
+        Some("jpg" | "jpeg") => "image/jpeg",
+        Some("gif") => "image/gif",
+        Some("svg") => "image/svg+xml",
+        Some("ico") => "image/x-icon",
+        Some("webp") => "image/webp",
+        Some("avif") => "image/avif",
+        Some("woff") => "font/woff",
+        Some("woff2") => "font/woff2",
+        Some("ttf") => "font/ttf",
+        Some("otf") => "font/otf",
+        Some("pdf") => "application/pdf",
+        Some("mp4") => "video/mp4",
+        Some("webm") => "video/webm",
+        _ => "application/octet-stream",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use apex_router::ApexServerRoute;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    struct IndexRoute;
+
+    impl ApexServerRoute for IndexRoute {}
+
+    async fn get(addr: SocketAddr, path: &str) -> String {
+        let mut stream = tokio::net::TcpStream::connect(addr).await.unwrap();
+        let request =
+            format!("GET {path} HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n");
+        stream.write_all(request.as_bytes()).await.unwrap();
+
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+
+        response.to_ascii_lowercase()
+    }
+
+    async fn post(addr: SocketAddr, path: &str, body: &str) -> String {
+        let mut stream = tokio::net::TcpStream::connect(addr).await.unwrap();
+        let request = format!(
+            "POST {path} HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\nContent-Length: {}\r\n\r\n{body}",
+            body.len()
+        );
+        stream.write_all(request.as_bytes()).await.unwrap();
+
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+
+        response.to_ascii_lowercase()
+    }
+
+    #[test]
+    fn static_content_types() {
+        assert_eq!(content_type(Path::new("app.wasm")), "application/wasm");
+        assert_eq!(
+            content_type(Path::new("pkg/app.JS")),
+            "text/javascript; charset=utf-8"
+        );
+        assert_eq!(
+            content_type(Path::new("README")),
+            "application/octet-stream"
+        );
+    }
+
+    #[tokio::test]
+    async fn rejects_large_bodies() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let (stop, stopped) = tokio::sync::oneshot::channel::<()>();
+
+        let server = serve(ApexServerRouter::new(&IndexRoute))
+            .max_body_size(16)
+            .shutdown_signal(async {
+                let _ = stopped.await;
+            });
+        let server = tokio::spawn(server.run_with_listener(listener));
+
+        let response = post(addr, "/", "name=small").await;
+        assert!(!response.starts_with("http/1.1 413"));
+
+        let response = post(addr, "/", "name=too-large-for-the-limit").await;
+        assert!(response.starts_with("http/1.1 413"));
+
+        stop.send(()).unwrap();
+        server.await.unwrap().unwrap();
+    }
+
+    #[tokio::test]
+    async fn serves_routes_and_static_files() {
+        let dir = std::env::temp_dir().join(format!("apex-server-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("style.css"), "body {}").unwrap();
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let (stop, stopped) = tokio::sync::oneshot::channel::<()>();
+
+        let server = serve(ApexServerRouter::new(&IndexRoute))
+            .static_dir("/static", &dir)
+            .shutdown_signal(async {
+                let _ = stopped.await;
+            });
+        let server = tokio::spawn(server.run_with_listener(listener));
+
+        let response = get(addr, "/static/style.css").await;
+        assert!(response.starts_with("http/1.1 200"));
+        assert!(response.contains("content-type: text/css; charset=utf-8"));
+        assert!(response.ends_with("body {}"));
+
+        let response = get(addr, "/static/../style.css").await;
+        assert!(response.starts_with("http/1.1 404"));
+
+        let response = get(addr, "/?has_exclude=true").await;
+        assert!(response.starts_with("http/1.1 200"));
+        assert!(response.contains("content-type: application/json"));
+
+        let response = get(addr, "/missing").await;
+        assert!(response.starts_with("http/1.1 404"));
+        assert!(response.contains("content-type: text/html"));
+
+        stop.send(()).unwrap();
+        server.await.unwrap().unwrap();
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -170,6 +170,57 @@ impl<T: Clone + fmt::Display + 'static> fmt::Display for Signal<T> {
     }
 }
 
+/// Either a static value or a signal, for props that are reactive only when given a signal.
+/// Reading it inside an effect subscribes to the signal, if there is one.
+#[derive(Clone)]
+pub enum MaybeSignal<T: 'static + Clone> {
+    Static(T),
+    Dynamic(Signal<T>),
+}
+
+impl<T: 'static + Clone> MaybeSignal<T> {
+    pub fn get(&self) -> T {
+        match self {
+            MaybeSignal::Static(value) => value.clone(),
+            MaybeSignal::Dynamic(signal) => signal.get(),
+        }
+    }
+
+    pub fn is_reactive(&self) -> bool {
+        matches!(self, MaybeSignal::Dynamic(_))
+    }
+}
+
+impl<T: 'static + Clone + Default> Default for MaybeSignal<T> {
+    fn default() -> Self {
+        MaybeSignal::Static(T::default())
+    }
+}
+
+impl<T: 'static + Clone> From<T> for MaybeSignal<T> {
+    fn from(value: T) -> Self {
+        MaybeSignal::Static(value)
+    }
+}
+
+impl<T: 'static + Clone> From<Signal<T>> for MaybeSignal<T> {
+    fn from(signal: Signal<T>) -> Self {
+        MaybeSignal::Dynamic(signal)
+    }
+}
+
+impl From<&str> for MaybeSignal<String> {
+    fn from(s: &str) -> Self {
+        MaybeSignal::Static(s.to_owned())
+    }
+}
+
+impl<T: Clone + fmt::Display + 'static> fmt::Display for MaybeSignal<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.get().fmt(f)
+    }
+}
+
 /// Register an effect that runs whenever any of the accessed signals change.
 pub fn effect<F: Fn() + 'static>(f: F) -> usize {
     // Assign a unique id to this effect
@@ -496,4 +547,23 @@ mod tests {
         s.set("Bar".to_owned());
         assert_eq!(upper.get(), "BAR");
     }
+
+    #[test]
+    fn maybe_signal_static_and_dynamic() {
+        let fixed = MaybeSignal::from(5);
+        assert_eq!(fixed.get(), 5);
+        assert!(!fixed.is_reactive());
+
+        let s = signal!(1);
+        let dynamic: MaybeSignal<i32> = s.clone().into();
+        let doubled = derive!(dynamic, { dynamic.get() * 2 });
+        assert!(dynamic.is_reactive());
+        assert_eq!(doubled.get(), 2);
+
+        s.set(4);
+        assert_eq!(doubled.get(), 8);
+
+        let label: MaybeSignal<String> = "label".into();
+        assert_eq!(label.to_string(), "label");
+    }
 }
@@ -0,0 +1,43 @@
+#![allow(missing_docs)]
+
+use apex::prelude::*;
+
+/// Renders its children, or `fallback` if rendering or hydrating them panics. A fallback
+/// rendered by the server is hydrated by the client as well.
+#[component]
+pub fn error_boundary(#[prop] fallback: ErrorFallback) {
+    let boundary = apex::error::next_boundary_id();
+
+    #[cfg(not(target_arch = "wasm32"))]
+    let template = apex::error::render_or_fallback(
+        &boundary,
+        data.clone(),
+        || {
+            let mut buffer = String::new();
+
+            if let Some(render_children) = &render_children {
+                render_children(&mut buffer, data.clone());
+            }
+
+            buffer
+        },
+        |error| fallback.build(error).render_any(data.clone()),
+    );
+
+    #[cfg(target_arch = "wasm32")]
+    let template =
+        move |state: std::rc::Rc<std::cell::RefCell<apex::apex_router::client_router::State>>| {
+            apex::error::hydrate_or_fallback(
+                &boundary,
+                state,
+                |state| {
+                    if let Some(hydrate_children) = &hydrate_children {
+                        hydrate_children(state);
+                    }
+                },
+                |error, state| fallback.build(error).hydrate_any()(state),
+            );
+        };
+
+    template
+}
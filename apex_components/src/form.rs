@@ -0,0 +1,77 @@
+#![allow(missing_docs)]
+
+use apex::prelude::*;
+use apex::wasm_bindgen;
+use apex::web_sys;
+
+/// Form posted to the `#[route_action]` of the route at `action`. With the client router
+/// running, it's submitted with `fetch` and the page updates without reloading, otherwise
+/// the browser submits it. Forms with a file input or a `multipart/form-data` enctype are
+/// always submitted by the browser.
+#[component]
+pub fn form(#[prop] action: String, #[attrs] attrs: HtmlAttributes) {
+    // The router submits to the path of the app, the browser to its path on the site
+    let site_action = with_base_path(&action);
+
+    let handle_submit = action!(action @ web_sys::Event => |event| {
+        let Some(form) = event
+            .target()
+            .and_then(|target| target.dyn_into::<web_sys::HtmlFormElement>().ok())
+        else {
+            return;
+        };
+
+        // The body is sent url-encoded, files and multipart forms are left to the browser
+        let has_file_input = form
+            .query_selector("input[type=file]")
+            .is_ok_and(|input| input.is_some());
+
+        if has_file_input || form.enctype().eq_ignore_ascii_case("multipart/form-data") {
+            return;
+        }
+
+        let Ok(form_data) = web_sys::FormData::new_with_form(&form) else {
+            return;
+        };
+
+        let Ok(body) = web_sys::UrlSearchParams::new_with_str_sequence_sequence(&form_data) else {
+            return;
+        };
+
+        event.prevent_default();
+
+        let detail = apex::js_sys::Object::new();
+        let _ = apex::js_sys::Reflect::set(&detail, &"action".into(), &action.as_str().into());
+        let _ = apex::js_sys::Reflect::set(&detail, &"body".into(), &body.to_string().into());
+        let _ = apex::js_sys::Reflect::set(&detail, &"form".into(), &form);
+
+        let event_init = web_sys::CustomEventInit::new();
+        event_init.set_detail(&wasm_bindgen::JsValue::from(detail));
+
+        // Without the event, or the document to dispatch it to, the browser submits the form
+        let Ok(custom_event) =
+            web_sys::CustomEvent::new_with_event_init_dict("apex:submit", &event_init)
+        else {
+            web_sys::console::error_1(&"Failed to create the apex:submit event".into());
+            let _ = form.submit();
+            return;
+        };
+
+        let Some(document) = web_sys::window().and_then(|window| window.document()) else {
+            web_sys::console::error_1(&"Document not found".into());
+            let _ = form.submit();
+            return;
+        };
+
+        if let Err(error) = document.dispatch_event(&custom_event) {
+            web_sys::console::error_1(&error);
+            let _ = form.submit();
+        }
+    });
+
+    tmpl! {
+        <form {..attrs} method="post" action={site_action} onsubmit={handle_submit}>
+            <#slot />
+        </form>
+    }
+}
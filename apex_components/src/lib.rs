@@ -1,5 +1,9 @@
 #![allow(missing_docs)]
 
+mod error_boundary;
+mod form;
 mod link;
 
+pub use error_boundary::*;
+pub use form::*;
 pub use link::*;
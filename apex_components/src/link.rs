@@ -5,7 +5,10 @@ use apex::wasm_bindgen;
 use apex::web_sys;
 
 #[component]
-pub fn link(#[prop] href: String, #[prop] text: String) {
+pub fn link(#[prop] href: String, #[prop] text: String, #[attrs] attrs: HtmlAttributes) {
+    // The router navigates to the path of the app, the browser to its path on the site
+    let site_href = with_base_path(&href);
+
     let handle_click = action!(href @ web_sys::MouseEvent => |event| {
             event.prevent_default();
             let detail = wasm_bindgen::JsValue::from_str(&href);
@@ -28,6 +31,6 @@ pub fn link(#[prop] href: String, #[prop] text: String) {
     });
 
     tmpl! {
-        <a href={href} onclick={handle_click}>{text}</a>
+        <a {..attrs} href={site_href} onclick={handle_click}>{text}</a>
     }
 }
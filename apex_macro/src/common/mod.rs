@@ -1,3 +1,5 @@
+mod slot_name_chars;
 mod to_pascal_case;
 
+pub(crate) use slot_name_chars::slot_name_chars;
 pub(crate) use to_pascal_case::to_pascal_case;
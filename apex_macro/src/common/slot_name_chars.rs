@@ -0,0 +1,23 @@
+use proc_macro2::{Span, TokenStream};
+use quote::quote_spanned;
+
+/// Type of a slot name for the slot traits of component builders, the tuple of its
+/// characters: `header` is `(SlotChar<'h'>, SlotChar<'e'>, ...)`
+pub(crate) fn slot_name_chars(name: &str, span: Span) -> TokenStream {
+    let chars = name.chars();
+
+    quote_spanned! {span=> (#(apex::helpers::SlotChar<#chars>,)*) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_slot_name_chars() {
+        assert_eq!(
+            slot_name_chars("row", Span::call_site()).to_string(),
+            "(apex :: helpers :: SlotChar < 'r' > , apex :: helpers :: SlotChar < 'o' > , apex :: helpers :: SlotChar < 'w' > ,)"
+        );
+    }
+}
@@ -1,29 +1,85 @@
-use proc_macro2::TokenStream;
-use quote::quote;
-use syn::ItemFn;
+use proc_macro2::{Span, TokenStream};
+use quote::{ToTokens, format_ident, quote, quote_spanned};
+use syn::{GenericParam, Generics, Ident, ItemFn};
 
 use crate::{
-    common::to_pascal_case,
-    component::{parse_props::parse_props, validate_component_function},
+    common::{slot_name_chars, to_pascal_case},
+    component::{
+        ComponentArgs,
+        has_slot_macro::{find_has_slot_calls, has_slot_ident},
+        parse_props::{parse_attrs_param, parse_props},
+        parse_slots::parse_slots,
+        template_slots::find_template_slots,
+        validate_component_function,
+    },
+    tmpl::{TmplLints, parse_tmpl_file},
 };
 
 /// Generate a component from a function
-pub(crate) fn generate_component(input: ItemFn) -> TokenStream {
+pub(crate) fn generate_component(args: ComponentArgs, input: ItemFn) -> syn::Result<TokenStream> {
     // Validate the function signature
-    validate_component_function(&input);
+    validate_component_function(&input)?;
 
     // Extract function details
     let fn_name = &input.sig.ident;
-    let fn_body = &input.block;
+    let fn_body = match &args.template {
+        Some(template) => template_body(&input.block, template)?,
+        None => input.block.to_token_stream(),
+    };
     let vis = &input.vis;
+    let (impl_generics, ty_generics, where_clause) = input.sig.generics.split_for_impl();
+    let generics = &input.sig.generics;
+    let phantom_type = phantom_type(generics);
 
     // Parse props and slots from function parameters
-    let props = parse_props(&input);
+    let mut props = parse_props(&input);
+    props.extend(parse_slots(&input));
+    let attrs_param = parse_attrs_param(&input);
 
     // Convert function name to PascalCase for the struct
     let struct_name = syn::Ident::new(&to_pascal_case(&fn_name.to_string()), fn_name.span());
     let builder_name = syn::Ident::new(&format!("{struct_name}Builder"), fn_name.span());
 
+    // Each required prop gets a type parameter on the builder tracking whether it has been
+    // set, so `build()` only exists once all of them are
+    let required_props = props
+        .iter()
+        .filter(|prop| prop.default.is_none())
+        .map(|prop| {
+            let state = format_ident!(
+                "__{}Prop",
+                to_pascal_case(&prop.name.ident.to_string()),
+                span = prop.name.ident.span()
+            );
+
+            (&prop.name.ident, state, &prop.ty)
+        })
+        .collect::<Vec<_>>();
+    let states = required_props
+        .iter()
+        .map(|(_, state, _)| state)
+        .collect::<Vec<_>>();
+
+    let mut builder_generics = generics.clone();
+    builder_generics.params.extend(
+        states
+            .iter()
+            .map(|state| GenericParam::Type((*state).clone().into())),
+    );
+    let (builder_impl_generics, builder_ty_generics, _) = builder_generics.split_for_impl();
+
+    let unset_builder_ty = builder_type(&builder_name, generics, &states, |_| {
+        quote! { apex::helpers::PropUnset }
+    });
+
+    // Bounds on `build()` rather than the impl, so a missing prop is reported with the
+    // message of `PropIsSet` and a note pointing at the prop
+    let build_bounds = required_props.iter().map(|(name, state, ty)| {
+        quote_spanned! {name.span()=>
+            #state: apex::helpers::PropIsSet<#ty>
+        }
+    });
+
     // Generate struct fields from props and slots
     let struct_fields = props
         .iter()
@@ -36,6 +92,8 @@ pub(crate) fn generate_component(input: ItemFn) -> TokenStream {
             }
         })
         .chain(std::iter::once(quote! {
+            _phantom: std::marker::PhantomData<#phantom_type>,
+            pub spread_attrs: apex::helpers::HtmlAttributes,
             pub render_children: Option<std::rc::Rc<Box<dyn for<'a> Fn(&'a mut String, std::rc::Rc<std::cell::RefCell<std::collections::HashMap<String, serde_json::Value>>>) + 'static>>>,
             pub named_slots: Option<std::collections::HashMap<String, std::rc::Rc<Box<dyn for<'a> Fn(&'a mut String, std::rc::Rc<std::cell::RefCell<std::collections::HashMap<String, serde_json::Value>>>) + 'static>>>>,
             pub hydrate_children: Option<std::rc::Rc<Box<
@@ -50,18 +108,22 @@ pub(crate) fn generate_component(input: ItemFn) -> TokenStream {
             >>>>,
         }));
 
-    // Generate builder struct fields (Option for all)
+    // Generate builder struct fields, required props are held by their state and the others
+    // are `None` until set
     let builder_fields = props
         .iter()
         .map(|prop| {
             let name = &prop.name;
             let ty = &prop.ty;
 
-            quote! {
-                #name: Option<#ty>
+            match required_state(&required_props, &name.ident) {
+                Some(state) => quote! { #name: #state },
+                None => quote! { #name: Option<#ty> },
             }
         })
         .chain(std::iter::once(quote! {
+            _phantom: std::marker::PhantomData<#phantom_type>,
+            spread_attrs: apex::helpers::HtmlAttributes,
             render_children: Option<std::rc::Rc<Box<dyn for<'a> Fn(&'a mut String, std::rc::Rc<std::cell::RefCell<std::collections::HashMap<String, serde_json::Value>>>) + 'static>>>,
             named_slots: Option<std::collections::HashMap<String, std::rc::Rc<Box<dyn for<'a> Fn(&'a mut String, std::rc::Rc<std::cell::RefCell<std::collections::HashMap<String, serde_json::Value>>>) + 'static>>>>,
             hydrate_children: Option<std::rc::Rc<Box<
@@ -76,18 +138,84 @@ pub(crate) fn generate_component(input: ItemFn) -> TokenStream {
             >>>>,
         }));
 
-    // Generate builder setter methods
+    // Generate builder setter methods, setting a required prop moves the builder to the state
+    // where it's set
     let builder_setters = props.iter().map(|prop| {
         let name = &prop.name;
         let ty = &prop.ty;
 
+        let (value_ty, value) = if prop.into {
+            (quote! { impl Into<#ty> }, quote! { value.into() })
+        } else {
+            (quote! { #ty }, quote! { value })
+        };
+
+        // Literal attributes in tmpl! are strings, converted here where the prop type is known
+        let literal_setter_name = format_ident!("__literal_{}", name.ident);
+        let literal_value = if prop.into {
+            quote! { value }
+        } else {
+            quote! { value.into() }
+        };
+        let literal_setter = |return_ty: TokenStream| {
+            quote! {
+                #[doc(hidden)]
+                pub fn #literal_setter_name(self, value: impl Into<#ty>) -> #return_ty {
+                    self.#name(#literal_value)
+                }
+            }
+        };
+
+        let Some(set_state) = required_state(&required_props, &name.ident) else {
+            let literal_setter = literal_setter(quote! { Self });
+
+            return quote! {
+                pub fn #name(mut self, value: #value_ty) -> Self {
+                    self.#name = Some(#value);
+                    self
+                }
+
+                #literal_setter
+            };
+        };
+
+        let set_builder_ty = builder_type(&builder_name, generics, &states, |state| {
+            if state == set_state {
+                quote! { apex::helpers::PropSet<#ty> }
+            } else {
+                quote! { #state }
+            }
+        });
+        let other_props = props
+            .iter()
+            .map(|prop| &prop.name)
+            .filter(|other| other.ident != name.ident);
+
+        let literal_setter = literal_setter(set_builder_ty.clone());
+
         quote! {
-            pub fn #name(mut self, value: #ty) -> Self {
-                self.#name = Some(value);
-                self
+            pub fn #name(self, value: #value_ty) -> #set_builder_ty {
+                #builder_name {
+                    #name: apex::helpers::PropSet(#value),
+                    #(#other_props: self.#other_props,)*
+                    _phantom: self._phantom,
+                    spread_attrs: self.spread_attrs,
+                    render_children: self.render_children,
+                    named_slots: self.named_slots,
+                    hydrate_children: self.hydrate_children,
+                    hydrate_named_slots: self.hydrate_named_slots,
+                }
             }
+
+            #literal_setter
         }
      }).chain(std::iter::once(quote! {
+         /// Attributes spread onto the component with `{..attrs}`, merged with earlier spreads
+         pub fn spread_attrs(mut self, value: apex::helpers::HtmlAttributes) -> Self {
+             self.spread_attrs.merge(value);
+             self
+         }
+     })).chain(std::iter::once(quote! {
          pub fn render_children(mut self, value: Box<dyn for<'a> Fn(&'a mut String, std::rc::Rc<std::cell::RefCell<std::collections::HashMap<String, serde_json::Value>>>) + 'static>) -> Self {
              self.render_children = Some(std::rc::Rc::new(value));
              self
@@ -125,9 +253,15 @@ pub(crate) fn generate_component(input: ItemFn) -> TokenStream {
         .iter()
         .map(|prop| {
             let name = &prop.name;
-            quote! { #name: None }
+
+            match required_state(&required_props, &name.ident) {
+                Some(_) => quote! { #name: apex::helpers::PropUnset },
+                None => quote! { #name: None },
+            }
         })
         .chain(std::iter::once(quote! {
+            _phantom: std::marker::PhantomData,
+            spread_attrs: apex::helpers::HtmlAttributes::new(),
             render_children: None,
             named_slots: None,
             hydrate_children: None,
@@ -144,89 +278,298 @@ pub(crate) fn generate_component(input: ItemFn) -> TokenStream {
                     #name: self.#name.unwrap_or_else(|| #default)
                 }
             } else {
-                let name_str = name.ident.to_string();
                 quote! {
-                    #name: self.#name.expect(&format!("Required prop '{}' not set", #name_str))
+                    #name: apex::helpers::PropIsSet::into_prop(self.#name)
                 }
             }
         })
         .chain(std::iter::once(quote! {
-            render_children: self.render_children.clone(),
-            named_slots: self.named_slots.clone(),
-            hydrate_children: self.hydrate_children.clone(),
-            hydrate_named_slots: self.hydrate_named_slots.clone(),
+            _phantom: std::marker::PhantomData,
+            spread_attrs: self.spread_attrs,
+            render_children: self.render_children,
+            named_slots: self.named_slots,
+            hydrate_children: self.hydrate_children,
+            hydrate_named_slots: self.hydrate_named_slots,
         }));
 
-    let prop_bindings = props
-        .iter()
-        .map(|prop| {
+    // Slots given in tmpl! are checked with the slot traits, implemented for the name of
+    // each slot. Any named slot is accepted when the templates of the component aren't known.
+    let mut slot_generics = builder_generics.clone();
+    slot_generics
+        .params
+        .push(GenericParam::Type(format_ident!("__SlotName").into()));
+    let (slot_impl_generics, _, _) = slot_generics.split_for_impl();
+
+    let named_slot_impls = match find_template_slots(input.block.to_token_stream(), args.template.as_ref()) {
+        Some(slot_names) => slot_names
+            .iter()
+            .map(|slot_name| {
+                let chars = slot_name_chars(slot_name, Span::call_site());
+
+                quote! {
+                    impl #slot_impl_generics apex::helpers::HasNamedSlot<#chars, __SlotName> for #builder_name #builder_ty_generics #where_clause {}
+                }
+            })
+            .collect::<Vec<_>>(),
+        None => {
+            let mut any_slot_generics = slot_generics.clone();
+            any_slot_generics
+                .params
+                .push(GenericParam::Type(format_ident!("__SlotChars").into()));
+            let (any_slot_impl_generics, _, _) = any_slot_generics.split_for_impl();
+
+            vec![quote! {
+                impl #any_slot_impl_generics apex::helpers::HasNamedSlot<__SlotChars, __SlotName> for #builder_name #builder_ty_generics #where_clause {}
+            }]
+        }
+    };
+
+    // Typed slots are set with the closures of the slot content, so tmpl! doesn't have to
+    // name the type of the value bound with `let:`
+    let typed_slot_impls = props.iter().filter_map(|prop| {
+        let slot_ty = prop.slot.as_ref()?;
+        let name = &prop.name;
+        let chars = slot_name_chars(&name.ident.to_string(), Span::call_site());
+
+        let output = match required_state(&required_props, &name.ident) {
+            Some(set_state) => {
+                let ty = &prop.ty;
+
+                builder_type(&builder_name, generics, &states, |state| {
+                    if state == set_state {
+                        quote! { apex::helpers::PropSet<#ty> }
+                    } else {
+                        quote! { #state }
+                    }
+                })
+            }
+            None => quote! { Self },
+        };
+
+        Some(quote! {
+            impl #slot_impl_generics apex::helpers::SetTypedSlot<#chars, __SlotName> for #builder_name #builder_ty_generics #where_clause {
+                type Value = #slot_ty;
+                type Output = #output;
+
+                fn set_typed_slot(
+                    self,
+                    render: impl Fn(&mut String, apex::helpers::RenderData, #slot_ty) + 'static,
+                    hydrate: impl Fn(std::rc::Rc<std::cell::RefCell<apex_router::client_router::State>>, #slot_ty) + 'static,
+                ) -> Self::Output {
+                    self.#name(apex::helpers::Slot::new(render, hydrate))
+                }
+            }
+        })
+    });
+
+    // Whether the slots asked about with has_slot! were given, known before the slots are
+    // moved out and the same whether rendering or hydrating
+    let has_slot_bindings = find_has_slot_calls(fn_body.clone())
+        .into_iter()
+        .map(|slot_name| {
+            let ident = has_slot_ident(slot_name.as_deref());
+            let typed_slot = props.iter().find(|prop| {
+                prop.slot.is_some() && slot_name.as_deref() == Some(&prop.name.ident.to_string())
+            });
+
+            let given = match (slot_name, typed_slot) {
+                (_, Some(prop)) => {
+                    let name = &prop.name;
+                    quote! { !self.#name.is_empty() }
+                }
+                (Some(slot_name), None) => quote! {
+                    self.named_slots.as_ref().is_some_and(|slots| slots.contains_key(#slot_name))
+                        || self.hydrate_named_slots.as_ref().is_some_and(|slots| slots.contains_key(#slot_name))
+                },
+                (None, None) => quote! {
+                    self.render_children.is_some() || self.hydrate_children.is_some()
+                },
+            };
+
+            quote! {
+                let #ident: bool = #given;
+            }
+        });
+
+    // Props are moved out of the component, so they don't have to be Clone
+    let prop_bindings = has_slot_bindings
+        .chain(props.iter().map(|prop| {
             let name = &prop.name;
             quote! {
-                let #name = self.#name.clone();
+                let #name = self.#name;
             }
-        })
+        }))
+        .chain(attrs_param.iter().map(|name| {
+            quote! {
+                let #name = self.spread_attrs;
+            }
+        }))
         .chain(std::iter::once(quote! {
             #[cfg(not(target_arch = "wasm32"))]
-            let render_children = self.render_children.clone();
+            let render_children = self.render_children;
             #[cfg(not(target_arch = "wasm32"))]
-            let named_slots = self.named_slots.clone();
+            let named_slots = self.named_slots;
 
             #[cfg(target_arch = "wasm32")]
-            let hydrate_children = self.hydrate_children.clone();
+            let hydrate_children = self.hydrate_children;
             #[cfg(target_arch = "wasm32")]
-            let hydrate_named_slots = self.hydrate_named_slots.clone();
+            let hydrate_named_slots = self.hydrate_named_slots;
         }))
         .collect::<Vec<_>>();
 
+    // Components that can be built without props can be picked at runtime with
+    // `{#component}` without writing a factory
+    let dynamic_constructor =
+        (required_props.is_empty() && generics.params.is_empty()).then(|| {
+            quote! {
+                impl #struct_name {
+                    pub fn dynamic() -> apex::helpers::DynamicComponent {
+                        apex::helpers::DynamicComponent::new(|slots: apex::helpers::DynamicSlots| {
+                            let mut builder = Self::builder();
+                            builder.render_children = slots.render_children;
+                            builder.named_slots = slots.named_slots;
+                            builder.hydrate_children = slots.hydrate_children;
+                            builder.hydrate_named_slots = slots.hydrate_named_slots;
+                            builder.build()
+                        })
+                    }
+                }
+            }
+        });
+
     // Generate the component struct and impl
     let output = quote! {
-        #vis struct #struct_name {
+        #vis struct #struct_name #generics #where_clause {
             #(#struct_fields),*
         }
 
-        pub struct #builder_name {
+        #vis struct #builder_name #builder_generics #where_clause {
             #(#builder_fields),*
         }
 
-        impl #builder_name {
+        impl #impl_generics #unset_builder_ty #where_clause {
             pub fn new() -> Self {
                 Self {
                     #(#builder_default_fields),*
                 }
             }
+        }
 
+        impl #builder_impl_generics #builder_name #builder_ty_generics #where_clause {
             #(#builder_setters)*
 
-            pub fn build(self) -> #struct_name {
+            pub fn build(self) -> #struct_name #ty_generics
+            where
+                #(#build_bounds,)*
+            {
                 #struct_name {
                     #(#build_struct_fields),*
                 }
             }
         }
 
-        impl #struct_name {
-            pub fn builder() -> #builder_name {
+        #(#named_slot_impls)*
+
+        #(#typed_slot_impls)*
+
+        impl #impl_generics #struct_name #ty_generics #where_clause {
+            pub fn builder() -> #unset_builder_ty {
                 #builder_name::new()
             }
         }
 
         #[cfg(not(target_arch = "wasm32"))]
-        impl #struct_name {
-            pub fn render(&self, data: std::rc::Rc<std::cell::RefCell<std::collections::HashMap<String, serde_json::Value>>>) -> String {
+        impl #impl_generics #struct_name #ty_generics #where_clause {
+            pub fn render(self, data: std::rc::Rc<std::cell::RefCell<std::collections::HashMap<String, serde_json::Value>>>) -> String {
                 #(#prop_bindings)*
                 #fn_body
             }
         }
 
         #[cfg(target_arch = "wasm32")]
-        impl #struct_name {
-            pub fn hydrate(&self) -> Box<dyn FnOnce(std::rc::Rc<std::cell::RefCell<apex_router::client_router::State>>)> {
+        impl #impl_generics #struct_name #ty_generics #where_clause {
+            pub fn hydrate(self) -> Box<dyn FnOnce(std::rc::Rc<std::cell::RefCell<apex_router::client_router::State>>)> {
                 #(#prop_bindings)*
                 let template_fn = #fn_body;
                 Box::new(template_fn)
             }
         }
+
+        impl #impl_generics apex::helpers::AnyComponent for #struct_name #ty_generics #where_clause {
+            #[cfg(not(target_arch = "wasm32"))]
+            fn render_any(self: Box<Self>, data: apex::helpers::RenderData) -> String {
+                (*self).render(data)
+            }
+
+            #[cfg(target_arch = "wasm32")]
+            fn hydrate_any(self: Box<Self>) -> Box<dyn FnOnce(std::rc::Rc<std::cell::RefCell<apex_router::client_router::State>>)> {
+                (*self).hydrate()
+            }
+        }
+
+        #dynamic_constructor
     };
 
-    output
+    Ok(output)
+}
+
+/// Type the component and its builder hold in a `PhantomData`, so that generic
+/// parameters only used by slots or the body are still considered used
+fn phantom_type(generics: &syn::Generics) -> TokenStream {
+    let lifetimes = generics.lifetimes().map(|param| &param.lifetime);
+    let types = generics.type_params().map(|param| &param.ident);
+
+    quote! { (#(&#lifetimes (),)* #(fn() -> #types,)*) }
+}
+
+/// Type parameter tracking whether the required prop `name` is set, `None` for props with
+/// a default
+fn required_state<'a>(
+    required_props: &'a [(&Ident, Ident, &syn::Type)],
+    name: &Ident,
+) -> Option<&'a Ident> {
+    required_props
+        .iter()
+        .find(|(required, _, _)| *required == name)
+        .map(|(_, state, _)| state)
+}
+
+/// Builder type with the generics of the component followed by the given state of each
+/// required prop
+fn builder_type(
+    builder_name: &Ident,
+    generics: &Generics,
+    states: &[&Ident],
+    state_type: impl Fn(&Ident) -> TokenStream,
+) -> TokenStream {
+    let params = generics.params.iter().map(|param| match param {
+        GenericParam::Lifetime(param) => param.lifetime.to_token_stream(),
+        GenericParam::Type(param) => param.ident.to_token_stream(),
+        GenericParam::Const(param) => param.ident.to_token_stream(),
+    });
+
+    let states = states.iter().map(|state| state_type(state));
+
+    quote! { #builder_name<#(#params,)* #(#states,)*> }
+}
+
+/// Function body followed by the template file, which becomes the value the component
+/// renders
+fn template_body(block: &syn::Block, template: &syn::LitStr) -> syn::Result<TokenStream> {
+    if let Some(syn::Stmt::Expr(expr, None)) = block.stmts.last() {
+        return Err(syn::Error::new_spanned(
+            expr,
+            "components with a `template` render the template file, remove the trailing expression",
+        ));
+    }
+
+    let stmts = &block.stmts;
+    let template = parse_tmpl_file(template, TmplLints::default())?;
+
+    Ok(quote! {
+        {
+            #(#stmts)*
+            #template
+        }
+    })
 }
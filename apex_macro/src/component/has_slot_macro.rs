@@ -0,0 +1,119 @@
+use proc_macro::TokenStream;
+use proc_macro2::{Ident, Span, TokenStream as TokenStream2, TokenTree};
+use quote::quote;
+use syn::{
+    LitStr,
+    parse::{Parse, ParseStream},
+};
+
+/// Parses the has_slot! macro input
+/// Expected format: has_slot!() for the children, has_slot!("name") for a named slot
+struct HasSlotInput {
+    slot_name: Option<LitStr>,
+}
+
+impl Parse for HasSlotInput {
+    fn parse(input: ParseStream<'_>) -> syn::Result<Self> {
+        Ok(HasSlotInput {
+            slot_name: input.parse()?,
+        })
+    }
+}
+
+/// The component binds whether each slot asked about was given before rendering or
+/// hydrating, has_slot! reads that binding
+pub(crate) fn generate_has_slot_macro(input: TokenStream) -> TokenStream2 {
+    let input = match syn::parse::<HasSlotInput>(input) {
+        Ok(input) => input,
+        Err(error) => return error.into_compile_error(),
+    };
+
+    let ident = has_slot_ident(input.slot_name.as_ref().map(LitStr::value).as_deref());
+
+    quote! { #ident }
+}
+
+/// Local holding whether the slot was given, `None` being the children
+pub(crate) fn has_slot_ident(slot_name: Option<&str>) -> Ident {
+    let name = match slot_name {
+        Some(slot_name) => format!(
+            "__has_slot_{}",
+            slot_name
+                .chars()
+                .map(|ch| if ch.is_ascii_alphanumeric() { ch } else { '_' })
+                .collect::<String>()
+        ),
+        None => "__has_children".to_owned(),
+    };
+
+    Ident::new(&name, Span::call_site())
+}
+
+/// Slots asked about with has_slot! anywhere in the component body, templates included
+pub(crate) fn find_has_slot_calls(tokens: TokenStream2) -> Vec<Option<String>> {
+    let mut slot_names = Vec::new();
+    collect_has_slot_calls(tokens, &mut slot_names);
+
+    slot_names
+}
+
+fn collect_has_slot_calls(tokens: TokenStream2, slot_names: &mut Vec<Option<String>>) {
+    let tokens = tokens.into_iter().collect::<Vec<_>>();
+
+    for (index, token) in tokens.iter().enumerate() {
+        let TokenTree::Group(group) = token else {
+            continue;
+        };
+
+        let is_has_slot_call = index >= 2
+            && matches!(
+                (&tokens[index - 2], &tokens[index - 1]),
+                (TokenTree::Ident(ident), TokenTree::Punct(punct))
+                    if ident == "has_slot" && punct.as_char() == '!'
+            );
+
+        if !is_has_slot_call {
+            collect_has_slot_calls(group.stream(), slot_names);
+            continue;
+        }
+
+        let slot_name = syn::parse2::<HasSlotInput>(group.stream())
+            .ok()
+            .map(|input| input.slot_name.map(|slot_name| slot_name.value()));
+
+        if let Some(slot_name) = slot_name
+            && !slot_names.contains(&slot_name)
+        {
+            slot_names.push(slot_name);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_has_slot_calls_in_nested_tokens() {
+        let tokens = r#"
+            let bordered = has_slot!("header");
+            tmpl! {
+                {#if has_slot!()}<main><#slot /></main>{/if}
+                {#if has_slot!("header") && has_slot!("footer")}<hr />{/if}
+            }
+        "#
+        .parse()
+        .unwrap();
+
+        assert_eq!(
+            find_has_slot_calls(tokens),
+            vec![Some("header".to_owned()), None, Some("footer".to_owned())]
+        );
+    }
+
+    #[test]
+    fn has_slot_ident_is_a_valid_identifier() {
+        assert_eq!(has_slot_ident(Some("side-bar")), "__has_slot_side_bar");
+        assert_eq!(has_slot_ident(None), "__has_children");
+    }
+}
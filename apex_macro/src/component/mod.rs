@@ -1,6 +1,12 @@
 mod generate_component;
+mod has_slot_macro;
+mod parse_component_args;
 mod parse_props;
+mod parse_slots;
+mod template_slots;
 mod validate_component_function;
 
 pub(crate) use generate_component::generate_component;
+pub(crate) use has_slot_macro::generate_has_slot_macro;
+pub(crate) use parse_component_args::{ComponentArgs, parse_component_args};
 use validate_component_function::validate_component_function;
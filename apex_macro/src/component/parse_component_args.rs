@@ -0,0 +1,89 @@
+use proc_macro::TokenStream;
+use syn::{
+    LitStr, Meta, Result,
+    parse::{Parse, ParseStream},
+};
+
+/// Arguments parsed from the #[component(...)] macro
+#[derive(Debug, Default)]
+pub(crate) struct ComponentArgs {
+    /// Template file rendered after the function body, relative to `CARGO_MANIFEST_DIR`
+    pub template: Option<LitStr>,
+}
+
+impl Parse for ComponentArgs {
+    fn parse(input: ParseStream<'_>) -> Result<Self> {
+        let mut component_args = ComponentArgs::default();
+
+        let parsed_args =
+            syn::punctuated::Punctuated::<Meta, syn::Token![,]>::parse_terminated(input)?;
+
+        for meta in parsed_args {
+            let Meta::NameValue(name_value) = meta else {
+                return Err(syn::Error::new_spanned(
+                    meta,
+                    "expected `key = value`, e.g. `template = \"card.html\"`",
+                ));
+            };
+
+            if name_value.path.is_ident("template") {
+                let syn::Expr::Lit(syn::ExprLit {
+                    lit: syn::Lit::Str(s),
+                    ..
+                }) = &name_value.value
+                else {
+                    return Err(syn::Error::new_spanned(
+                        &name_value.value,
+                        "expected a string literal, e.g. `template = \"card.html\"`",
+                    ));
+                };
+
+                component_args.template = Some(s.clone());
+            } else {
+                let key = name_value
+                    .path
+                    .get_ident()
+                    .map(ToString::to_string)
+                    .unwrap_or_else(|| "?".to_owned());
+
+                return Err(syn::Error::new_spanned(
+                    &name_value.path,
+                    format!("unknown component argument `{key}`, expected: template"),
+                ));
+            }
+        }
+
+        Ok(component_args)
+    }
+}
+
+/// Parse arguments from the component macro attribute
+pub(crate) fn parse_component_args(args: TokenStream) -> Result<ComponentArgs> {
+    if args.is_empty() {
+        return Ok(ComponentArgs::default());
+    }
+
+    syn::parse(args)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_component_args() {
+        let args: ComponentArgs = syn::parse_str(r#"template = "card.html""#).unwrap();
+
+        assert_eq!(args.template.unwrap().value(), "card.html");
+    }
+
+    #[test]
+    fn test_unknown_component_argument() {
+        let error = syn::parse_str::<ComponentArgs>(r#"templat = "card.html""#).unwrap_err();
+
+        assert_eq!(
+            error.to_string(),
+            "unknown component argument `templat`, expected: template"
+        );
+    }
+}
@@ -5,6 +5,10 @@ pub(crate) struct ComponentProp {
     pub name: PatIdent,
     pub ty: Type,
     pub default: Option<Expr>,
+    /// `#[prop(into)]`, the setter takes anything converting into the prop type
+    pub into: bool,
+    /// Type of the value a `#[slot]` of type `Slot<T>` is rendered with
+    pub slot: Option<Type>,
 }
 
 /// Parse props from function parameters that have #[prop] attribute
@@ -16,6 +20,7 @@ pub(crate) fn parse_props(input: &ItemFn) -> Vec<ComponentProp> {
             // Check if parameter has #[prop] attribute
             let mut has_prop_attr = false;
             let mut default_value = None;
+            let mut into = false;
 
             for attr in &pat_type.attrs {
                 let attr_name = attr
@@ -36,7 +41,9 @@ pub(crate) fn parse_props(input: &ItemFn) -> Vec<ComponentProp> {
                                 // Shorthand: #[prop(default)] -> Default::default(),
                                 // but for EventHandler<_> use apex::helpers::noop_event()
                                 syn::Meta::Path(path) => {
-                                    if path.is_ident("default") {
+                                    if path.is_ident("into") {
+                                        into = true;
+                                    } else if path.is_ident("default") {
                                         let expr_str = match &*pat_type.ty {
                                             syn::Type::Path(type_path) => {
                                                 let last_segment = type_path.path.segments.last();
@@ -89,10 +96,17 @@ pub(crate) fn parse_props(input: &ItemFn) -> Vec<ComponentProp> {
             if has_prop_attr {
                 // Extract the parameter name and type
                 if let Pat::Ident(pat_ident) = &*pat_type.pat {
+                    // Option props are optional, default to None and accept the inner value
+                    let is_option = is_option_type(&pat_type.ty);
+                    let default_value =
+                        default_value.or_else(|| is_option.then(|| syn::parse_quote!(None)));
+
                     props.push(ComponentProp {
                         name: pat_ident.clone(),
                         ty: (*pat_type.ty).clone(),
                         default: default_value,
+                        into: into || is_option,
+                        slot: None,
                     });
                 }
             }
@@ -101,3 +115,71 @@ pub(crate) fn parse_props(input: &ItemFn) -> Vec<ComponentProp> {
 
     props
 }
+
+/// Whether the prop is written `Option<T>`, `std::option::Option<T>` or
+/// `core::option::Option<T>`. Such props are optional: they default to `None` and their
+/// setter takes the inner value. Only the type as written is seen, so an other type named
+/// `Option` imported under that name would be taken for it.
+fn is_option_type(ty: &Type) -> bool {
+    let Type::Path(type_path) = ty else {
+        return false;
+    };
+
+    if type_path.qself.is_some() {
+        return false;
+    }
+
+    let segments = type_path
+        .path
+        .segments
+        .iter()
+        .map(|segment| segment.ident.to_string())
+        .collect::<Vec<_>>();
+    let is_option_path = match segments.as_slice() {
+        [option] => option == "Option" && type_path.path.leading_colon.is_none(),
+        [krate, module, option] => {
+            matches!(krate.as_str(), "std" | "core") && module == "option" && option == "Option"
+        }
+        _ => false,
+    };
+
+    is_option_path
+        && type_path.path.segments.last().is_some_and(|segment| {
+            matches!(
+                &segment.arguments,
+                syn::PathArguments::AngleBracketed(arguments) if arguments.args.len() == 1
+            )
+        })
+}
+
+/// Find the parameter marked with #[attrs] that receives attributes spread onto the component
+pub(crate) fn parse_attrs_param(input: &ItemFn) -> Option<PatIdent> {
+    input.sig.inputs.iter().find_map(|arg| match arg {
+        FnArg::Typed(pat_type)
+            if pat_type
+                .attrs
+                .iter()
+                .any(|attr| attr.path().is_ident("attrs")) =>
+        {
+            match &*pat_type.pat {
+                Pat::Ident(pat_ident) => Some(pat_ident.clone()),
+                _ => None,
+            }
+        }
+        _ => None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn option_types() {
+        assert!(is_option_type(&syn::parse_quote!(Option<u32>)));
+        assert!(is_option_type(&syn::parse_quote!(std::option::Option<u32>)));
+        assert!(!is_option_type(&syn::parse_quote!(my_crate::Option<u32>)));
+        assert!(!is_option_type(&syn::parse_quote!(Option)));
+        assert!(!is_option_type(&syn::parse_quote!(Vec<Option<u32>>)));
+    }
+}
@@ -1,13 +1,10 @@
-use syn::{Expr, FnArg, ItemFn, Pat, PatIdent};
+use syn::{Expr, FnArg, ItemFn, Pat, Type};
 
-/// A component slot extracted from function parameters
-pub(crate) struct ComponentSlot {
-    pub _name: PatIdent,
-    pub _default: Option<Expr>,
-}
+use crate::component::parse_props::ComponentProp;
 
-/// Parse slots from function parameters that have #[slot] attribute
-pub(crate) fn parse_slots(input: &ItemFn) -> Vec<ComponentSlot> {
+/// Parse typed slots from function parameters that have #[slot] attribute. They are set on
+/// the builder like props, so a slot without a default has to be given.
+pub(crate) fn parse_slots(input: &ItemFn) -> Vec<ComponentProp> {
     let mut slots = Vec::new();
 
     for arg in &input.sig.inputs {
@@ -30,6 +27,13 @@ pub(crate) fn parse_slots(input: &ItemFn) -> Vec<ComponentSlot> {
                         syn::punctuated::Punctuated::<syn::Meta, syn::Token![,]>::parse_terminated,
                     ) {
                         for meta in args {
+                            // Shorthand: #[slot(default)] -> an empty slot
+                            if let syn::Meta::Path(path) = &meta
+                                && path.is_ident("default")
+                            {
+                                default_value = Some(syn::parse_quote!(Default::default()));
+                            }
+
                             if let syn::Meta::NameValue(name_value) = meta {
                                 if name_value.path.is_ident("default") {
                                     if let syn::Expr::Lit(syn::ExprLit {
@@ -55,9 +59,12 @@ pub(crate) fn parse_slots(input: &ItemFn) -> Vec<ComponentSlot> {
             if has_slot_attr {
                 // Extract the parameter name and type
                 if let Pat::Ident(pat_ident) = &*pat_type.pat {
-                    slots.push(ComponentSlot {
-                        _name: pat_ident.clone(),
-                        _default: default_value,
+                    slots.push(ComponentProp {
+                        name: pat_ident.clone(),
+                        ty: (*pat_type.ty).clone(),
+                        default: default_value,
+                        into: false,
+                        slot: Some(slot_value_type(&pat_type.ty)),
                     });
                 }
             }
@@ -66,3 +73,25 @@ pub(crate) fn parse_slots(input: &ItemFn) -> Vec<ComponentSlot> {
 
     slots
 }
+
+/// `T` of `Slot<T>`, `()` when the slot isn't given a value
+fn slot_value_type(ty: &Type) -> Type {
+    let Type::Path(type_path) = ty else {
+        return syn::parse_quote!(());
+    };
+
+    type_path
+        .path
+        .segments
+        .last()
+        .and_then(|segment| match &segment.arguments {
+            syn::PathArguments::AngleBracketed(args) => {
+                args.args.iter().find_map(|arg| match arg {
+                    syn::GenericArgument::Type(ty) => Some(ty.clone()),
+                    _ => None,
+                })
+            }
+            _ => None,
+        })
+        .unwrap_or_else(|| syn::parse_quote!(()))
+}
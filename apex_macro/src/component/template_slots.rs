@@ -0,0 +1,88 @@
+use proc_macro2::{TokenStream, TokenTree};
+use syn::LitStr;
+
+use crate::tmpl::{tmpl_file_slot_names, tmpl_slot_names};
+
+/// Named slots rendered by the templates of a component, the `tmpl!`s of its body and its
+/// `template` file. `None` when the slots aren't known: the body has no `tmpl!`, e.g. it
+/// renders with an other macro, or a template doesn't parse, which `tmpl!` reports.
+pub(crate) fn find_template_slots(
+    body: TokenStream,
+    template: Option<&LitStr>,
+) -> Option<Vec<String>> {
+    let mut slot_names = Vec::new();
+    let mut found = collect_template_slots(body, &mut slot_names)?;
+
+    if let Some(template) = template {
+        slot_names.extend(tmpl_file_slot_names(template).ok()?);
+        found = true;
+    }
+
+    slot_names.sort();
+    slot_names.dedup();
+
+    found.then_some(slot_names)
+}
+
+/// Adds the slots of the `tmpl!`s in `tokens`, returning whether there were any
+fn collect_template_slots(tokens: TokenStream, slot_names: &mut Vec<String>) -> Option<bool> {
+    let tokens = tokens.into_iter().collect::<Vec<_>>();
+    let mut found = false;
+
+    for (index, token) in tokens.iter().enumerate() {
+        let TokenTree::Group(group) = token else {
+            continue;
+        };
+
+        let is_tmpl_call = index >= 2
+            && matches!(
+                (&tokens[index - 2], &tokens[index - 1]),
+                (TokenTree::Ident(ident), TokenTree::Punct(punct))
+                    if ident == "tmpl" && punct.as_char() == '!'
+            );
+
+        if is_tmpl_call {
+            slot_names.extend(tmpl_slot_names(group.stream()).ok()?);
+            found = true;
+        }
+
+        // Templates can be nested in the expressions of an other template
+        found |= collect_template_slots(group.stream(), slot_names)?;
+    }
+
+    Some(found)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_slots_of_nested_templates() {
+        let tokens = r#"
+            {
+                let footer = tmpl! { <footer><#slot actions /></footer> };
+                tmpl! {
+                    <header><#slot header>Title</#slot></header>
+                    <#slot row {item} />
+                    {#if open}<#slot />{/if}
+                    {footer}
+                }
+            }
+        "#
+        .parse()
+        .unwrap();
+
+        assert_eq!(
+            find_template_slots(tokens, None),
+            Some(vec!["actions".to_owned(), "header".to_owned()])
+        );
+    }
+
+    #[test]
+    fn slots_are_unknown_without_templates() {
+        let tokens = "{ format!(\"<p></p>\") }".parse().unwrap();
+
+        assert_eq!(find_template_slots(tokens, None), None);
+    }
+}
@@ -1,7 +1,7 @@
 use syn::{FnArg, ItemFn, Pat, ReturnType};
 
 /// Validate that the function has the correct signature for a component
-pub(crate) fn validate_component_function(input: &ItemFn) {
+pub(crate) fn validate_component_function(input: &ItemFn) -> syn::Result<()> {
     // Check that all parameters have #[prop] or #[slot] attribute
     for arg in &input.sig.inputs {
         match arg {
@@ -35,8 +35,16 @@ pub(crate) fn validate_component_function(input: &ItemFn) {
                         .unwrap_or(false)
                 });
 
+                let has_attrs_attr = pat_type.attrs.iter().any(|attr| {
+                    attr.path()
+                        .get_ident()
+                        .map(|ident| ident == "attrs")
+                        .unwrap_or(false)
+                });
+
                 if !has_prop_attr
                     && !has_slot_attr
+                    && !has_attrs_attr
                     && !has_server_context_attr
                     && !has_route_data_attr
                 {
@@ -46,13 +54,19 @@ pub(crate) fn validate_component_function(input: &ItemFn) {
                         _ => "parameter".to_owned(),
                     };
 
-                    panic!(
-                        "Component parameter '{param_name}' must have #[prop], #[slot], #[route_data], or #[server_context] attribute"
-                    );
+                    return Err(syn::Error::new_spanned(
+                        pat_type,
+                        format!(
+                            "component parameter `{param_name}` must have #[prop], #[slot], #[attrs], #[route_data], or #[server_context] attribute"
+                        ),
+                    ));
                 }
             }
-            FnArg::Receiver(_) => {
-                panic!("Component functions cannot have self parameter");
+            FnArg::Receiver(receiver) => {
+                return Err(syn::Error::new_spanned(
+                    receiver,
+                    "component functions cannot have self parameter",
+                ));
             }
         }
     }
@@ -69,4 +83,6 @@ pub(crate) fn validate_component_function(input: &ItemFn) {
             // panic!("Component functions must have an explicit Html return type");
         }
     }
+
+    Ok(())
 }
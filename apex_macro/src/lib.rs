@@ -4,14 +4,16 @@ use proc_macro::TokenStream;
 use syn::{ItemFn, parse_macro_input};
 
 use crate::{
-    component::generate_component,
-    route::{generate_route, parse_route_args},
+    component::{generate_component, generate_has_slot_macro, parse_component_args},
+    route::{generate_action, generate_route, parse_route_args},
+    server_fn::generate_server_fn,
     tmpl::parse_tmpl,
 };
 
 pub(crate) mod common;
 mod component;
 mod route;
+mod server_fn;
 pub(crate) mod tmpl;
 
 #[proc_macro]
@@ -20,21 +22,83 @@ pub fn tmpl(input: TokenStream) -> TokenStream {
 }
 
 #[proc_macro_attribute]
-pub fn component(_args: TokenStream, input: TokenStream) -> TokenStream {
+pub fn component(args: TokenStream, input: TokenStream) -> TokenStream {
     let item_fn = parse_macro_input!(input as ItemFn);
+    let component_args = match parse_component_args(args) {
+        Ok(component_args) => component_args,
+        Err(error) => return error.into_compile_error().into(),
+    };
 
-    generate_component(item_fn).into()
+    generate_component(component_args, item_fn)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
 }
 
 #[proc_macro_attribute]
 pub fn route(args: TokenStream, input: TokenStream) -> TokenStream {
     let item_fn = parse_macro_input!(input as ItemFn);
-    let route_args = parse_route_args(args);
+    let route_args = match parse_route_args(args) {
+        Ok(route_args) => route_args,
+        Err(error) => return error.into_compile_error().into(),
+    };
 
-    generate_route(route_args, item_fn).into()
+    generate_route(route_args, item_fn)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
 }
 
 #[proc_macro]
 pub fn loader_data(input: TokenStream) -> TokenStream {
     crate::route::generate_loader_data_macro(input).into()
 }
+
+#[proc_macro_attribute]
+pub fn route_action(args: TokenStream, input: TokenStream) -> TokenStream {
+    if !args.is_empty() {
+        return syn::Error::new(
+            proc_macro2::Span::call_site(),
+            "`#[route_action]` doesn't take arguments",
+        )
+        .into_compile_error()
+        .into();
+    }
+
+    let item_fn = parse_macro_input!(input as ItemFn);
+
+    generate_action(item_fn)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+#[proc_macro]
+pub fn action_data(input: TokenStream) -> TokenStream {
+    crate::route::generate_action_data_macro(input).into()
+}
+
+#[proc_macro]
+pub fn has_slot(input: TokenStream) -> TokenStream {
+    generate_has_slot_macro(input).into()
+}
+
+/// Runs an async function on the server, the browser calling it with a `POST` request to
+/// `/apex/server/<module path>::<name>`.
+///
+/// The function is registered by `ApexServerRouter::new`, which collects the `#[server]`
+/// functions of every crate linked into the server.
+#[proc_macro_attribute]
+pub fn server(args: TokenStream, input: TokenStream) -> TokenStream {
+    if !args.is_empty() {
+        return syn::Error::new(
+            proc_macro2::Span::call_site(),
+            "`#[server]` doesn't take arguments",
+        )
+        .into_compile_error()
+        .into();
+    }
+
+    let item_fn = parse_macro_input!(input as ItemFn);
+
+    generate_server_fn(item_fn)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
@@ -0,0 +1,68 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{FnArg, ItemFn};
+
+use super::generate_data_helper::generate_data_helper;
+use super::generate_route::loader_return_type::{LoaderReturn, loader_return_type};
+
+/// Generate a route action from `#[route_action] fn name(request: RequestContext) -> T`, where `T`
+/// is the data of the action, possibly in a `LoaderResult<T>` or `Result<T, E>`.
+///
+/// The action becomes a server-only `async fn` returning `LoaderResult<T>`, used by
+/// `#[route(action = name)]`, and its data is read with `action_data!(name)`.
+pub(crate) fn generate_action(input: ItemFn) -> syn::Result<TokenStream> {
+    let fn_name = &input.sig.ident;
+    let vis = &input.vis;
+
+    let [FnArg::Typed(_)] = input.sig.inputs.iter().collect::<Vec<_>>()[..] else {
+        return Err(syn::Error::new_spanned(
+            &input.sig,
+            "actions must have exactly one parameter: request: RequestContext",
+        ));
+    };
+
+    let syn::ReturnType::Type(_, return_type) = &input.sig.output else {
+        return Err(syn::Error::new_spanned(
+            &input.sig,
+            "actions must return their data, e.g. LoaderResult<FormErrors>",
+        ));
+    };
+
+    let action_return = loader_return_type(return_type);
+    let data_type = action_return.data_type();
+
+    // The action is kept as is inside the generated function, so it can `return` early
+    let mut action_fn = input.clone();
+    action_fn.vis = syn::Visibility::Inherited;
+
+    if let Some(FnArg::Typed(request)) = action_fn.sig.inputs.first_mut() {
+        *request.ty = syn::parse_quote!(apex::apex_router::RequestContext);
+    }
+
+    let call_action_fn = if input.sig.asyncness.is_some() {
+        quote! { #fn_name(request).await }
+    } else {
+        quote! { #fn_name(request) }
+    };
+
+    let action_result = match action_return {
+        LoaderReturn::LoaderResult(_) => call_action_fn,
+        LoaderReturn::Result(_) => {
+            quote! { apex::apex_router::LoaderResult::from(#call_action_fn) }
+        }
+        LoaderReturn::Data(_) => quote! { apex::apex_router::LoaderResult::Ok(#call_action_fn) },
+    };
+
+    let action_data_helper = generate_data_helper(fn_name, "action", data_type);
+
+    Ok(quote! {
+        #[cfg(not(target_arch = "wasm32"))]
+        #vis async fn #fn_name(request: apex::apex_router::RequestContext) -> apex::apex_router::LoaderResult<#data_type> {
+            #action_fn
+
+            #action_result
+        }
+
+        #action_data_helper
+    })
+}
@@ -0,0 +1,28 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{Ident, Type};
+
+/// Generate the function reading the data `fn_name` stored for the page, used by
+/// `loader_data!` and `action_data!`. In the browser the data comes from the init data,
+/// on the server from the data of the route being rendered.
+pub(crate) fn generate_data_helper(fn_name: &Ident, kind: &str, data_type: &Type) -> TokenStream {
+    let helper_name = Ident::new(&format!("get_{fn_name}_{kind}_data"), fn_name.span());
+
+    quote! {
+        #[cfg(target_arch = "wasm32")]
+        pub(crate) fn #helper_name() -> Signal<Option<#data_type>> {
+            let route_name = stringify!(#fn_name);
+            signal!(apex::apex_router::init_data::get_typed_route_data::<#data_type>(route_name))
+        }
+
+        #[cfg(not(target_arch = "wasm32"))]
+        pub(crate) fn #helper_name(data: &std::collections::HashMap<String, serde_json::Value>) -> Signal<Option<#data_type>> {
+            let route_name = stringify!(#fn_name);
+
+            signal!(
+                data.get(route_name)
+                    .and_then(|value| serde_json::from_value::<#data_type>(value.clone()).ok())
+            )
+        }
+    }
+}
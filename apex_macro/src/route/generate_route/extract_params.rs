@@ -0,0 +1,69 @@
+use syn::{FnArg, GenericArgument, ItemFn, PathArguments, Type};
+
+/// How the route function takes its path parameters
+pub(crate) enum RouteParams {
+    /// `HashMap<String, String>` of the raw parameters
+    Map,
+    /// `Path<T>`, the parameters deserialized into `T`
+    Path,
+}
+
+pub(crate) fn extract_params(input: &ItemFn) -> syn::Result<RouteParams> {
+    let Some(FnArg::Typed(pat_type)) = input.sig.inputs.first() else {
+        return Err(syn::Error::new_spanned(
+            &input.sig,
+            "could not extract parameters from route function",
+        ));
+    };
+
+    let Type::Path(type_path) = &*pat_type.ty else {
+        return Ok(RouteParams::Map);
+    };
+
+    let Some(segment) = type_path.path.segments.last() else {
+        return Ok(RouteParams::Map);
+    };
+
+    if segment.ident != "Path" {
+        return Ok(RouteParams::Map);
+    }
+
+    match &segment.arguments {
+        PathArguments::AngleBracketed(arguments) if arguments.args.len() == 1 => {
+            match arguments.args.first() {
+                Some(GenericArgument::Type(_)) => Ok(RouteParams::Path),
+                _ => Err(syn::Error::new_spanned(
+                    &pat_type.ty,
+                    "expected the type of the path parameters, e.g. Path<UserParams>",
+                )),
+            }
+        }
+        _ => Err(syn::Error::new_spanned(
+            &pat_type.ty,
+            "expected the type of the path parameters, e.g. Path<UserParams>",
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn extract_params_type() {
+        let input: ItemFn = syn::parse_quote! {
+            fn user_page(params: HashMap<String, String>) {}
+        };
+        assert!(matches!(extract_params(&input), Ok(RouteParams::Map)));
+
+        let input: ItemFn = syn::parse_quote! {
+            fn user_page(Path(params): Path<UserParams>) {}
+        };
+        assert!(matches!(extract_params(&input), Ok(RouteParams::Path)));
+
+        let input: ItemFn = syn::parse_quote! {
+            fn user_page(params: Path) {}
+        };
+        assert!(extract_params(&input).is_err());
+    }
+}
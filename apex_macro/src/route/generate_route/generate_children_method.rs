@@ -35,6 +35,10 @@ mod tests {
             component: None,
             path: None,
             children: vec![],
+            error: None,
+            action: None,
+            middleware: vec![],
+            local: false,
         };
 
         let result = generate_children_method(&args, quote!(apex::apex_router::ApexRoute));
@@ -53,6 +57,10 @@ mod tests {
             component: None,
             path: None,
             children: vec![create_ident("HomeRoute")],
+            error: None,
+            action: None,
+            middleware: vec![],
+            local: false,
         };
 
         let result = generate_children_method(&args, quote!(apex::apex_router::ApexRoute));
@@ -75,6 +83,10 @@ mod tests {
                 create_ident("AboutRoute"),
                 create_ident("ContactRoute"),
             ],
+            error: None,
+            action: None,
+            middleware: vec![],
+            local: false,
         };
 
         let result = generate_children_method(&args, quote!(apex::apex_router::ApexRoute));
@@ -97,6 +109,10 @@ mod tests {
             component: Some(create_ident("MyComponent")),
             path: Some(syn::LitStr::new("/test", proc_macro2::Span::call_site())),
             children: vec![create_ident("ChildRoute")],
+            error: None,
+            action: None,
+            middleware: vec![],
+            local: false,
         };
 
         let result = generate_children_method(&args, quote!(apex::apex_router::ApexRoute));
@@ -117,6 +133,10 @@ mod tests {
             component: None,
             path: None,
             children: vec![],
+            error: None,
+            action: None,
+            middleware: vec![],
+            local: false,
         };
 
         let result =
@@ -130,6 +150,10 @@ mod tests {
             component: None,
             path: None,
             children: vec![create_ident("TestRoute")],
+            error: None,
+            action: None,
+            middleware: vec![],
+            local: false,
         };
 
         let result = generate_children_method(&args, quote!(apex::apex_router::ApexServerRoute));
@@ -142,6 +166,10 @@ mod tests {
             component: None,
             path: None,
             children: vec![create_ident("SomeRoute")],
+            error: None,
+            action: None,
+            middleware: vec![],
+            local: false,
         };
 
         let result = generate_children_method(&args, quote!(apex::apex_router::ApexRoute));
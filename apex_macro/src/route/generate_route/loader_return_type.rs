@@ -0,0 +1,88 @@
+use syn::{GenericArgument, PathArguments, Type};
+
+/// How the route function returns its data
+pub(crate) enum LoaderReturn<'a> {
+    /// Plain data, always rendered
+    Data(&'a Type),
+    /// `LoaderResult<T>`, may answer with a redirect, a 404 or any response instead
+    LoaderResult(&'a Type),
+    /// `Result<T, E>`, an error answers with a server error
+    Result(&'a Type),
+}
+
+impl<'a> LoaderReturn<'a> {
+    pub(crate) fn data_type(&self) -> &'a Type {
+        match self {
+            Self::Data(ty) | Self::LoaderResult(ty) | Self::Result(ty) => ty,
+        }
+    }
+}
+
+/// Find out whether the return type wraps the data in `LoaderResult<T>` or `Result<T, E>`
+pub(crate) fn loader_return_type(ty: &Type) -> LoaderReturn<'_> {
+    let Type::Path(type_path) = ty else {
+        return LoaderReturn::Data(ty);
+    };
+
+    let Some(segment) = type_path.path.segments.last() else {
+        return LoaderReturn::Data(ty);
+    };
+
+    let PathArguments::AngleBracketed(arguments) = &segment.arguments else {
+        return LoaderReturn::Data(ty);
+    };
+
+    let Some(GenericArgument::Type(data_type)) = arguments.args.first() else {
+        return LoaderReturn::Data(ty);
+    };
+
+    match (segment.ident.to_string().as_str(), arguments.args.len()) {
+        ("LoaderResult", 1) => LoaderReturn::LoaderResult(data_type),
+        ("Result", 2) => LoaderReturn::Result(data_type),
+        _ => LoaderReturn::Data(ty),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use quote::quote;
+
+    fn data_type(ty: &str) -> (&'static str, String) {
+        let ty: Type = syn::parse_str(ty).unwrap();
+
+        let kind = match loader_return_type(&ty) {
+            LoaderReturn::Data(_) => "data",
+            LoaderReturn::LoaderResult(_) => "loader_result",
+            LoaderReturn::Result(_) => "result",
+        };
+
+        let data_type = loader_return_type(&ty).data_type();
+        (kind, quote!(#data_type).to_string())
+    }
+
+    #[test]
+    fn loader_return_types() {
+        assert_eq!(data_type("UserData"), ("data", "UserData".to_owned()));
+        assert_eq!(
+            data_type("Vec<UserData>"),
+            ("data", "Vec < UserData >".to_owned())
+        );
+        assert_eq!(
+            data_type("LoaderResult<UserData>"),
+            ("loader_result", "UserData".to_owned())
+        );
+        assert_eq!(
+            data_type("apex::LoaderResult<Vec<UserData>>"),
+            ("loader_result", "Vec < UserData >".to_owned())
+        );
+        assert_eq!(
+            data_type("Result<UserData, String>"),
+            ("result", "UserData".to_owned())
+        );
+        assert_eq!(
+            data_type("std::io::Result<UserData>"),
+            ("data", "std :: io :: Result < UserData >".to_owned())
+        );
+    }
+}
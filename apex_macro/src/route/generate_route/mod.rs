@@ -1,29 +1,67 @@
-mod extract_params_name;
+mod extract_params;
 mod generate_children_method;
+pub(crate) mod loader_return_type;
 mod validate_route_function;
 
 use proc_macro2::TokenStream;
 use quote::quote;
 use syn::ItemFn;
 
+use super::generate_data_helper::generate_data_helper;
 use super::parse_route_args::RouteArgs;
 use crate::common::to_pascal_case;
 
-use extract_params_name::extract_params_name;
+use extract_params::{RouteParams, extract_params};
 use generate_children_method::generate_children_method;
+use loader_return_type::{LoaderReturn, loader_return_type};
 use validate_route_function::validate_route_function;
 
-pub(crate) fn generate_route(args: RouteArgs, input: ItemFn) -> TokenStream {
+pub(crate) fn generate_route(args: RouteArgs, input: ItemFn) -> syn::Result<TokenStream> {
     let fn_name = &input.sig.ident;
-    let fn_body = &input.block;
     let route_struct_name = syn::Ident::new(
         &format!("{}Route", to_pascal_case(&fn_name.to_string())),
         fn_name.span(),
     );
 
-    validate_route_function(&input);
+    validate_route_function(&input)?;
 
-    let params_name = extract_params_name(&input);
+    if args.local && input.sig.asyncness.is_none() {
+        return Err(syn::Error::new_spanned(
+            input.sig.fn_token,
+            "`local` routes must be `async fn`",
+        ));
+    }
+
+    let route_params = extract_params(&input)?;
+
+    // With an error component, a failing render or hydration shows it instead
+    let (render_component, hydrate_component) = match args.error.as_ref() {
+        Some(error_component) => (
+            quote! {
+                apex::error::render_or_fallback(
+                    stringify!(#fn_name),
+                    data.clone(),
+                    || component.render(data.clone()),
+                    |error| #error_component::builder().error(error).build().render(data.clone()),
+                )
+            },
+            quote! {
+                apex::error::hydrate_or_fallback(
+                    stringify!(#fn_name),
+                    state.clone(),
+                    |state| component.hydrate()(state),
+                    |error, state| #error_component::builder().error(error).build().hydrate()(state),
+                );
+            },
+        ),
+        None => (
+            quote! { component.render(data.clone()) },
+            quote! {
+                let hydrate_fn = component.hydrate();
+                hydrate_fn(state.clone());
+            },
+        ),
+    };
 
     let hydrate_component_method = if let Some(component_name) = args.component.as_ref() {
         quote! {
@@ -32,8 +70,7 @@ pub(crate) fn generate_route(args: RouteArgs, input: ItemFn) -> TokenStream {
                 state: std::rc::Rc<std::cell::RefCell<apex_router::client_router::State>>
             ) {
                 let component = #component_name::builder().build();
-                let hydrate_fn = component.hydrate();
-                hydrate_fn(state.clone());
+                #hydrate_component
             }
         }
     } else {
@@ -48,38 +85,77 @@ pub(crate) fn generate_route(args: RouteArgs, input: ItemFn) -> TokenStream {
         syn::ReturnType::Default => false,
     };
 
-    let loader_data_helper = if has_return_value {
-        let helper_name = syn::Ident::new(&format!("get_{fn_name}_loader_data"), fn_name.span());
+    let loader_return = match &input.sig.output {
+        syn::ReturnType::Type(_, ty) if has_return_value => Some(loader_return_type(ty)),
+        _ => None,
+    };
 
-        let return_type = match &input.sig.output {
-            syn::ReturnType::Type(_, ty) => ty,
-            syn::ReturnType::Default => {
-                panic!("Route with return value must have explicit return type")
-            }
-        };
+    let loader_data_helper = match loader_return.as_ref() {
+        Some(loader_return) => generate_data_helper(fn_name, "loader", loader_return.data_type()),
+        None => quote! {},
+    };
 
-        quote! {
-            #[cfg(target_arch = "wasm32")]
-            pub(crate) fn #helper_name() -> Signal<Option<#return_type>> {
-                let route_name = stringify!(#fn_name);
-                signal!(apex::apex_router::init_data::get_typed_route_data::<#return_type>(route_name))
-            }
+    let has_component = args.component.is_some();
 
-            #[cfg(not(target_arch = "wasm32"))]
-            pub(crate) fn #helper_name(data: &std::collections::HashMap<String, serde_json::Value>) -> Signal<Option<#return_type>> {
-                let route_name = stringify!(#fn_name);
+    // The route function is kept as is inside the handler, so it can `return` early and be
+    // `async`. The handler's future only runs it, the route is rendered afterwards.
+    let mut route_fn = input.clone();
+    route_fn.vis = syn::Visibility::Inherited;
 
-                signal!(
-                    data.get(route_name)
-                        .and_then(|value| serde_json::from_value::<#return_type>(value.clone()).ok())
-                )
-            }
+    // The parameter types were validated, the router passes the params and the request as is
+    let mut route_fn_args = vec![quote!(__params)];
+
+    if route_fn.sig.inputs.len() > 1 {
+        route_fn_args.push(quote!(__request.clone()));
+    }
+
+    // Parameters that don't deserialize don't match the route
+    let parse_params = match &route_params {
+        RouteParams::Path => quote! {
+            let Ok(__params) = apex::apex_router::Path::from_params(&__params) else {
+                return Err(apex::apex_router::HttpResponse::not_found());
+            };
+        },
+        RouteParams::Map => quote! {},
+    };
+
+    // A `local` route function is run by `run_local`, so its future may hold values that
+    // aren't `Send` across `.await`s. A function that didn't finish answers with a 500.
+    let call_route_fn = if args.local {
+        quote! {
+            apex::apex_router::run_local({
+                let __request = __request.clone();
+                move || #fn_name(#(#route_fn_args),*)
+            })
+            .await?
         }
+    } else if input.sig.asyncness.is_some() {
+        quote! { #fn_name(#(#route_fn_args),*).await }
     } else {
-        quote! {}
+        quote! { #fn_name(#(#route_fn_args),*) }
     };
 
-    let has_component = args.component.is_some();
+    // Anything but data is sent as the response, without rendering the route
+    let load_route_data = match loader_return {
+        Some(LoaderReturn::LoaderResult(_)) => quote! {
+            let route_data = match #call_route_fn.into_response() {
+                Ok(route_data) => route_data,
+                Err(response) => return Err(response),
+            };
+        },
+        Some(LoaderReturn::Result(_)) => quote! {
+            let route_data = match apex::apex_router::LoaderResult::from(#call_route_fn).into_response() {
+                Ok(route_data) => route_data,
+                Err(response) => return Err(response),
+            };
+        },
+        Some(LoaderReturn::Data(_)) => quote! {
+            let route_data = #call_route_fn;
+        },
+        None => quote! {
+            #call_route_fn;
+        },
+    };
 
     let handler_method_logic = if has_return_value && has_component {
         let Some(component_name) = args.component.as_ref() else {
@@ -87,7 +163,6 @@ pub(crate) fn generate_route(args: RouteArgs, input: ItemFn) -> TokenStream {
         };
 
         quote! {
-            let route_data = { #fn_body };
             let component = #component_name::builder().build();
 
             let route_name = stringify!(#fn_name);
@@ -96,7 +171,7 @@ pub(crate) fn generate_route(args: RouteArgs, input: ItemFn) -> TokenStream {
                 data.borrow_mut().insert(route_name.to_owned(), serialized_data);
             }
 
-            let html = component.render(data.clone());
+            let html = #render_component;
 
             html
         }
@@ -106,13 +181,13 @@ pub(crate) fn generate_route(args: RouteArgs, input: ItemFn) -> TokenStream {
         };
 
         quote! {
-            { #fn_body };
             let component = #component_name::builder().build();
-            component.render(data.clone())
+            #render_component
         }
+    } else if !has_return_value {
+        quote! { String::new() }
     } else {
         quote! {
-            let route_data = { #fn_body };
             let route_name = stringify!(#fn_name);
 
             if let Ok(serialized_data) = serde_json::to_value(&route_data) {
@@ -129,6 +204,39 @@ pub(crate) fn generate_route(args: RouteArgs, input: ItemFn) -> TokenStream {
         .map(|p| p.value())
         .unwrap_or_else(|| "/".to_owned());
 
+    // The action's data is added to the route data, for `action_data!`
+    let server_action_method = match args.action.as_ref() {
+        Some(action) => quote! {
+            fn action(&self) -> Option<apex::apex_router::ApexServerAction> {
+                Some(Box::new(|request: apex::apex_router::RequestContext| {
+                    Box::pin(async move {
+                        let action_data = #action(request).await.into_response()?;
+                        let mut data = std::collections::HashMap::new();
+
+                        if let Ok(serialized_data) = serde_json::to_value(&action_data) {
+                            data.insert(stringify!(#action).to_owned(), serialized_data);
+                        }
+
+                        Ok(data)
+                    })
+                }))
+            }
+        },
+        None => quote! {},
+    };
+
+    let server_middleware_method = if args.middleware.is_empty() {
+        quote! {}
+    } else {
+        let middleware = &args.middleware;
+
+        quote! {
+            fn middleware(&self) -> Vec<std::sync::Arc<dyn apex::apex_router::ApexMiddleware>> {
+                vec![#(std::sync::Arc::new(#middleware) as std::sync::Arc<dyn apex::apex_router::ApexMiddleware>),*]
+            }
+        }
+    };
+
     let server_children_method =
         generate_children_method(&args, quote!(apex::apex_router::ApexServerRoute));
 
@@ -151,19 +259,35 @@ pub(crate) fn generate_route(args: RouteArgs, input: ItemFn) -> TokenStream {
             fn path(&self) -> &'static str { #path }
 
             fn handler(&self) -> apex::apex_router::ApexServerHandler {
-                Box::new(|#params_name: std::collections::HashMap<String, String>| {
+                Box::new(|__params: std::collections::HashMap<String, String>, __request: apex::apex_router::RequestContext| {
                     Box::pin(async move {
-                        let mut data: std::rc::Rc<std::cell::RefCell<std::collections::HashMap<String, serde_json::Value>>> = std::rc::Rc::new(std::cell::RefCell::new(std::collections::HashMap::new()));
+                        #route_fn
+
+                        #parse_params
 
-                        let html = {
-                            #handler_method_logic
-                        };
+                        #load_route_data
 
-                        (html, data)
+                        // Rendered after the loaders of all the routes of the request ran
+                        let render: apex::apex_router::ApexServerRender = Box::new(move |action_data| {
+                            let data: std::rc::Rc<std::cell::RefCell<std::collections::HashMap<String, serde_json::Value>>> = std::rc::Rc::new(std::cell::RefCell::new(action_data));
+
+                            // Components read the request with `RequestContext::current()`
+                            let html = __request.scope(|| {
+                                #handler_method_logic
+                            });
+
+                            (html, data.take())
+                        });
+
+                        Ok(render)
                     })
                 })
             }
 
+            #server_action_method
+
+            #server_middleware_method
+
             #server_children_method
         }
     };
@@ -191,9 +315,9 @@ pub(crate) fn generate_route(args: RouteArgs, input: ItemFn) -> TokenStream {
         quote! {}
     };
 
-    quote! {
+    Ok(quote! {
         #server_route
         #client_route
         #loader_data_helper
-    }
+    })
 }
@@ -1,17 +1,117 @@
-use quote::quote;
-use syn::{FnArg, ItemFn};
+use syn::{FnArg, GenericArgument, ItemFn, PathArguments, PathSegment, Type};
 
-pub(crate) fn validate_route_function(input: &ItemFn) {
-    if input.sig.inputs.len() != 1 {
-        panic!("Route functions must have exactly one parameter: params: HashMap<String, String>");
+pub(crate) fn validate_route_function(input: &ItemFn) -> syn::Result<()> {
+    if !matches!(input.sig.inputs.len(), 1 | 2) {
+        return Err(syn::Error::new_spanned(
+            &input.sig.inputs,
+            "route functions must have one or two parameters: params: HashMap<String, String> or Path<T>, request: RequestContext",
+        ));
     }
 
-    if let Some(FnArg::Typed(pat_type)) = input.sig.inputs.first() {
-        let type_str = quote!(#pat_type.ty).to_string();
-        if !type_str.contains("HashMap") {
-            panic!("Route function parameter should be HashMap<String, String>");
+    // The router passes these types as they are, the parameters can't have any other type
+    let expected_types: [(fn(&Type) -> bool, &str); 2] = [
+        (
+            is_params_type,
+            "route function parameter should be HashMap<String, String> or Path<T>",
+        ),
+        (
+            is_request_type,
+            "second route function parameter should be RequestContext",
+        ),
+    ];
+
+    for (input, (is_expected_type, message)) in input.sig.inputs.iter().zip(expected_types) {
+        match input {
+            FnArg::Typed(pat_type) => {
+                if !is_expected_type(&pat_type.ty) {
+                    return Err(syn::Error::new_spanned(&pat_type.ty, message));
+                }
+            }
+            receiver @ FnArg::Receiver(_) => {
+                return Err(syn::Error::new_spanned(
+                    receiver,
+                    "route functions cannot have self parameter",
+                ));
+            }
         }
-    } else {
-        panic!("Route functions cannot have self parameter");
+    }
+
+    Ok(())
+}
+
+/// Last segment of the path of a type, `HashMap<String, String>` of
+/// `std::collections::HashMap<String, String>`
+fn last_segment(ty: &Type) -> Option<&PathSegment> {
+    match ty {
+        Type::Path(type_path) if type_path.qself.is_none() => type_path.path.segments.last(),
+        _ => None,
+    }
+}
+
+/// `HashMap<String, String>`, or `Path<T>` whose type is checked by `extract_params`
+fn is_params_type(ty: &Type) -> bool {
+    let Some(segment) = last_segment(ty) else {
+        return false;
+    };
+
+    if segment.ident == "Path" {
+        return true;
+    }
+
+    let PathArguments::AngleBracketed(arguments) = &segment.arguments else {
+        return false;
+    };
+
+    segment.ident == "HashMap"
+        && arguments.args.len() == 2
+        && arguments.args.iter().all(|argument| {
+            matches!(
+                argument,
+                GenericArgument::Type(ty)
+                    if last_segment(ty).is_some_and(|segment| {
+                        segment.ident == "String" && segment.arguments.is_none()
+                    })
+            )
+        })
+}
+
+fn is_request_type(ty: &Type) -> bool {
+    last_segment(ty)
+        .is_some_and(|segment| segment.ident == "RequestContext" && segment.arguments.is_none())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parameter_types() {
+        let input: ItemFn = syn::parse_quote! {
+            fn user_page(params: std::collections::HashMap<String, String>, request: RequestContext) {}
+        };
+        assert!(validate_route_function(&input).is_ok());
+
+        let input: ItemFn = syn::parse_quote! {
+            fn user_page(Path(params): Path<UserParams>) {}
+        };
+        assert!(validate_route_function(&input).is_ok());
+
+        let input: ItemFn = syn::parse_quote! {
+            fn user_page(params: HashMap<String, u32>) {}
+        };
+        let error = validate_route_function(&input).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "route function parameter should be HashMap<String, String> or Path<T>"
+        );
+
+        let input: ItemFn = syn::parse_quote! {
+            fn user_page(params: HashMap<String, String>, request: &RequestContext) {}
+        };
+        let error = validate_route_function(&input).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "second route function parameter should be RequestContext"
+        );
     }
 }
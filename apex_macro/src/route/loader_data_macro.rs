@@ -21,10 +21,22 @@ impl Parse for LoaderDataInput {
 }
 
 pub(crate) fn generate_loader_data_macro(input: TokenStream) -> TokenStream2 {
-    let input: LoaderDataInput = syn::parse(input).unwrap();
+    generate_data_macro(input, "loader")
+}
+
+/// `action_data!(action_name)`, the data returned by an action, read like loader data
+pub(crate) fn generate_action_data_macro(input: TokenStream) -> TokenStream2 {
+    generate_data_macro(input, "action")
+}
+
+fn generate_data_macro(input: TokenStream, kind: &str) -> TokenStream2 {
+    let input: LoaderDataInput = match syn::parse(input) {
+        Ok(input) => input,
+        Err(error) => return error.into_compile_error(),
+    };
 
     let route_name = &input.route_name;
-    let helper_name = syn::Ident::new(&format!("get_{route_name}_loader_data"), route_name.span());
+    let helper_name = syn::Ident::new(&format!("get_{route_name}_{kind}_data"), route_name.span());
 
     // Generate different code based on the target architecture
     quote! {
@@ -1,7 +1,10 @@
+mod generate_action;
+mod generate_data_helper;
 pub(crate) mod generate_route;
 mod loader_data_macro;
 mod parse_route_args;
 
+pub(crate) use generate_action::generate_action;
 pub(crate) use generate_route::generate_route;
-pub(crate) use loader_data_macro::generate_loader_data_macro;
+pub(crate) use loader_data_macro::{generate_action_data_macro, generate_loader_data_macro};
 pub(crate) use parse_route_args::parse_route_args;
@@ -10,6 +10,15 @@ pub(crate) struct RouteArgs {
     pub component: Option<Ident>,
     pub path: Option<LitStr>,
     pub children: Vec<Ident>,
+    /// Component rendered instead of `component` when it fails, given the error as `error`
+    pub error: Option<Ident>,
+    /// `#[route_action]` handling the requests other than `GET`, e.g. form submissions
+    pub action: Option<Ident>,
+    /// Middleware run around the requests of the route and of its children
+    pub middleware: Vec<syn::Expr>,
+    /// `local`, the async route function runs on a thread of its own so its future doesn't
+    /// have to be `Send`
+    pub local: bool,
 }
 
 impl Parse for RouteArgs {
@@ -24,45 +33,73 @@ impl Parse for RouteArgs {
             syn::punctuated::Punctuated::<Meta, syn::Token![,]>::parse_terminated(input)?;
 
         for meta in parsed_args {
-            match meta {
-                Meta::NameValue(name_value) => {
-                    if name_value.path.is_ident("component") {
-                        if let syn::Expr::Path(expr_path) = &name_value.value {
-                            if let Some(ident) = expr_path.path.get_ident() {
-                                route_args.component = Some(ident.clone());
-                            }
-                        }
-                    } else if name_value.path.is_ident("path") {
-                        if let syn::Expr::Lit(syn::ExprLit {
-                            lit: syn::Lit::Str(s),
-                            ..
-                        }) = &name_value.value
-                        {
-                            route_args.path = Some(s.clone());
-                        }
-                    } else if name_value.path.is_ident("children") {
-                        // Handle children = [Route1, Route2, ...]
-                        if let syn::Expr::Array(array) = &name_value.value {
-                            for element in &array.elems {
-                                if let syn::Expr::Path(expr_path) = element {
-                                    if let Some(ident) = expr_path.path.get_ident() {
-                                        route_args.children.push(ident.clone());
-                                    }
-                                }
-                            }
-                        }
-                    }
-                }
-                Meta::Path(path) => {
-                    // Handle simple identifiers without values if needed
-                    if let Some(ident) = path.get_ident() {
-                        // Could handle flags like #[route(async)] here
-                        let _ = ident;
-                    }
-                }
-                Meta::List(_) => {
-                    // Handle nested attributes if needed in the future
+            if let Meta::Path(path) = &meta
+                && path.is_ident("local")
+            {
+                route_args.local = true;
+                continue;
+            }
+
+            let Meta::NameValue(name_value) = meta else {
+                return Err(syn::Error::new_spanned(
+                    meta,
+                    "expected `key = value`, e.g. `path = \"/about\"`",
+                ));
+            };
+
+            if name_value.path.is_ident("component") {
+                route_args.component = Some(expect_ident(&name_value.value)?);
+            } else if name_value.path.is_ident("path") {
+                let syn::Expr::Lit(syn::ExprLit {
+                    lit: syn::Lit::Str(s),
+                    ..
+                }) = &name_value.value
+                else {
+                    return Err(syn::Error::new_spanned(
+                        &name_value.value,
+                        "expected a string literal, e.g. `path = \"/about\"`",
+                    ));
+                };
+
+                route_args.path = Some(s.clone());
+            } else if name_value.path.is_ident("error") {
+                route_args.error = Some(expect_ident(&name_value.value)?);
+            } else if name_value.path.is_ident("action") {
+                route_args.action = Some(expect_ident(&name_value.value)?);
+            } else if name_value.path.is_ident("middleware") {
+                let syn::Expr::Array(array) = &name_value.value else {
+                    return Err(syn::Error::new_spanned(
+                        &name_value.value,
+                        "expected an array of middleware, e.g. `middleware = [require_auth]`",
+                    ));
+                };
+
+                route_args.middleware.extend(array.elems.iter().cloned());
+            } else if name_value.path.is_ident("children") {
+                // Handle children = [Route1, Route2, ...]
+                let syn::Expr::Array(array) = &name_value.value else {
+                    return Err(syn::Error::new_spanned(
+                        &name_value.value,
+                        "expected an array of routes, e.g. `children = [AboutRoute]`",
+                    ));
+                };
+
+                for element in &array.elems {
+                    route_args.children.push(expect_ident(element)?);
                 }
+            } else {
+                let key = name_value
+                    .path
+                    .get_ident()
+                    .map(ToString::to_string)
+                    .unwrap_or_else(|| "?".to_owned());
+
+                return Err(syn::Error::new_spanned(
+                    &name_value.path,
+                    format!(
+                        "unknown route argument `{key}`, expected one of: component, path, children, error, action, middleware, local"
+                    ),
+                ));
             }
         }
 
@@ -70,13 +107,90 @@ impl Parse for RouteArgs {
     }
 }
 
+/// Expect a plain identifier such as a component or route name
+fn expect_ident(expr: &syn::Expr) -> Result<Ident> {
+    if let syn::Expr::Path(expr_path) = expr {
+        if let Some(ident) = expr_path.path.get_ident() {
+            return Ok(ident.clone());
+        }
+    }
+
+    Err(syn::Error::new_spanned(expr, "expected an identifier"))
+}
+
 /// Parse arguments from the route macro attribute
-pub(crate) fn parse_route_args(args: TokenStream) -> RouteArgs {
+pub(crate) fn parse_route_args(args: TokenStream) -> Result<RouteArgs> {
     if args.is_empty() {
-        return RouteArgs::default();
+        return Ok(RouteArgs::default());
     }
 
-    syn::parse(args).unwrap_or_else(|err| {
-        panic!("Failed to parse route arguments: {err}");
-    })
+    syn::parse(args)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_route_args() {
+        let args: RouteArgs =
+            syn::parse_str(r#"component = About, path = "/about", children = [TeamRoute]"#)
+                .unwrap();
+
+        assert_eq!(args.component.unwrap(), "About");
+        assert_eq!(args.path.unwrap().value(), "/about");
+        assert_eq!(args.children.len(), 1);
+        assert_eq!(args.children[0], "TeamRoute");
+        assert!(args.error.is_none());
+    }
+
+    #[test]
+    fn test_parse_route_error_component() {
+        let args: RouteArgs = syn::parse_str(r#"component = About, error = AboutError"#).unwrap();
+
+        assert_eq!(args.error.unwrap(), "AboutError");
+    }
+
+    #[test]
+    fn test_parse_route_action() {
+        let args: RouteArgs = syn::parse_str(r#"component = Todos, action = add_todo"#).unwrap();
+
+        assert_eq!(args.action.unwrap(), "add_todo");
+    }
+
+    #[test]
+    fn test_parse_route_middleware() {
+        let args: RouteArgs =
+            syn::parse_str(r#"component = Admin, middleware = [require_auth, Log::new()]"#)
+                .unwrap();
+
+        assert_eq!(args.middleware.len(), 2);
+    }
+
+    #[test]
+    fn test_parse_route_local() {
+        let args: RouteArgs = syn::parse_str(r#"path = "/stats", local"#).unwrap();
+
+        assert!(args.local);
+    }
+
+    #[test]
+    fn test_unknown_route_argument() {
+        let error = syn::parse_str::<RouteArgs>(r#"componnet = About"#).unwrap_err();
+
+        assert_eq!(
+            error.to_string(),
+            "unknown route argument `componnet`, expected one of: component, path, children, error, action, middleware, local"
+        );
+    }
+
+    #[test]
+    fn test_route_path_must_be_string() {
+        let error = syn::parse_str::<RouteArgs>("path = about").unwrap_err();
+
+        assert_eq!(
+            error.to_string(),
+            "expected a string literal, e.g. `path = \"/about\"`"
+        );
+    }
 }
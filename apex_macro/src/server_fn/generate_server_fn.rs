@@ -0,0 +1,175 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{FnArg, ItemFn, Pat, ReturnType};
+
+use crate::common::to_pascal_case;
+
+/// Generate a server function from `#[server] async fn name(args) -> Result<T, E>`.
+///
+/// The server keeps the function as is, and gets an endpoint collected at link time for
+/// `ApexServerRouter::new`. The browser gets a function with the same signature calling the
+/// endpoint, its arguments and result sent as JSON.
+pub(crate) fn generate_server_fn(input: ItemFn) -> syn::Result<TokenStream> {
+    let fn_name = &input.sig.ident;
+    let vis = &input.vis;
+    let struct_name = syn::Ident::new(
+        &format!("{}ServerFn", to_pascal_case(&fn_name.to_string())),
+        fn_name.span(),
+    );
+
+    if input.sig.asyncness.is_none() {
+        return Err(syn::Error::new_spanned(
+            input.sig.fn_token,
+            "server functions must be async",
+        ));
+    }
+
+    let (ok_type, error_type) = result_types(&input.sig.output)?;
+
+    let mut arg_names = Vec::new();
+    let mut arg_types = Vec::new();
+
+    for arg in &input.sig.inputs {
+        let FnArg::Typed(pat_type) = arg else {
+            return Err(syn::Error::new_spanned(
+                arg,
+                "server functions cannot have self parameter",
+            ));
+        };
+
+        let Pat::Ident(pat_ident) = &*pat_type.pat else {
+            return Err(syn::Error::new_spanned(
+                &pat_type.pat,
+                "server function parameters must be plain identifiers",
+            ));
+        };
+
+        arg_names.push(&pat_ident.ident);
+        arg_types.push(&pat_type.ty);
+    }
+
+    let inputs = &input.sig.inputs;
+    let output = &input.sig.output;
+
+    // Same path on the server and in the browser, as both build the same crate
+    let path = quote! {
+        concat!("/apex/server/", module_path!(), "::", stringify!(#fn_name))
+    };
+
+    Ok(quote! {
+        #[cfg(not(target_arch = "wasm32"))]
+        #input
+
+        #[cfg(not(target_arch = "wasm32"))]
+        #vis struct #struct_name;
+
+        #[cfg(not(target_arch = "wasm32"))]
+        impl #struct_name {
+            pub fn new() -> Self {
+                Self
+            }
+        }
+
+        #[cfg(not(target_arch = "wasm32"))]
+        impl apex::apex_router::ApexServerFn for #struct_name {
+            fn path(&self) -> &'static str {
+                #path
+            }
+
+            fn handler(&self) -> apex::apex_router::ApexServerFnHandler {
+                Box::new(|request: apex::apex_router::RequestContext| {
+                    Box::pin(apex::apex_router::handle_server_fn(
+                        request,
+                        |(#(#arg_names,)*): (#(#arg_types,)*)| #fn_name(#(#arg_names),*),
+                    ))
+                })
+            }
+        }
+
+        #[cfg(not(target_arch = "wasm32"))]
+        apex::apex_router::inventory::submit! {
+            apex::apex_router::ServerFnRegistration(&#struct_name)
+        }
+
+        #[cfg(target_arch = "wasm32")]
+        #vis async fn #fn_name(#inputs) #output {
+            apex::apex_router::call_server_fn::<_, #ok_type, #error_type>(
+                #path,
+                &(#(#arg_names,)*),
+            )
+            .await
+        }
+    })
+}
+
+/// The `T` and `E` of the `Result<T, E>` returned by the server function
+fn result_types(output: &ReturnType) -> syn::Result<(&syn::Type, &syn::Type)> {
+    let error = || {
+        syn::Error::new_spanned(
+            output,
+            "server functions must return a Result<T, E>, E implementing From<ServerFnError>",
+        )
+    };
+
+    let ReturnType::Type(_, ty) = output else {
+        return Err(error());
+    };
+
+    let syn::Type::Path(type_path) = &**ty else {
+        return Err(error());
+    };
+
+    let Some(segment) = type_path.path.segments.last() else {
+        return Err(error());
+    };
+
+    let syn::PathArguments::AngleBracketed(arguments) = &segment.arguments else {
+        return Err(error());
+    };
+
+    match (
+        segment.ident == "Result",
+        arguments.args.first(),
+        arguments.args.iter().nth(1),
+    ) {
+        (true, Some(syn::GenericArgument::Type(ok)), Some(syn::GenericArgument::Type(err))) => {
+            Ok((ok, err))
+        }
+        _ => Err(error()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn server_fn_must_return_a_result() {
+        let input: ItemFn = syn::parse_quote! {
+            async fn get_total(a: i32, b: i32) -> i32 {
+                a + b
+            }
+        };
+
+        assert!(
+            generate_server_fn(input)
+                .unwrap_err()
+                .to_string()
+                .starts_with("server functions must return a Result<T, E>")
+        );
+    }
+
+    #[test]
+    fn server_fn_must_be_async() {
+        let input: ItemFn = syn::parse_quote! {
+            fn get_total(a: i32, b: i32) -> Result<i32, ServerFnError> {
+                Ok(a + b)
+            }
+        };
+
+        assert_eq!(
+            generate_server_fn(input).unwrap_err().to_string(),
+            "server functions must be async"
+        );
+    }
+}
@@ -0,0 +1,3 @@
+mod generate_server_fn;
+
+pub(crate) use generate_server_fn::generate_server_fn;
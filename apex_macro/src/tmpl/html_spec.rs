@@ -0,0 +1,701 @@
+//! Element and attribute tables from the HTML, SVG and MathML specs used to validate templates
+
+const HTML_ELEMENTS: &[&str] = &[
+    "a",
+    "abbr",
+    "address",
+    "area",
+    "article",
+    "aside",
+    "audio",
+    "b",
+    "base",
+    "bdi",
+    "bdo",
+    "blockquote",
+    "body",
+    "br",
+    "button",
+    "canvas",
+    "caption",
+    "cite",
+    "code",
+    "col",
+    "colgroup",
+    "data",
+    "datalist",
+    "dd",
+    "del",
+    "details",
+    "dfn",
+    "dialog",
+    "div",
+    "dl",
+    "dt",
+    "em",
+    "embed",
+    "fieldset",
+    "figcaption",
+    "figure",
+    "footer",
+    "form",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "head",
+    "header",
+    "hgroup",
+    "hr",
+    "html",
+    "i",
+    "iframe",
+    "img",
+    "input",
+    "ins",
+    "kbd",
+    "label",
+    "legend",
+    "li",
+    "link",
+    "main",
+    "map",
+    "mark",
+    "menu",
+    "meta",
+    "meter",
+    "nav",
+    "noscript",
+    "object",
+    "ol",
+    "optgroup",
+    "option",
+    "output",
+    "p",
+    "picture",
+    "pre",
+    "progress",
+    "q",
+    "rp",
+    "rt",
+    "ruby",
+    "s",
+    "samp",
+    "script",
+    "search",
+    "section",
+    "select",
+    "slot",
+    "small",
+    "source",
+    "span",
+    "strong",
+    "style",
+    "sub",
+    "summary",
+    "sup",
+    "table",
+    "tbody",
+    "td",
+    "template",
+    "textarea",
+    "tfoot",
+    "th",
+    "thead",
+    "time",
+    "title",
+    "tr",
+    "track",
+    "u",
+    "ul",
+    "var",
+    "video",
+    "wbr",
+];
+
+const SVG_ELEMENTS: &[&str] = &[
+    "animate",
+    "animateMotion",
+    "animateTransform",
+    "circle",
+    "clipPath",
+    "defs",
+    "desc",
+    "ellipse",
+    "feBlend",
+    "feColorMatrix",
+    "feComponentTransfer",
+    "feComposite",
+    "feConvolveMatrix",
+    "feDiffuseLighting",
+    "feDisplacementMap",
+    "feDistantLight",
+    "feDropShadow",
+    "feFlood",
+    "feFuncA",
+    "feFuncB",
+    "feFuncG",
+    "feFuncR",
+    "feGaussianBlur",
+    "feImage",
+    "feMerge",
+    "feMergeNode",
+    "feMorphology",
+    "feOffset",
+    "fePointLight",
+    "feSpecularLighting",
+    "feSpotLight",
+    "feTile",
+    "feTurbulence",
+    "filter",
+    "foreignObject",
+    "g",
+    "image",
+    "line",
+    "linearGradient",
+    "marker",
+    "mask",
+    "metadata",
+    "mpath",
+    "path",
+    "pattern",
+    "polygon",
+    "polyline",
+    "radialGradient",
+    "rect",
+    "set",
+    "stop",
+    "svg",
+    "switch",
+    "symbol",
+    "text",
+    "textPath",
+    "tspan",
+    "use",
+    "view",
+];
+
+const MATHML_ELEMENTS: &[&str] = &[
+    "annotation",
+    "maction",
+    "math",
+    "menclose",
+    "merror",
+    "mfenced",
+    "mfrac",
+    "mi",
+    "mmultiscripts",
+    "mn",
+    "mo",
+    "mover",
+    "mpadded",
+    "mphantom",
+    "mprescripts",
+    "mroot",
+    "mrow",
+    "ms",
+    "mspace",
+    "msqrt",
+    "mstyle",
+    "msub",
+    "msubsup",
+    "msup",
+    "mtable",
+    "mtd",
+    "mtext",
+    "mtr",
+    "munder",
+    "munderover",
+    "semantics",
+];
+
+const GLOBAL_ATTRIBUTES: &[&str] = &[
+    "accesskey",
+    "autocapitalize",
+    "autocorrect",
+    "autofocus",
+    "class",
+    "contenteditable",
+    "dir",
+    "draggable",
+    "enterkeyhint",
+    "hidden",
+    "id",
+    "inert",
+    "inputmode",
+    "is",
+    "itemid",
+    "itemprop",
+    "itemref",
+    "itemscope",
+    "itemtype",
+    "lang",
+    "nonce",
+    "part",
+    "popover",
+    "role",
+    "slot",
+    "spellcheck",
+    "style",
+    "tabindex",
+    "title",
+    "translate",
+    "writingsuggestions",
+];
+
+const FORM_SUBMITTER_ATTRIBUTES: &[&str] = &[
+    "form",
+    "formaction",
+    "formenctype",
+    "formmethod",
+    "formnovalidate",
+    "formtarget",
+    "popovertarget",
+    "popovertargetaction",
+];
+
+/// Attributes specific to an element, on top of the global ones
+fn element_attributes(tag: &str) -> &'static [&'static str] {
+    match tag {
+        "a" => &[
+            "download",
+            "href",
+            "hreflang",
+            "ping",
+            "referrerpolicy",
+            "rel",
+            "target",
+            "type",
+        ],
+        "area" => &[
+            "alt",
+            "coords",
+            "download",
+            "href",
+            "ping",
+            "referrerpolicy",
+            "rel",
+            "shape",
+            "target",
+        ],
+        "audio" => &[
+            "autoplay",
+            "controls",
+            "crossorigin",
+            "loop",
+            "muted",
+            "preload",
+            "src",
+        ],
+        "base" => &["href", "target"],
+        "blockquote" | "q" => &["cite"],
+        "button" => &[
+            "command",
+            "commandfor",
+            "disabled",
+            "form",
+            "formaction",
+            "formenctype",
+            "formmethod",
+            "formnovalidate",
+            "formtarget",
+            "name",
+            "popovertarget",
+            "popovertargetaction",
+            "type",
+            "value",
+        ],
+        "canvas" => &["height", "width"],
+        "col" | "colgroup" => &["span"],
+        "data" => &["value"],
+        "del" | "ins" => &["cite", "datetime"],
+        "details" => &["name", "open"],
+        "dialog" => &["closedby", "open"],
+        "embed" => &["height", "src", "type", "width"],
+        "fieldset" => &["disabled", "form", "name"],
+        "form" => &[
+            "accept-charset",
+            "action",
+            "autocomplete",
+            "enctype",
+            "method",
+            "name",
+            "novalidate",
+            "rel",
+            "target",
+        ],
+        "html" => &["manifest", "xmlns"],
+        "iframe" => &[
+            "allow",
+            "allowfullscreen",
+            "height",
+            "loading",
+            "name",
+            "referrerpolicy",
+            "sandbox",
+            "src",
+            "srcdoc",
+            "width",
+        ],
+        "img" => &[
+            "alt",
+            "crossorigin",
+            "decoding",
+            "fetchpriority",
+            "height",
+            "ismap",
+            "loading",
+            "referrerpolicy",
+            "sizes",
+            "src",
+            "srcset",
+            "usemap",
+            "width",
+        ],
+        "input" => &[
+            "accept",
+            "alt",
+            "autocomplete",
+            "capture",
+            "checked",
+            "dirname",
+            "disabled",
+            "height",
+            "list",
+            "max",
+            "maxlength",
+            "min",
+            "minlength",
+            "multiple",
+            "name",
+            "pattern",
+            "placeholder",
+            "readonly",
+            "required",
+            "size",
+            "src",
+            "step",
+            "type",
+            "value",
+            "width",
+        ],
+        "label" => &["for"],
+        "li" => &["value"],
+        "link" => &[
+            "as",
+            "blocking",
+            "color",
+            "crossorigin",
+            "disabled",
+            "fetchpriority",
+            "href",
+            "hreflang",
+            "imagesizes",
+            "imagesrcset",
+            "integrity",
+            "media",
+            "referrerpolicy",
+            "rel",
+            "sizes",
+            "type",
+        ],
+        "map" => &["name"],
+        "meta" => &["charset", "content", "http-equiv", "media", "name"],
+        "meter" => &["high", "low", "max", "min", "optimum", "value"],
+        "object" => &["data", "form", "height", "name", "type", "width"],
+        "ol" => &["reversed", "start", "type"],
+        "optgroup" => &["disabled", "label"],
+        "option" => &["disabled", "label", "selected", "value"],
+        "output" => &["for", "form", "name"],
+        "progress" => &["max", "value"],
+        "script" => &[
+            "async",
+            "blocking",
+            "crossorigin",
+            "defer",
+            "fetchpriority",
+            "integrity",
+            "nomodule",
+            "referrerpolicy",
+            "src",
+            "type",
+        ],
+        "select" => &[
+            "autocomplete",
+            "disabled",
+            "form",
+            "multiple",
+            "name",
+            "required",
+            "size",
+        ],
+        "slot" => &["name"],
+        "source" => &["height", "media", "sizes", "src", "srcset", "type", "width"],
+        "style" => &["blocking", "media"],
+        "td" => &["colspan", "headers", "rowspan"],
+        "template" => &[
+            "shadowrootclonable",
+            "shadowrootdelegatesfocus",
+            "shadowrootmode",
+            "shadowrootserializable",
+        ],
+        "textarea" => &[
+            "autocomplete",
+            "cols",
+            "dirname",
+            "disabled",
+            "form",
+            "maxlength",
+            "minlength",
+            "name",
+            "placeholder",
+            "readonly",
+            "required",
+            "rows",
+            "wrap",
+        ],
+        "th" => &["abbr", "colspan", "headers", "rowspan", "scope"],
+        "time" => &["datetime"],
+        "track" => &["default", "kind", "label", "src", "srclang"],
+        "video" => &[
+            "autoplay",
+            "controls",
+            "crossorigin",
+            "height",
+            "loop",
+            "muted",
+            "playsinline",
+            "poster",
+            "preload",
+            "src",
+            "width",
+        ],
+        _ => &[],
+    }
+}
+
+/// Custom elements must contain a hyphen, e.g. `<my-widget>`
+pub(crate) fn is_custom_element(tag: &str) -> bool {
+    tag.contains('-')
+}
+
+pub(crate) fn is_html_element(tag: &str) -> bool {
+    HTML_ELEMENTS.contains(&tag.to_ascii_lowercase().as_str())
+}
+
+pub(crate) fn is_svg_element(tag: &str) -> bool {
+    SVG_ELEMENTS.contains(&tag)
+}
+
+pub(crate) fn is_mathml_element(tag: &str) -> bool {
+    MATHML_ELEMENTS.contains(&tag)
+}
+
+pub(crate) fn is_known_element(tag: &str) -> bool {
+    is_html_element(tag) || is_svg_element(tag) || is_mathml_element(tag) || is_custom_element(tag)
+}
+
+/// Whether `name` is a valid attribute of the HTML element `tag`. Event handlers, `data-*`
+/// and `aria-*` attributes are valid everywhere.
+pub(crate) fn is_known_attribute(tag: &str, name: &str) -> bool {
+    let name = name.to_ascii_lowercase();
+
+    name.starts_with("on")
+        || name.starts_with("data-")
+        || name.starts_with("aria-")
+        || GLOBAL_ATTRIBUTES.contains(&name.as_str())
+        || element_attributes(&tag.to_ascii_lowercase()).contains(&name.as_str())
+        || (tag.eq_ignore_ascii_case("input") && FORM_SUBMITTER_ATTRIBUTES.contains(&name.as_str()))
+}
+
+/// Closest known attribute, to suggest a fix for typos like `clas`
+pub(crate) fn suggest_attribute(tag: &str, name: &str) -> Option<&'static str> {
+    let tag = tag.to_ascii_lowercase();
+
+    GLOBAL_ATTRIBUTES
+        .iter()
+        .chain(element_attributes(&tag))
+        .copied()
+        .map(|candidate| (candidate, edit_distance(name, candidate)))
+        .filter(|(_, distance)| *distance <= 2)
+        .min_by_key(|(_, distance)| *distance)
+        .map(|(candidate, _)| candidate)
+}
+
+/// Closest known element, to suggest a fix for typos like `<buton>`
+pub(crate) fn suggest_element(tag: &str) -> Option<&'static str> {
+    HTML_ELEMENTS
+        .iter()
+        .chain(SVG_ELEMENTS)
+        .chain(MATHML_ELEMENTS)
+        .copied()
+        .map(|candidate| (candidate, edit_distance(tag, candidate)))
+        .filter(|(_, distance)| *distance <= 2)
+        .min_by_key(|(_, distance)| *distance)
+        .map(|(candidate, _)| candidate)
+}
+
+/// Why `child` can't be a direct child of `parent`, both HTML elements
+pub(crate) fn invalid_nesting(parent: &str, child: &str) -> Option<String> {
+    const FLOW_CONTENT: &[&str] = &[
+        "address",
+        "article",
+        "aside",
+        "blockquote",
+        "details",
+        "dialog",
+        "div",
+        "dl",
+        "fieldset",
+        "figcaption",
+        "figure",
+        "footer",
+        "form",
+        "h1",
+        "h2",
+        "h3",
+        "h4",
+        "h5",
+        "h6",
+        "header",
+        "hgroup",
+        "hr",
+        "main",
+        "menu",
+        "nav",
+        "ol",
+        "p",
+        "pre",
+        "search",
+        "section",
+        "table",
+        "ul",
+    ];
+    const INTERACTIVE_CONTENT: &[&str] = &[
+        "a", "button", "details", "embed", "iframe", "input", "label", "select", "textarea",
+    ];
+
+    let parent = parent.to_ascii_lowercase();
+    let child = child.to_ascii_lowercase();
+    let parent = parent.as_str();
+    let child = child.as_str();
+
+    let expected_parents: &[&str] = match child {
+        "li" => &["ul", "ol", "menu"],
+        "dt" | "dd" => &["dl", "div"],
+        "tr" => &["table", "thead", "tbody", "tfoot"],
+        "td" | "th" => &["tr"],
+        "thead" | "tbody" | "tfoot" | "caption" | "colgroup" => &["table"],
+        "col" => &["colgroup"],
+        "option" => &["select", "datalist", "optgroup"],
+        "optgroup" => &["select"],
+        "legend" => &["fieldset"],
+        "summary" => &["details"],
+        "figcaption" => &["figure"],
+        _ => &[],
+    };
+
+    if !expected_parents.is_empty() && !expected_parents.contains(&parent) {
+        let expected = expected_parents
+            .iter()
+            .map(|parent| format!("`<{parent}>`"))
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        return Some(format!("`<{child}>` must be a child of one of: {expected}"));
+    }
+
+    let reason = match parent {
+        "p" if FLOW_CONTENT.contains(&child) => "`<p>` can only contain phrasing content",
+        "a" | "button" if INTERACTIVE_CONTENT.contains(&child) => {
+            "interactive content can't be nested in `<a>` or `<button>`"
+        }
+        "ul" | "ol" | "menu" if !matches!(child, "li" | "script" | "template") => {
+            "lists can only contain `<li>`"
+        }
+        "form" if child == "form" => "forms can't be nested",
+        _ => return None,
+    };
+
+    Some(reason.to_owned())
+}
+
+/// Elements that can be focused and activated with the keyboard
+pub(crate) fn is_interactive_element(tag: &str) -> bool {
+    matches!(
+        tag.to_ascii_lowercase().as_str(),
+        "a" | "area"
+            | "button"
+            | "details"
+            | "input"
+            | "label"
+            | "option"
+            | "select"
+            | "summary"
+            | "textarea"
+    )
+}
+
+/// Elements labelled by a `<label>` wrapping them
+pub(crate) fn is_labelable_element(tag: &str) -> bool {
+    matches!(
+        tag.to_ascii_lowercase().as_str(),
+        "button" | "input" | "meter" | "output" | "progress" | "select" | "textarea"
+    )
+}
+
+/// Levenshtein distance, for "did you mean" suggestions
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b = b.chars().collect::<Vec<_>>();
+    let mut previous = (0..=b.len()).collect::<Vec<_>>();
+
+    for (i, a_char) in a.chars().enumerate() {
+        let mut current = vec![i + 1; b.len() + 1];
+
+        for (j, b_char) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(a_char != *b_char);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+
+        previous = current;
+    }
+
+    previous[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_known_elements() {
+        assert!(is_known_element("div"));
+        assert!(is_known_element("clipPath"));
+        assert!(is_known_element("mfrac"));
+        assert!(is_known_element("my-widget"));
+        assert!(!is_known_element("buton"));
+        assert_eq!(suggest_element("buton"), Some("button"));
+    }
+
+    #[test]
+    fn test_known_attributes() {
+        assert!(is_known_attribute("div", "class"));
+        assert!(is_known_attribute("div", "data-id"));
+        assert!(is_known_attribute("div", "aria-label"));
+        assert!(is_known_attribute("img", "alt"));
+        assert!(!is_known_attribute("div", "alt"));
+        assert!(!is_known_attribute("div", "clas"));
+        assert_eq!(suggest_attribute("div", "clas"), Some("class"));
+    }
+
+    #[test]
+    fn test_invalid_nesting() {
+        assert!(invalid_nesting("p", "div").is_some());
+        assert!(invalid_nesting("button", "a").is_some());
+        assert!(invalid_nesting("div", "li").is_some());
+        assert!(invalid_nesting("ul", "li").is_none());
+        assert!(invalid_nesting("div", "p").is_none());
+    }
+}
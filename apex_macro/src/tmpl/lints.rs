@@ -0,0 +1,108 @@
+use std::cell::RefCell;
+
+use proc_macro2::{Span, TokenStream};
+use quote::quote_spanned;
+use syn::{Ident, punctuated::Punctuated};
+
+/// Checks tmpl! runs on the template, each can be turned off with `#![allow(name)]`
+/// at the start of the template
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum Lint {
+    /// Tags that are neither HTML, SVG, MathML nor custom elements (error)
+    UnknownElements,
+    /// Attributes not defined for an HTML element (error)
+    UnknownAttributes,
+    /// Elements in a parent the HTML spec doesn't allow them in (error)
+    InvalidNesting,
+    /// Common accessibility issues (warning)
+    A11y,
+}
+
+impl Lint {
+    const ALL: [(Lint, &'static str); 4] = [
+        (Lint::UnknownElements, "unknown_elements"),
+        (Lint::UnknownAttributes, "unknown_attributes"),
+        (Lint::InvalidNesting, "invalid_nesting"),
+        (Lint::A11y, "a11y"),
+    ];
+
+    fn from_name(name: &Ident) -> syn::Result<Lint> {
+        Self::ALL
+            .iter()
+            .find(|(_, lint_name)| name == lint_name)
+            .map(|(lint, _)| *lint)
+            .ok_or_else(|| {
+                let expected = Self::ALL
+                    .iter()
+                    .map(|(_, lint_name)| *lint_name)
+                    .collect::<Vec<_>>()
+                    .join(", ");
+
+                syn::Error::new(
+                    name.span(),
+                    format!("unknown tmpl! lint `{name}`, expected one of: {expected}"),
+                )
+            })
+    }
+}
+
+/// Lints enabled for a template along with the warnings they raised
+#[derive(Debug, Default)]
+pub(crate) struct TmplLints {
+    allowed: Vec<Lint>,
+    warnings: RefCell<Vec<(Span, String)>>,
+}
+
+impl TmplLints {
+    /// Lints from the `#![allow(...)]` inner attributes of the template
+    pub(crate) fn from_attributes(attributes: &[syn::Attribute]) -> syn::Result<Self> {
+        let mut lints = Self::default();
+
+        for attribute in attributes {
+            if !attribute.path().is_ident("allow") {
+                return Err(syn::Error::new_spanned(
+                    attribute,
+                    "only `#![allow(...)]` is supported in templates",
+                ));
+            }
+
+            let names =
+                attribute.parse_args_with(Punctuated::<Ident, syn::Token![,]>::parse_terminated)?;
+
+            for name in names {
+                lints.allowed.push(Lint::from_name(&name)?);
+            }
+        }
+
+        Ok(lints)
+    }
+
+    pub(crate) fn is_enabled(&self, lint: Lint) -> bool {
+        !self.allowed.contains(&lint)
+    }
+
+    pub(crate) fn warn(&self, span: Span, message: String) {
+        self.warnings.borrow_mut().push((span, message));
+    }
+
+    /// Stable Rust has no API for proc macro warnings, so each warning is emitted as the use
+    /// of a deprecated constant, which rustc reports as a warning at the given span
+    pub(crate) fn warning_tokens(&self) -> TokenStream {
+        self.warnings
+            .borrow()
+            .iter()
+            .map(|(span, message)| {
+                let note = format!("tmpl!: {message}");
+
+                quote_spanned! {*span=>
+                    {
+                        #[deprecated(note = #note)]
+                        #[allow(non_upper_case_globals)]
+                        const apex_tmpl_warning: u8 = 0;
+                        let _ = apex_tmpl_warning;
+                    }
+                }
+            })
+            .collect()
+    }
+}
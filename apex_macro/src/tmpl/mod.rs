@@ -1,24 +1,190 @@
+mod html_spec;
+mod lints;
 mod parse_tmpl;
 mod parse_tmpl_into_ast;
 mod render_ast;
+mod tokens_to_source;
 
+use proc_macro2::{Span, TokenStream, TokenTree};
+
+pub(crate) use lints::TmplLints;
 pub(crate) use parse_tmpl::*;
 
+/// Rust expression written in the template. Keeps the original tokens, so the generated
+/// code points back at the template, next to the normalized source used for comparisons.
+#[derive(Debug, Clone)]
+pub(crate) struct TmplExpr {
+    source: String,
+    tokens: TokenStream,
+}
+
+impl TmplExpr {
+    pub(crate) fn new(source: String, tokens: TokenStream) -> Self {
+        Self { source, tokens }
+    }
+
+    pub(crate) fn as_str(&self) -> &str {
+        &self.source
+    }
+
+    /// Parse the original tokens as an expression
+    pub(crate) fn to_expr(&self) -> syn::Result<syn::Expr> {
+        syn::parse2(self.tokens.clone())
+    }
+
+    /// Drop the leading `..` of a `{..attrs}` spread
+    pub(crate) fn strip_spread(self) -> Self {
+        let Some(source) = self.source.strip_prefix("..") else {
+            return self;
+        };
+
+        let tokens = self
+            .tokens
+            .into_iter()
+            .enumerate()
+            .skip_while(|(index, token)| {
+                *index < 2 && matches!(token, TokenTree::Punct(punct) if punct.as_char() == '.')
+            })
+            .map(|(_, token)| token)
+            .collect();
+
+        Self::new(source.trim().to_owned(), tokens)
+    }
+
+    /// Split at the first `keyword` outside of any group or string, as the `in` of
+    /// `{#component widget in [...]}`
+    pub(crate) fn split_at_keyword(&self, keyword: &str) -> Option<(Self, Self)> {
+        let tokens = self.tokens.clone().into_iter().collect::<Vec<_>>();
+        let index = tokens
+            .iter()
+            .position(|token| matches!(token, TokenTree::Ident(ident) if ident == keyword))?;
+        let source_index = find_top_level_word(&self.source, keyword)?;
+        let (before, after) = self.source.split_at(source_index);
+
+        Some((
+            Self::new(
+                before.trim().to_owned(),
+                tokens[..index].iter().cloned().collect(),
+            ),
+            Self::new(
+                after[keyword.len()..].trim().to_owned(),
+                tokens[index + 1..].iter().cloned().collect(),
+            ),
+        ))
+    }
+}
+
+/// Byte index of `word` in `source`, outside of brackets and string literals
+fn find_top_level_word(source: &str, word: &str) -> Option<usize> {
+    let is_ident_char = |ch: char| ch.is_alphanumeric() || ch == '_';
+    let mut depth = 0usize;
+    let mut in_string = false;
+    let mut escaped = false;
+
+    for (index, ch) in source.char_indices() {
+        if in_string {
+            match ch {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => in_string = false,
+                _ => {}
+            }
+
+            continue;
+        }
+
+        match ch {
+            '"' => in_string = true,
+            '(' | '[' | '{' => depth += 1,
+            ')' | ']' | '}' => depth = depth.saturating_sub(1),
+            _ if depth == 0 && source[index..].starts_with(word) => {
+                let before = source[..index].chars().next_back();
+                let after = source[index + word.len()..].chars().next();
+
+                if !before.is_some_and(is_ident_char) && !after.is_some_and(is_ident_char) {
+                    return Some(index);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    None
+}
+
+impl PartialEq for TmplExpr {
+    fn eq(&self, other: &Self) -> bool {
+        self.source == other.source
+    }
+}
+
+impl From<&str> for TmplExpr {
+    fn from(source: &str) -> Self {
+        let source = source.trim().to_owned();
+        let tokens = source.parse().unwrap_or_default();
+
+        Self::new(source, tokens)
+    }
+}
+
+/// Tag name of an element or component, with the span of its opening tag for errors
+/// reported by the generated code
+#[derive(Debug, Clone)]
+pub(crate) struct TmplTag {
+    name: String,
+    span: Span,
+}
+
+impl TmplTag {
+    pub(crate) fn new(name: String, span: Span) -> Self {
+        Self { name, span }
+    }
+
+    pub(crate) fn span(&self) -> Span {
+        self.span
+    }
+}
+
+impl std::ops::Deref for TmplTag {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.name
+    }
+}
+
+impl std::fmt::Display for TmplTag {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.name)
+    }
+}
+
+impl PartialEq for TmplTag {
+    fn eq(&self, other: &Self) -> bool {
+        self.name == other.name
+    }
+}
+
+impl From<&str> for TmplTag {
+    fn from(name: &str) -> Self {
+        Self::new(name.to_owned(), Span::call_site())
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub(crate) enum Attribute {
     Empty,
     Literal(String),
-    Expression(String),
-    EventListener(String),
+    Expression(TmplExpr),
+    EventListener(TmplExpr),
+    /// `{..attrs}` spread of anything implementing `IntoHtmlAttributes`
+    Spread(TmplExpr),
 }
 
 impl Attribute {
     pub(crate) fn push(&mut self, ch: char) {
-        match self {
-            Attribute::Empty => {}
-            Attribute::Literal(s) => s.push(ch),
-            Attribute::Expression(s) => s.push(ch),
-            Attribute::EventListener(s) => s.push(ch),
+        if let Attribute::Literal(s) = self {
+            s.push(ch);
         }
     }
 }
@@ -26,11 +192,11 @@ impl Attribute {
 #[derive(Debug, Clone, PartialEq)]
 pub(crate) enum ConditionalBlock {
     If {
-        condition: String,
+        condition: TmplExpr,
         children: Vec<TmplAst>,
     },
     ElseIf {
-        condition: String,
+        condition: TmplExpr,
         children: Vec<TmplAst>,
     },
     Else {
@@ -43,22 +209,74 @@ pub(crate) type Attributes = std::collections::HashMap<String, Attribute>;
 #[derive(Debug, Clone, PartialEq)]
 pub(crate) enum TmplAst {
     Text(String),
-    Expression(String),
+    Expression(TmplExpr),
     Element {
-        tag: String,
+        tag: TmplTag,
         attributes: Attributes,
         is_component: bool,
         self_closing: bool,
         children: Vec<TmplAst>,
     },
     Slot {
-        name: Option<String>,
+        /// Name of the slot, spanned at its opening tag
+        name: Option<TmplTag>,
+        /// Pattern of `let:`, binding the value a typed slot is rendered with
+        binding: Option<String>,
         children: Vec<TmplAst>,
     },
     SlotInterpolation {
         slot_name: Option<String>,
+        /// Value a typed slot is rendered with, `<#slot row {item} />`
+        props: Option<TmplExpr>,
         default_children: Option<Vec<TmplAst>>,
     },
     ConditionalDirective(Vec<ConditionalBlock>),
+    /// `{#component expr in candidates}`, the component the expression evaluates to at
+    /// runtime, one of the candidates it can switch to on the client
+    DynamicComponent {
+        component: TmplExpr,
+        candidates: Option<TmplExpr>,
+        children: Vec<TmplAst>,
+    },
     Outlet,
+    /// `<!--! ... -->` comment kept in the output
+    Comment(String),
+    /// `<!DOCTYPE ...>` declaration, without the surrounding `<!` and `>`
+    Doctype(String),
+}
+
+/// Elements that never have children or a closing tag
+pub(crate) fn is_void_element(tag: &str) -> bool {
+    matches!(
+        tag.to_ascii_lowercase().as_str(),
+        "area"
+            | "base"
+            | "br"
+            | "col"
+            | "embed"
+            | "hr"
+            | "img"
+            | "input"
+            | "link"
+            | "meta"
+            | "source"
+            | "track"
+            | "wbr"
+    )
+}
+
+/// Elements whose content is raw text: braces are not expressions and whitespace is kept
+pub(crate) fn is_raw_text_element(tag: &str) -> bool {
+    matches!(tag.to_ascii_lowercase().as_str(), "script" | "style")
+}
+
+/// Elements whose content is escapable raw text: it can't hold the expression markers, so
+/// expressions are rendered as plain text and update the element's text content
+pub(crate) fn is_escapable_raw_text_element(tag: &str) -> bool {
+    matches!(tag.to_ascii_lowercase().as_str(), "textarea" | "title")
+}
+
+/// Elements whose whitespace is significant and must not be collapsed
+pub(crate) fn preserves_whitespace(tag: &str) -> bool {
+    matches!(tag.to_ascii_lowercase().as_str(), "pre" | "textarea") || is_raw_text_element(tag)
 }
@@ -1,15 +1,161 @@
+use std::path::Path;
+
 use proc_macro::TokenStream;
 use quote::quote;
+use syn::{Ident, LitStr, parse::ParseStream};
 
-use crate::tmpl::{parse_tmpl_into_ast::*, render_ast::*};
+use crate::tmpl::{
+    ConditionalBlock, TmplAst, lints::TmplLints, parse_tmpl_into_ast::*, render_ast::*,
+    tokens_to_source::*,
+};
 
 pub(crate) fn parse_tmpl(input: TokenStream) -> proc_macro2::TokenStream {
-    let input_str = input.to_string();
-    let parsed_content = parse_tmpl_into_ast(&input_str);
+    let result =
+        parse_lint_attributes(input.into()).and_then(|(lints, input)| {
+            match parse_file_arg(input.clone()) {
+                Some(path) => parse_tmpl_file(&path, lints),
+                None => render_tmpl(tokens_to_source(input).with_lints(lints)),
+            }
+        });
+
+    result.unwrap_or_else(syn::Error::into_compile_error)
+}
+
+/// Split the `#![allow(...)]` attributes at the start of the template from its content
+fn parse_lint_attributes(
+    input: proc_macro2::TokenStream,
+) -> syn::Result<(TmplLints, proc_macro2::TokenStream)> {
+    let parser = |input: ParseStream<'_>| {
+        let attributes = input.call(syn::Attribute::parse_inner)?;
+        let content = input.parse::<proc_macro2::TokenStream>()?;
+
+        Ok((attributes, content))
+    };
+
+    let (attributes, content) = syn::parse::Parser::parse2(parser, input)?;
+
+    Ok((TmplLints::from_attributes(&attributes)?, content))
+}
+
+/// Render a template read from `path`, relative to `CARGO_MANIFEST_DIR`. The file is
+/// tracked, so changing it rebuilds the crate.
+pub(crate) fn parse_tmpl_file(
+    path: &LitStr,
+    lints: TmplLints,
+) -> syn::Result<proc_macro2::TokenStream> {
+    let (full_path, content) = read_tmpl_file(path)?;
+
+    let template =
+        render_tmpl(TmplChars::from_file(&path.value(), &content, path.span()).with_lints(lints))?;
+
+    Ok(quote! {
+        {
+            const _: &str = include_str!(#full_path);
+            #template
+        }
+    })
+}
+
+/// Reads the template at `path`, relative to `CARGO_MANIFEST_DIR`, returning its full path
+/// and its content
+fn read_tmpl_file(path: &LitStr) -> syn::Result<(String, String)> {
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR")
+        .map_err(|_| syn::Error::new(path.span(), "CARGO_MANIFEST_DIR is not set"))?;
+    let full_path = Path::new(&manifest_dir).join(path.value());
+    let content = std::fs::read_to_string(&full_path).map_err(|error| {
+        syn::Error::new(
+            path.span(),
+            format!("failed to read template `{}`: {error}", full_path.display()),
+        )
+    })?;
+
+    Ok((full_path.to_string_lossy().into_owned(), content))
+}
+
+/// Named slots rendered by the `tmpl!` with this input, `<#slot name />`, without the typed
+/// ones, which are `#[slot]` parameters of the component
+pub(crate) fn tmpl_slot_names(input: proc_macro2::TokenStream) -> syn::Result<Vec<String>> {
+    let (_, input) = parse_lint_attributes(input)?;
+
+    match parse_file_arg(input.clone()) {
+        Some(path) => tmpl_file_slot_names(&path),
+        None => Ok(slot_names(&parse_tmpl_into_ast(tokens_to_source(input))?)),
+    }
+}
+
+/// Named slots rendered by the template file at `path`, like [`tmpl_slot_names`]
+pub(crate) fn tmpl_file_slot_names(path: &LitStr) -> syn::Result<Vec<String>> {
+    let (_, content) = read_tmpl_file(path)?;
+    let ast = parse_tmpl_into_ast(TmplChars::from_file(&path.value(), &content, path.span()))?;
+
+    Ok(slot_names(&ast))
+}
+
+fn slot_names(ast: &[TmplAst]) -> Vec<String> {
+    let mut names = Vec::new();
+
+    for node in ast {
+        match node {
+            TmplAst::SlotInterpolation {
+                slot_name,
+                props,
+                default_children,
+            } => {
+                if let (Some(slot_name), None) = (slot_name, props) {
+                    names.push(slot_name.clone());
+                }
+
+                if let Some(default_children) = default_children {
+                    names.extend(slot_names(default_children));
+                }
+            }
+            TmplAst::Element { children, .. }
+            | TmplAst::Slot { children, .. }
+            | TmplAst::DynamicComponent { children, .. } => {
+                names.extend(slot_names(children));
+            }
+            TmplAst::ConditionalDirective(blocks) => {
+                for block in blocks {
+                    let (ConditionalBlock::If { children, .. }
+                    | ConditionalBlock::ElseIf { children, .. }
+                    | ConditionalBlock::Else { children }) = block;
+
+                    names.extend(slot_names(children));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    names
+}
+
+/// `file = "path"` form of `tmpl!`
+fn parse_file_arg(input: proc_macro2::TokenStream) -> Option<LitStr> {
+    let parser = |input: ParseStream<'_>| {
+        let key = input.parse::<Ident>()?;
+
+        if key != "file" {
+            return Err(syn::Error::new(key.span(), "expected `file`"));
+        }
+
+        input.parse::<syn::Token![=]>()?;
+        input.parse::<LitStr>()
+    };
+
+    syn::parse::Parser::parse2(parser, input).ok()
+}
+
+fn render_tmpl(chars: TmplChars) -> syn::Result<proc_macro2::TokenStream> {
+    let lints = chars.lints().clone();
+    let parsed_content = parse_tmpl_into_ast(chars)?;
     let (render_instructions, hydration_expressions) = render_ast(&parsed_content);
+    let warnings = lints.warning_tokens();
 
-    quote! {
+    Ok(quote! {
         {
+            #warnings
+
             #[cfg(not(target_arch = "wasm32"))]
             {
                 let mut buffer = String::with_capacity(1024);
@@ -27,5 +173,5 @@ pub(crate) fn parse_tmpl(input: TokenStream) -> proc_macro2::TokenStream {
                 hydrate
             }
         }
-    }
+    })
 }
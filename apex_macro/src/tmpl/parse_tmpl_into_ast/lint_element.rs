@@ -0,0 +1,142 @@
+use super::tmpl_chars::{TmplChars, TmplSpan};
+
+use crate::tmpl::{
+    Attributes, ConditionalBlock, TmplAst,
+    html_spec::{
+        invalid_nesting, is_html_element, is_interactive_element, is_known_attribute,
+        is_known_element, is_labelable_element, suggest_attribute, suggest_element,
+    },
+    lints::Lint,
+};
+
+/// Validate the tag, attributes and placement of an element against the HTML spec
+pub(crate) fn lint_element(
+    chars: &TmplChars,
+    span: &TmplSpan,
+    parent: Option<&str>,
+    tag: &str,
+    attributes: &Attributes,
+) -> syn::Result<()> {
+    let lints = chars.lints();
+
+    if lints.is_enabled(Lint::UnknownElements) && !is_known_element(tag) {
+        let message = match suggest_element(tag) {
+            Some(suggestion) => {
+                format!("unknown element `<{tag}>`, did you mean `<{suggestion}>`?")
+            }
+            None => format!("unknown element `<{tag}>`, custom elements must contain a hyphen"),
+        };
+
+        return Err(span.error(message));
+    }
+
+    if !is_html_element(tag) {
+        return Ok(());
+    }
+
+    if lints.is_enabled(Lint::UnknownAttributes) {
+        let mut names = attributes
+            .keys()
+            .filter(|name| !is_template_attribute(name))
+            .collect::<Vec<_>>();
+        names.sort();
+
+        for name in names {
+            if is_known_attribute(tag, name) {
+                continue;
+            }
+
+            let message = match suggest_attribute(tag, name) {
+                Some(suggestion) => {
+                    format!("unknown attribute `{name}` on `<{tag}>`, did you mean `{suggestion}`?")
+                }
+                None => format!("unknown attribute `{name}` on `<{tag}>`"),
+            };
+
+            return Err(span.error(message));
+        }
+    }
+
+    if lints.is_enabled(Lint::InvalidNesting) {
+        if let Some(parent) = parent.filter(|parent| is_html_element(parent)) {
+            if let Some(reason) = invalid_nesting(parent, tag) {
+                return Err(span.error(format!(
+                    "`<{tag}>` can't be a child of `<{parent}>`: {reason}"
+                )));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Warn about common accessibility issues once the children of the element are known
+pub(crate) fn lint_element_a11y(
+    chars: &TmplChars,
+    span: &TmplSpan,
+    tag: &str,
+    attributes: &Attributes,
+    children: &[TmplAst],
+) {
+    let lints = chars.lints();
+
+    // A spread may provide any attribute, so there's nothing to tell
+    if !lints.is_enabled(Lint::A11y)
+        || !is_html_element(tag)
+        || attributes.keys().any(|name| name.starts_with(".."))
+    {
+        return;
+    }
+
+    let tag = tag.to_ascii_lowercase();
+    let has_attribute = |name: &str| attributes.contains_key(name);
+
+    if tag == "img" && !has_attribute("alt") {
+        lints.warn(
+            span.span(),
+            span.message(
+                "`<img>` is missing an `alt` attribute, use `alt=\"\"` for decorative images",
+            ),
+        );
+    }
+
+    if tag == "label" && !has_attribute("for") && !contains_labelable_element(children) {
+        lints.warn(
+            span.span(),
+            span.message("`<label>` has no `for` attribute and doesn't wrap a form control"),
+        );
+    }
+
+    if has_attribute("onclick") && !is_interactive_element(&tag) && !has_attribute("role") {
+        lints.warn(
+            span.span(),
+            span.message(format!(
+                "`<{tag}>` has a click handler but isn't keyboard accessible, use a `<button>` or add `role` and `tabindex`"
+            )),
+        );
+    }
+}
+
+/// Directives and spreads handled by tmpl! itself rather than rendered as is
+fn is_template_attribute(name: &str) -> bool {
+    name.starts_with("..") || name.starts_with("class:") || name.starts_with("style:")
+}
+
+fn contains_labelable_element(children: &[TmplAst]) -> bool {
+    children.iter().any(|child| match child {
+        // Components and slots may render a form control
+        TmplAst::Element {
+            tag,
+            children,
+            is_component,
+            ..
+        } => *is_component || is_labelable_element(tag) || contains_labelable_element(children),
+        TmplAst::SlotInterpolation { .. } => true,
+        TmplAst::ConditionalDirective(blocks) => blocks.iter().any(|block| match block {
+            ConditionalBlock::If { children, .. }
+            | ConditionalBlock::ElseIf { children, .. }
+            | ConditionalBlock::Else { children } => contains_labelable_element(children),
+        }),
+        _ => false,
+    })
+}
@@ -1,6 +1,6 @@
-use std::str::Chars;
+use super::tmpl_chars::TmplChars;
 
-pub(crate) fn match_chars(chars: &mut std::iter::Peekable<Chars<'_>>, end_of_block: &str) -> bool {
+pub(crate) fn match_chars(chars: &mut TmplChars, end_of_block: &str) -> bool {
     let mut end_of_block_chars = end_of_block.chars().peekable();
 
     if chars.peek() == end_of_block_chars.peek() {
@@ -19,7 +19,7 @@ pub(crate) fn match_chars(chars: &mut std::iter::Peekable<Chars<'_>>, end_of_blo
 
         if matched {
             // Consume the characters from the original iterator
-            for _ in 0..end_of_block.len() {
+            for _ in 0..end_of_block.chars().count() {
                 chars.next();
             }
 
@@ -36,21 +36,21 @@ mod tests {
 
     #[test]
     fn test_match_closing_element_tag() {
-        let mut chars = "</div>".chars().peekable();
+        let mut chars = TmplChars::from("</div>");
         assert!(match_chars(&mut chars, "</div>"));
         assert_eq!(chars.peek(), None);
     }
 
     #[test]
     fn test_match_closing_slot_tag() {
-        let mut chars = "</#header>".chars().peekable();
+        let mut chars = TmplChars::from("</#header>");
         assert!(match_chars(&mut chars, "</#header>"));
         assert_eq!(chars.peek(), None);
     }
 
     #[test]
     fn test_match_closing_directive_tag() {
-        let mut chars = "<#endif>".chars().peekable();
+        let mut chars = TmplChars::from("<#endif>");
         assert!(match_chars(&mut chars, "<#endif>"));
         assert_eq!(chars.peek(), None);
     }
@@ -1,41 +1,117 @@
 use crate::tmpl::TmplAst;
-use regex::Regex;
 
 mod is_pascal_case;
+mod lint_element;
 mod match_chars;
+mod parse_comment;
+mod parse_component_directive;
 mod parse_conditional_directive;
 mod parse_directive_name;
 mod parse_directive_params;
+mod parse_doctype;
 mod parse_element_opening_tag;
+mod parse_raw_text;
 mod parse_slot_interpolation;
 mod parse_slot_name;
 mod process_chars_until;
+mod tmpl_chars;
 
 use process_chars_until::*;
+pub(crate) use tmpl_chars::{SourceChar, TmplChars};
 
-pub(crate) fn parse_tmpl_into_ast(input: &str) -> Vec<TmplAst> {
-    // Normalize input: remove line breaks and reduce all whitespace to a single space
-    let input = input
-        .replace(['\n', '\r'], " ")
-        .split_whitespace()
-        .collect::<Vec<_>>()
-        .join(" ")
-        .trim()
-        .to_owned();
-
-    // Use regex to trim whitespace from expressions in curly braces
-    let re = Regex::new(r"\{([^}]*)\}").unwrap();
-    let input = re
-        .replace_all(&input, |caps: &regex::Captures<'_>| {
-            let expression = caps.get(1).map(|m| m.as_str().trim()).unwrap_or("");
-            format!("{{{expression}}}")
-        })
-        .to_string();
-
-    let mut chars = input.chars().peekable();
-    let (ast, _) = process_chars_until(&mut chars, None);
-
-    ast
+pub(crate) fn parse_tmpl_into_ast(input: TmplChars) -> syn::Result<Vec<TmplAst>> {
+    let mut chars = input.map_chars(normalize_whitespace);
+    let (ast, _) = process_chars_until(&mut chars, None)?;
+
+    Ok(ast)
+}
+
+/// Reduce all whitespace to a single space and trim expressions in curly braces, leaving
+/// the content of `<pre>`, `<textarea>`, `<script>` and `<style>` untouched
+fn normalize_whitespace(input: Vec<SourceChar>) -> Vec<SourceChar> {
+    let chars = input
+        .iter()
+        .map(|source_char| source_char.ch)
+        .collect::<Vec<_>>();
+    let mut output = Vec::with_capacity(input.len());
+
+    let mut start = 0;
+    let mut end = chars.len();
+
+    while start < end && chars[start].is_whitespace() {
+        start += 1;
+    }
+
+    while end > start && chars[end - 1].is_whitespace() {
+        end -= 1;
+    }
+
+    let mut part_start = start;
+    let mut i = start;
+
+    while i < end {
+        if let Some((tag_end, content_end)) = find_preserved_content(&chars[..end], i) {
+            normalize_part(&input[part_start..tag_end], &mut output);
+            output.extend_from_slice(&input[tag_end..content_end]);
+            part_start = content_end;
+            i = content_end;
+        } else {
+            i += 1;
+        }
+    }
+
+    normalize_part(&input[part_start..end], &mut output);
+
+    output
+}
+
+/// When a `<pre>`, `<textarea>`, `<script>` or `<style>` opening tag starts at `i`, returns
+/// where its content starts and ends
+fn find_preserved_content(chars: &[char], i: usize) -> Option<(usize, usize)> {
+    if chars[i] != '<' {
+        return None;
+    }
+
+    let tag = ["pre", "textarea", "script", "style"]
+        .into_iter()
+        .find(|tag| {
+            let after = i + 1 + tag.len();
+
+            after < chars.len()
+                && chars[i + 1..after].iter().copied().eq(tag.chars())
+                && (chars[after] == '>' || chars[after].is_whitespace())
+        })?;
+
+    let tag_end = (i..chars.len()).find(|&j| chars[j] == '>')? + 1;
+    let closing_tag = format!("</{tag}>").chars().collect::<Vec<_>>();
+    let content_end = (tag_end..chars.len())
+        .find(|&j| chars[j..].starts_with(&closing_tag))
+        .unwrap_or(chars.len());
+
+    Some((tag_end, content_end))
+}
+
+fn normalize_part(part: &[SourceChar], output: &mut Vec<SourceChar>) {
+    for &source_char in part {
+        let last = output.last().map(|last| last.ch);
+
+        if source_char.ch.is_whitespace() {
+            if !matches!(last, Some(' ' | '{')) {
+                output.push(SourceChar {
+                    ch: ' ',
+                    ..source_char
+                });
+            }
+        } else {
+            if source_char.ch == '}' {
+                while output.last().is_some_and(|last| last.ch == ' ') {
+                    output.pop();
+                }
+            }
+
+            output.push(source_char);
+        }
+    }
 }
 
 #[cfg(test)]
@@ -47,20 +123,20 @@ mod tests {
     #[test]
     fn whitespace_normalization() {
         let input = "  <div>  Hello  </div>  <span>  World  </span>  ";
-        let ast = parse_tmpl_into_ast(input);
+        let ast = parse_tmpl_into_ast(TmplChars::from(input)).unwrap();
 
         assert_eq!(
             ast,
             vec![
                 TmplAst::Element {
-                    tag: "div".to_owned(),
+                    tag: "div".into(),
                     attributes: HashMap::new(),
                     is_component: false,
                     self_closing: false,
                     children: vec![TmplAst::Text("Hello".to_owned())],
                 },
                 TmplAst::Element {
-                    tag: "span".to_owned(),
+                    tag: "span".into(),
                     attributes: HashMap::new(),
                     is_component: false,
                     self_closing: false,
@@ -73,12 +149,12 @@ mod tests {
     #[test]
     fn newline_removal() {
         let input = "<div>\nHello,\nworld!\n</div>";
-        let ast = parse_tmpl_into_ast(input);
+        let ast = parse_tmpl_into_ast(TmplChars::from(input)).unwrap();
 
         assert_eq!(
             ast,
             vec![TmplAst::Element {
-                tag: "div".to_owned(),
+                tag: "div".into(),
                 attributes: HashMap::new(),
                 is_component: false,
                 self_closing: false,
@@ -90,7 +166,7 @@ mod tests {
     #[test]
     fn empty_input() {
         let input = "";
-        let ast = parse_tmpl_into_ast(input);
+        let ast = parse_tmpl_into_ast(TmplChars::from(input)).unwrap();
 
         assert_eq!(ast, vec![]);
     }
@@ -98,7 +174,7 @@ mod tests {
     #[test]
     fn whitespace_only_input() {
         let input = "   \n\t  ";
-        let ast = parse_tmpl_into_ast(input);
+        let ast = parse_tmpl_into_ast(TmplChars::from(input)).unwrap();
 
         assert_eq!(ast, vec![]);
     }
@@ -117,12 +193,12 @@ mod tests {
             </div>
         "#;
 
-        let ast = parse_tmpl_into_ast(input);
+        let ast = parse_tmpl_into_ast(TmplChars::from(input)).unwrap();
 
         assert_eq!(
             ast,
             vec![TmplAst::Element {
-                tag: "div".to_owned(),
+                tag: "div".into(),
                 attributes: HashMap::from([(
                     "class".to_owned(),
                     Attribute::Literal("container".to_owned())
@@ -131,32 +207,32 @@ mod tests {
                 self_closing: false,
                 children: vec![
                     TmplAst::Element {
-                        tag: "h1".to_owned(),
+                        tag: "h1".into(),
                         attributes: HashMap::new(),
                         is_component: false,
                         self_closing: false,
                         children: vec![
                             TmplAst::Text("Welcome, ".to_owned()),
-                            TmplAst::Expression("user.name".to_owned()),
+                            TmplAst::Expression("user.name".into()),
                             TmplAst::Text("!".to_owned()),
                         ],
                     },
                     TmplAst::Element {
-                        tag: "p".to_owned(),
+                        tag: "p".into(),
                         attributes: HashMap::new(),
                         is_component: false,
                         self_closing: false,
                         children: vec![
                             TmplAst::Text("You have ".to_owned()),
-                            TmplAst::Expression("message_count".to_owned()),
+                            TmplAst::Expression("message_count".into()),
                             TmplAst::Text(" new messages.".to_owned()),
                         ],
                     },
                     TmplAst::Element {
-                        tag: "button".to_owned(),
+                        tag: "button".into(),
                         attributes: HashMap::from([(
                             "onclick".to_owned(),
-                            Attribute::EventListener("handle_click".to_owned())
+                            Attribute::EventListener("handle_click".into())
                         )]),
                         is_component: false,
                         self_closing: false,
@@ -184,23 +260,24 @@ mod tests {
             </UserProfile>
         "#;
 
-        let ast = parse_tmpl_into_ast(input);
+        let ast = parse_tmpl_into_ast(TmplChars::from(input)).unwrap();
 
         assert_eq!(
             ast,
             vec![TmplAst::Element {
-                tag: "UserProfile".to_owned(),
+                tag: "UserProfile".into(),
                 attributes: HashMap::from([(
                     "user".to_owned(),
-                    Attribute::Expression("current_user".to_owned())
+                    Attribute::Expression("current_user".into())
                 )]),
                 is_component: true,
                 self_closing: false,
                 children: vec![
                     TmplAst::Slot {
-                        name: Some("header".to_owned()),
+                        name: Some("header".into()),
+                        binding: None,
                         children: vec![TmplAst::Element {
-                            tag: "h2".to_owned(),
+                            tag: "h2".into(),
                             attributes: HashMap::new(),
                             is_component: false,
                             self_closing: false,
@@ -208,37 +285,39 @@ mod tests {
                         },],
                     },
                     TmplAst::Slot {
-                        name: Some("content".to_owned()),
+                        name: Some("content".into()),
+                        binding: None,
                         children: vec![
                             TmplAst::Element {
-                                tag: "p".to_owned(),
+                                tag: "p".into(),
                                 attributes: HashMap::new(),
                                 is_component: false,
                                 self_closing: false,
                                 children: vec![
                                     TmplAst::Text("Email: ".to_owned()),
-                                    TmplAst::Expression("current_user.email".to_owned()),
+                                    TmplAst::Expression("current_user.email".into()),
                                 ],
                             },
                             TmplAst::Element {
-                                tag: "p".to_owned(),
+                                tag: "p".into(),
                                 attributes: HashMap::new(),
                                 is_component: false,
                                 self_closing: false,
                                 children: vec![
                                     TmplAst::Text("Member since: ".to_owned()),
-                                    TmplAst::Expression("current_user.join_date".to_owned()),
+                                    TmplAst::Expression("current_user.join_date".into()),
                                 ],
                             },
                         ],
                     },
                     TmplAst::Slot {
-                        name: Some("footer".to_owned()),
+                        name: Some("footer".into()),
+                        binding: None,
                         children: vec![TmplAst::Element {
-                            tag: "button".to_owned(),
+                            tag: "button".into(),
                             attributes: HashMap::from([(
                                 "onclick".to_owned(),
-                                Attribute::EventListener("edit_profile".to_owned())
+                                Attribute::EventListener("edit_profile".into())
                             )]),
                             is_component: false,
                             self_closing: false,
@@ -260,19 +339,19 @@ mod tests {
             </div>
         "#;
 
-        let ast = parse_tmpl_into_ast(input);
+        let ast = parse_tmpl_into_ast(TmplChars::from(input)).unwrap();
 
         assert_eq!(
             ast,
             vec![TmplAst::Element {
-                tag: "div".to_owned(),
+                tag: "div".into(),
                 attributes: HashMap::new(),
                 is_component: false,
                 self_closing: false,
                 children: vec![TmplAst::ConditionalDirective(vec![ConditionalBlock::If {
-                    condition: "true".to_owned(),
+                    condition: "true".into(),
                     children: vec![TmplAst::Element {
-                        tag: "span".to_owned(),
+                        tag: "span".into(),
                         attributes: HashMap::new(),
                         is_component: false,
                         self_closing: false,
@@ -302,12 +381,12 @@ mod tests {
             </div>
         "#;
 
-        let ast = parse_tmpl_into_ast(input);
+        let ast = parse_tmpl_into_ast(TmplChars::from(input)).unwrap();
 
         assert_eq!(
             ast,
             vec![TmplAst::Element {
-                tag: "div".to_owned(),
+                tag: "div".into(),
                 attributes: HashMap::from([(
                     "class".to_owned(),
                     Attribute::Literal("dashboard".to_owned())
@@ -316,9 +395,9 @@ mod tests {
                 self_closing: false,
                 children: vec![
                     TmplAst::ConditionalDirective(vec![ConditionalBlock::If {
-                        condition: "user.is_admin".to_owned(),
+                        condition: "user.is_admin".into(),
                         children: vec![TmplAst::Element {
-                            tag: "div".to_owned(),
+                            tag: "div".into(),
                             attributes: HashMap::from([(
                                 "class".to_owned(),
                                 Attribute::Literal("admin-panel".to_owned())
@@ -327,17 +406,17 @@ mod tests {
                             self_closing: false,
                             children: vec![
                                 TmplAst::Element {
-                                    tag: "h3".to_owned(),
+                                    tag: "h3".into(),
                                     attributes: HashMap::new(),
                                     is_component: false,
                                     self_closing: false,
                                     children: vec![TmplAst::Text("Admin Controls".to_owned())],
                                 },
                                 TmplAst::Element {
-                                    tag: "button".to_owned(),
+                                    tag: "button".into(),
                                     attributes: HashMap::from([(
                                         "onclick".to_owned(),
-                                        Attribute::EventListener("delete_user".to_owned())
+                                        Attribute::EventListener("delete_user".into())
                                     )]),
                                     is_component: false,
                                     self_closing: false,
@@ -347,9 +426,9 @@ mod tests {
                         },],
                     }]),
                     TmplAst::ConditionalDirective(vec![ConditionalBlock::If {
-                        condition: "user.has_notifications".to_owned(),
+                        condition: "user.has_notifications".into(),
                         children: vec![TmplAst::Element {
-                            tag: "div".to_owned(),
+                            tag: "div".into(),
                             attributes: HashMap::from([(
                                 "class".to_owned(),
                                 Attribute::Literal("notifications".to_owned())
@@ -357,13 +436,13 @@ mod tests {
                             is_component: false,
                             self_closing: false,
                             children: vec![TmplAst::Element {
-                                tag: "p".to_owned(),
+                                tag: "p".into(),
                                 attributes: HashMap::new(),
                                 is_component: false,
                                 self_closing: false,
                                 children: vec![
                                     TmplAst::Text("You have ".to_owned()),
-                                    TmplAst::Expression("notification_count".to_owned()),
+                                    TmplAst::Expression("notification_count".into()),
                                     TmplAst::Text(" notifications".to_owned()),
                                 ],
                             },],
@@ -377,19 +456,19 @@ mod tests {
     #[test]
     fn trimming_whitespace_in_directives() {
         let input = "<div>{#if true}<span>Hello, world!</span>{/if}</div>";
-        let ast = parse_tmpl_into_ast(input);
+        let ast = parse_tmpl_into_ast(TmplChars::from(input)).unwrap();
 
         assert_eq!(
             ast,
             vec![TmplAst::Element {
-                tag: "div".to_owned(),
+                tag: "div".into(),
                 attributes: HashMap::new(),
                 is_component: false,
                 self_closing: false,
                 children: vec![TmplAst::ConditionalDirective(vec![ConditionalBlock::If {
-                    condition: "true".to_owned(),
+                    condition: "true".into(),
                     children: vec![TmplAst::Element {
-                        tag: "span".to_owned(),
+                        tag: "span".into(),
                         attributes: HashMap::new(),
                         is_component: false,
                         self_closing: false,
@@ -403,16 +482,16 @@ mod tests {
     #[test]
     fn expression_whitespace_trimming() {
         let input = "<div> { user.name } </div>";
-        let ast = parse_tmpl_into_ast(input);
+        let ast = parse_tmpl_into_ast(TmplChars::from(input)).unwrap();
 
         assert_eq!(
             ast,
             vec![TmplAst::Element {
-                tag: "div".to_owned(),
+                tag: "div".into(),
                 attributes: HashMap::new(),
                 is_component: false,
                 self_closing: false,
-                children: vec![TmplAst::Expression("user.name".to_owned())],
+                children: vec![TmplAst::Expression("user.name".into())],
             }]
         );
     }
@@ -420,12 +499,12 @@ mod tests {
     #[test]
     fn outlet_directive() {
         let input = "<div>{#outlet}</div>";
-        let ast = parse_tmpl_into_ast(input);
+        let ast = parse_tmpl_into_ast(TmplChars::from(input)).unwrap();
 
         assert_eq!(
             ast,
             vec![TmplAst::Element {
-                tag: "div".to_owned(),
+                tag: "div".into(),
                 attributes: HashMap::new(),
                 is_component: false,
                 self_closing: false,
@@ -437,12 +516,12 @@ mod tests {
     #[test]
     fn outlet_directive_with_whitespace() {
         let input = "<div> {#outlet} </div>";
-        let ast = parse_tmpl_into_ast(input);
+        let ast = parse_tmpl_into_ast(TmplChars::from(input)).unwrap();
 
         assert_eq!(
             ast,
             vec![TmplAst::Element {
-                tag: "div".to_owned(),
+                tag: "div".into(),
                 attributes: HashMap::new(),
                 is_component: false,
                 self_closing: false,
@@ -467,23 +546,23 @@ mod tests {
             </html>
         "#;
 
-        let ast = parse_tmpl_into_ast(input);
+        let ast = parse_tmpl_into_ast(TmplChars::from(input)).unwrap();
 
         assert_eq!(
             ast,
             vec![TmplAst::Element {
-                tag: "html".to_owned(),
+                tag: "html".into(),
                 attributes: HashMap::new(),
                 is_component: false,
                 self_closing: false,
                 children: vec![
                     TmplAst::Element {
-                        tag: "head".to_owned(),
+                        tag: "head".into(),
                         attributes: HashMap::new(),
                         is_component: false,
                         self_closing: false,
                         children: vec![TmplAst::Element {
-                            tag: "title".to_owned(),
+                            tag: "title".into(),
                             attributes: HashMap::new(),
                             is_component: false,
                             self_closing: false,
@@ -491,20 +570,20 @@ mod tests {
                         }],
                     },
                     TmplAst::Element {
-                        tag: "body".to_owned(),
+                        tag: "body".into(),
                         attributes: HashMap::new(),
                         is_component: false,
                         self_closing: false,
                         children: vec![
                             TmplAst::Element {
-                                tag: "nav".to_owned(),
+                                tag: "nav".into(),
                                 attributes: HashMap::new(),
                                 is_component: false,
                                 self_closing: false,
                                 children: vec![TmplAst::Text("Navigation".to_owned())],
                             },
                             TmplAst::Element {
-                                tag: "main".to_owned(),
+                                tag: "main".into(),
                                 attributes: HashMap::new(),
                                 is_component: false,
                                 self_closing: false,
@@ -529,20 +608,20 @@ mod tests {
             </div>
         "#;
 
-        let ast = parse_tmpl_into_ast(input);
+        let ast = parse_tmpl_into_ast(TmplChars::from(input)).unwrap();
 
         assert_eq!(
             ast,
             vec![TmplAst::Element {
-                tag: "div".to_owned(),
+                tag: "div".into(),
                 attributes: HashMap::new(),
                 is_component: false,
                 self_closing: false,
                 children: vec![TmplAst::ConditionalDirective(vec![
                     ConditionalBlock::If {
-                        condition: "user.is_authenticated".to_owned(),
+                        condition: "user.is_authenticated".into(),
                         children: vec![TmplAst::Element {
-                            tag: "span".to_owned(),
+                            tag: "span".into(),
                             attributes: HashMap::new(),
                             is_component: false,
                             self_closing: false,
@@ -551,7 +630,7 @@ mod tests {
                     },
                     ConditionalBlock::Else {
                         children: vec![TmplAst::Element {
-                            tag: "span".to_owned(),
+                            tag: "span".into(),
                             attributes: HashMap::new(),
                             is_component: false,
                             self_closing: false,
@@ -577,20 +656,20 @@ mod tests {
             </div>
         "#;
 
-        let ast = parse_tmpl_into_ast(input);
+        let ast = parse_tmpl_into_ast(TmplChars::from(input)).unwrap();
 
         assert_eq!(
             ast,
             vec![TmplAst::Element {
-                tag: "div".to_owned(),
+                tag: "div".into(),
                 attributes: HashMap::new(),
                 is_component: false,
                 self_closing: false,
                 children: vec![TmplAst::ConditionalDirective(vec![
                     ConditionalBlock::If {
-                        condition: "user.role == \"admin\"".to_owned(),
+                        condition: "user.role == \"admin\"".into(),
                         children: vec![TmplAst::Element {
-                            tag: "span".to_owned(),
+                            tag: "span".into(),
                             attributes: HashMap::new(),
                             is_component: false,
                             self_closing: false,
@@ -598,9 +677,9 @@ mod tests {
                         }],
                     },
                     ConditionalBlock::ElseIf {
-                        condition: "user.role == \"moderator\"".to_owned(),
+                        condition: "user.role == \"moderator\"".into(),
                         children: vec![TmplAst::Element {
-                            tag: "span".to_owned(),
+                            tag: "span".into(),
                             attributes: HashMap::new(),
                             is_component: false,
                             self_closing: false,
@@ -609,7 +688,7 @@ mod tests {
                     },
                     ConditionalBlock::Else {
                         children: vec![TmplAst::Element {
-                            tag: "span".to_owned(),
+                            tag: "span".into(),
                             attributes: HashMap::new(),
                             is_component: false,
                             self_closing: false,
@@ -640,20 +719,20 @@ mod tests {
             </div>
         "#;
 
-        let ast = parse_tmpl_into_ast(input);
+        let ast = parse_tmpl_into_ast(TmplChars::from(input)).unwrap();
 
         assert_eq!(
             ast,
             vec![TmplAst::Element {
-                tag: "div".to_owned(),
+                tag: "div".into(),
                 attributes: HashMap::new(),
                 is_component: false,
                 self_closing: false,
                 children: vec![TmplAst::ConditionalDirective(vec![
                     ConditionalBlock::If {
-                        condition: "user.is_authenticated".to_owned(),
+                        condition: "user.is_authenticated".into(),
                         children: vec![TmplAst::Element {
-                            tag: "div".to_owned(),
+                            tag: "div".into(),
                             attributes: HashMap::from([(
                                 "class".to_owned(),
                                 Attribute::Literal("user-area".to_owned())
@@ -663,13 +742,13 @@ mod tests {
                             children: vec![
                                 TmplAst::ConditionalDirective(vec![
                                     ConditionalBlock::If {
-                                        condition: "user.has_avatar".to_owned(),
+                                        condition: "user.has_avatar".into(),
                                         children: vec![TmplAst::Element {
-                                            tag: "img".to_owned(),
+                                            tag: "img".into(),
                                             attributes: HashMap::from([
                                                 (
                                                     "src".to_owned(),
-                                                    Attribute::Expression("user.avatar".to_owned())
+                                                    Attribute::Expression("user.avatar".into())
                                                 ),
                                                 (
                                                     "alt".to_owned(),
@@ -683,7 +762,7 @@ mod tests {
                                     },
                                     ConditionalBlock::Else {
                                         children: vec![TmplAst::Element {
-                                            tag: "div".to_owned(),
+                                            tag: "div".into(),
                                             attributes: HashMap::from([(
                                                 "class".to_owned(),
                                                 Attribute::Literal("default-avatar".to_owned())
@@ -695,21 +774,21 @@ mod tests {
                                     },
                                 ]),
                                 TmplAst::Element {
-                                    tag: "span".to_owned(),
+                                    tag: "span".into(),
                                     attributes: HashMap::new(),
                                     is_component: false,
                                     self_closing: false,
-                                    children: vec![TmplAst::Expression("user.name".to_owned())],
+                                    children: vec![TmplAst::Expression("user.name".into())],
                                 },
                             ],
                         }],
                     },
                     ConditionalBlock::Else {
                         children: vec![TmplAst::Element {
-                            tag: "button".to_owned(),
+                            tag: "button".into(),
                             attributes: HashMap::from([(
                                 "onclick".to_owned(),
-                                Attribute::EventListener("show_login".to_owned())
+                                Attribute::EventListener("show_login".into())
                             )]),
                             is_component: false,
                             self_closing: false,
@@ -724,14 +803,65 @@ mod tests {
     #[test]
     fn unnamed_slot_interpolation() {
         let input = "<#slot>Hello, world!</#slot>";
-        let ast = parse_tmpl_into_ast(input);
+        let ast = parse_tmpl_into_ast(TmplChars::from(input)).unwrap();
 
         assert_eq!(
             ast,
             vec![TmplAst::SlotInterpolation {
                 slot_name: None,
+                props: None,
                 default_children: Some(vec![TmplAst::Text("Hello, world!".to_owned())]),
             }]
         );
     }
+
+    #[test]
+    fn whitespace_preserved_in_pre() {
+        let input = "<div>\n  <pre>  a\n    b  </pre>\n  <input value={ x }>\n</div>";
+        let ast = parse_tmpl_into_ast(TmplChars::from(input)).unwrap();
+
+        assert_eq!(
+            ast,
+            vec![TmplAst::Element {
+                tag: "div".into(),
+                attributes: HashMap::new(),
+                is_component: false,
+                self_closing: false,
+                children: vec![
+                    TmplAst::Element {
+                        tag: "pre".into(),
+                        attributes: HashMap::new(),
+                        is_component: false,
+                        self_closing: false,
+                        children: vec![TmplAst::Text("  a\n    b  ".to_owned())],
+                    },
+                    TmplAst::Element {
+                        tag: "input".into(),
+                        attributes: HashMap::from([(
+                            "value".to_owned(),
+                            Attribute::Expression("x".into())
+                        )]),
+                        is_component: false,
+                        self_closing: true,
+                        children: vec![],
+                    },
+                ],
+            }]
+        );
+    }
+
+    #[test]
+    fn errors_in_template_files_report_the_file_location() {
+        let chars = TmplChars::from_file(
+            "card.html",
+            "<section>\n  <p>Hello</span>\n</section>",
+            proc_macro2::Span::call_site(),
+        );
+        let error = parse_tmpl_into_ast(chars).unwrap_err();
+
+        assert_eq!(
+            error.to_string(),
+            "mismatched closing tag `</span>`, expected `</p>`\n --> card.html:2:11"
+        );
+    }
 }
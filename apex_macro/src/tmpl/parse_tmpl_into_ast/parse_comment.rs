@@ -0,0 +1,64 @@
+use super::tmpl_chars::TmplChars;
+
+use crate::tmpl::TmplAst;
+
+use super::match_chars::match_chars;
+
+/// Parse an HTML comment. Comments are stripped from the output unless they start with
+/// `<!--!`, which is rendered as a regular `<!-- ... -->` comment.
+pub(crate) fn parse_comment(chars: &mut TmplChars) -> syn::Result<Option<TmplAst>> {
+    let opening_span = chars.span();
+
+    if !match_chars(chars, "<!--") {
+        return Err(chars.error("expected '<!--'"));
+    }
+
+    let mut content = String::new();
+
+    loop {
+        if match_chars(chars, "-->") {
+            break;
+        }
+
+        let Some(ch) = chars.next() else {
+            return Err(opening_span.error("unclosed comment, expected '-->'"));
+        };
+
+        content.push(ch);
+    }
+
+    Ok(content
+        .strip_prefix('!')
+        .map(|content| TmplAst::Comment(content.trim().to_owned())))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stripped_comment() {
+        let mut chars = TmplChars::from("<!-- a comment -->rest");
+
+        assert_eq!(parse_comment(&mut chars).unwrap(), None);
+        assert_eq!(chars.collect::<String>(), "rest");
+    }
+
+    #[test]
+    fn preserved_comment() {
+        let mut chars = TmplChars::from("<!--! keep me -->");
+
+        assert_eq!(
+            parse_comment(&mut chars).unwrap(),
+            Some(TmplAst::Comment("keep me".to_owned()))
+        );
+    }
+
+    #[test]
+    fn unclosed_comment() {
+        let mut chars = TmplChars::from("<!-- never closed");
+        let error = parse_comment(&mut chars).unwrap_err();
+
+        assert_eq!(error.to_string(), "unclosed comment, expected '-->'");
+    }
+}
@@ -0,0 +1,115 @@
+use crate::tmpl::TmplAst;
+
+use super::parse_directive_params::parse_directive_params;
+use super::process_chars_until::process_chars_until;
+use super::tmpl_chars::TmplChars;
+
+/// Parse `{#component expr in candidates}...{/component}`, the content being the children
+/// and named slots given to the component. The candidates are the components the
+/// expression can switch to, rendered ahead so the browser can swap them in.
+pub(crate) fn parse_component_directive(chars: &mut TmplChars) -> syn::Result<TmplAst> {
+    let opening_span = chars.span();
+    let params = parse_directive_params(chars)?;
+
+    let (component, candidates) = match params.split_at_keyword("in") {
+        Some((component, candidates)) => (component, Some(candidates)),
+        None => (params, None),
+    };
+
+    for expr in std::iter::once(&component).chain(&candidates) {
+        if let Err(error) = expr.to_expr() {
+            return Err(
+                opening_span.error(format!("invalid component `{}`: {error}", expr.as_str()))
+            );
+        }
+    }
+
+    let (children, exit) = process_chars_until(chars, Some(&["{/component}"]))?;
+
+    if exit != "{/component}" {
+        return Err(
+            opening_span.error("unclosed `{#component}` directive, expected `{/component}`")
+        );
+    }
+
+    Ok(TmplAst::DynamicComponent {
+        component,
+        candidates,
+        children,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn component_directive() {
+        let mut chars = TmplChars::from("widget.get()}<p>Hello</p>{/component}");
+        let ast = parse_component_directive(&mut chars).unwrap();
+
+        assert_eq!(
+            ast,
+            TmplAst::DynamicComponent {
+                component: "widget.get()".into(),
+                candidates: None,
+                children: vec![TmplAst::Element {
+                    tag: "p".into(),
+                    attributes: Default::default(),
+                    is_component: false,
+                    self_closing: false,
+                    children: vec![TmplAst::Text("Hello".to_owned())],
+                }],
+            }
+        );
+    }
+
+    #[test]
+    fn component_directive_with_candidates() {
+        let mut chars =
+            TmplChars::from(r#"widget.get() in [Badge::dynamic(), label("in")]}{/component}"#);
+        let ast = parse_component_directive(&mut chars).unwrap();
+
+        assert_eq!(
+            ast,
+            TmplAst::DynamicComponent {
+                component: "widget.get()".into(),
+                candidates: Some(r#"[Badge::dynamic(), label("in")]"#.into()),
+                children: vec![],
+            }
+        );
+
+        let TmplAst::DynamicComponent {
+            candidates: Some(candidates),
+            ..
+        } = ast
+        else {
+            unreachable!();
+        };
+
+        assert!(candidates.to_expr().is_ok());
+    }
+
+    #[test]
+    fn unclosed_component_directive() {
+        let mut chars = TmplChars::from("widget}<p>Hello</p>");
+        let error = parse_component_directive(&mut chars).unwrap_err();
+
+        assert_eq!(
+            error.to_string(),
+            "unclosed `{#component}` directive, expected `{/component}`"
+        );
+    }
+
+    #[test]
+    fn invalid_component_expression() {
+        let mut chars = TmplChars::from("widget +}{/component}");
+        let error = parse_component_directive(&mut chars).unwrap_err();
+
+        assert!(
+            error
+                .to_string()
+                .starts_with("invalid component `widget +`")
+        );
+    }
+}
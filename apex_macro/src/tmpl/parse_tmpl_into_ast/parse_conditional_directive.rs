@@ -1,15 +1,15 @@
-use std::str::Chars;
-
-use crate::tmpl::ConditionalBlock;
+use crate::tmpl::{ConditionalBlock, TmplExpr};
 
 use super::parse_directive_params::parse_directive_params;
 use super::process_chars_until::process_chars_until;
+use super::tmpl_chars::{TmplChars, TmplSpan};
 
 pub(crate) fn parse_conditional_directive(
-    chars: &mut std::iter::Peekable<Chars<'_>>,
-) -> Vec<ConditionalBlock> {
+    chars: &mut TmplChars,
+) -> syn::Result<Vec<ConditionalBlock>> {
     let mut conditional_blocks = Vec::new();
-    let directive_params = parse_directive_params(chars);
+    let opening_span = chars.span();
+    let directive_params = parse_condition(chars)?;
 
     let (block, exit) = process_chars_until(
         chars,
@@ -18,7 +18,7 @@ pub(crate) fn parse_conditional_directive(
             "{:else}",
             "{:else if", // No closing '}', bc there're params to parse
         ]),
-    );
+    )?;
 
     // First block is always an "if" block
     conditional_blocks.push(ConditionalBlock::If {
@@ -27,16 +27,17 @@ pub(crate) fn parse_conditional_directive(
     });
 
     if exit == "{/if}" {
-        return conditional_blocks;
+        return Ok(conditional_blocks);
     } else if exit == "{:else}" {
-        let (block, _) = process_chars_until(chars, Some(&["{/if}"]));
+        let (block, exit) = process_chars_until(chars, Some(&["{/if}"]))?;
+        expect_end_of_if(&exit, &opening_span)?;
 
         conditional_blocks.push(ConditionalBlock::Else { children: block });
     } else if exit == "{:else if" {
         // Parse the else-if condition
-        let else_if_condition = parse_directive_params(chars);
+        let else_if_condition = parse_condition(chars)?;
 
-        let (block, exit) = process_chars_until(chars, Some(&["{/if}", "{:else}", "{:else if"]));
+        let (block, exit) = process_chars_until(chars, Some(&["{/if}", "{:else}", "{:else if"]))?;
 
         conditional_blocks.push(ConditionalBlock::ElseIf {
             condition: else_if_condition,
@@ -47,29 +48,34 @@ pub(crate) fn parse_conditional_directive(
         if exit != "{/if}" {
             // Put back the exit token for recursive parsing
             let remaining_blocks = if exit == "{:else}" {
-                let (block, _) = process_chars_until(chars, Some(&["{/if}"]));
+                let (block, exit) = process_chars_until(chars, Some(&["{/if}"]))?;
+                expect_end_of_if(&exit, &opening_span)?;
+
                 vec![ConditionalBlock::Else { children: block }]
             } else if exit == "{:else if" {
-                parse_conditional_directive_continuation(chars)
+                parse_conditional_directive_continuation(chars, &opening_span)?
             } else {
-                Vec::new()
+                return Err(unclosed_if(&opening_span));
             };
 
             conditional_blocks.extend(remaining_blocks);
         }
+    } else {
+        return Err(unclosed_if(&opening_span));
     }
 
-    conditional_blocks
+    Ok(conditional_blocks)
 }
 
 // Helper function to parse continuation of else-if/else blocks
 fn parse_conditional_directive_continuation(
-    chars: &mut std::iter::Peekable<Chars<'_>>,
-) -> Vec<ConditionalBlock> {
+    chars: &mut TmplChars,
+    opening_span: &TmplSpan,
+) -> syn::Result<Vec<ConditionalBlock>> {
     let mut blocks = Vec::new();
-    let condition = parse_directive_params(chars);
+    let condition = parse_condition(chars)?;
 
-    let (block, exit) = process_chars_until(chars, Some(&["{/if}", "{:else}", "{:else if"]));
+    let (block, exit) = process_chars_until(chars, Some(&["{/if}", "{:else}", "{:else if"]))?;
 
     blocks.push(ConditionalBlock::ElseIf {
         condition,
@@ -77,13 +83,47 @@ fn parse_conditional_directive_continuation(
     });
 
     if exit == "{:else}" {
-        let (block, _) = process_chars_until(chars, Some(&["{/if}"]));
+        let (block, exit) = process_chars_until(chars, Some(&["{/if}"]))?;
+        expect_end_of_if(&exit, opening_span)?;
+
         blocks.push(ConditionalBlock::Else { children: block });
     } else if exit == "{:else if" {
-        blocks.extend(parse_conditional_directive_continuation(chars));
+        blocks.extend(parse_conditional_directive_continuation(
+            chars,
+            opening_span,
+        )?);
+    } else if exit != "{/if}" {
+        return Err(unclosed_if(opening_span));
+    }
+
+    Ok(blocks)
+}
+
+/// Parse the condition of `{#if ...}`/`{:else if ...}`, making sure it is a valid expression
+fn parse_condition(chars: &mut TmplChars) -> syn::Result<TmplExpr> {
+    let span = chars.span();
+    let condition = parse_directive_params(chars)?;
+
+    if let Err(error) = condition.to_expr() {
+        return Err(span.error(format!(
+            "invalid condition `{}`: {error}",
+            condition.as_str()
+        )));
+    }
+
+    Ok(condition)
+}
+
+fn expect_end_of_if(exit: &str, opening_span: &TmplSpan) -> syn::Result<()> {
+    if exit == "{/if}" {
+        Ok(())
+    } else {
+        Err(unclosed_if(opening_span))
     }
+}
 
-    blocks
+fn unclosed_if(opening_span: &TmplSpan) -> syn::Error {
+    opening_span.error("unclosed `{#if}` directive, expected `{/if}`")
 }
 
 #[cfg(test)]
@@ -93,15 +133,26 @@ mod tests {
 
     #[test]
     fn test_parse_conditional_directive() {
-        let mut chars = "true}Hello, world!{/if}".chars().peekable();
-        let conditional_blocks = parse_conditional_directive(&mut chars);
+        let mut chars = TmplChars::from("true}Hello, world!{/if}");
+        let conditional_blocks = parse_conditional_directive(&mut chars).unwrap();
 
         assert_eq!(
             conditional_blocks,
             vec![ConditionalBlock::If {
-                condition: "true".to_owned(),
+                condition: "true".into(),
                 children: vec![TmplAst::Text("Hello, world!".to_owned())],
             }]
         );
     }
+
+    #[test]
+    fn test_unclosed_conditional_directive() {
+        let mut chars = TmplChars::from("true}Hello, world!");
+        let error = parse_conditional_directive(&mut chars).unwrap_err();
+
+        assert_eq!(
+            error.to_string(),
+            "unclosed `{#if}` directive, expected `{/if}`"
+        );
+    }
 }
@@ -1,6 +1,6 @@
-use std::str::Chars;
+use super::tmpl_chars::TmplChars;
 
-pub(crate) fn parse_directive_name(chars: &mut std::iter::Peekable<Chars<'_>>) -> String {
+pub(crate) fn parse_directive_name(chars: &mut TmplChars) -> String {
     let mut name = String::new();
 
     while let Some(&c) = chars.peek() {
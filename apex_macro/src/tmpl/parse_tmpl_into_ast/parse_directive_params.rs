@@ -1,15 +1,11 @@
-use std::str::Chars;
+use super::tmpl_chars::TmplChars;
 
-pub(crate) fn parse_directive_params(chars: &mut std::iter::Peekable<Chars<'_>>) -> String {
-    let mut params = String::new();
+use crate::tmpl::TmplExpr;
 
-    for c in chars.by_ref() {
-        if c == '}' {
-            break;
-        }
+pub(crate) fn parse_directive_params(chars: &mut TmplChars) -> syn::Result<TmplExpr> {
+    let span = chars.span();
 
-        params.push(c);
-    }
-
-    params.trim().to_owned()
+    chars
+        .take_until_closing_brace()
+        .ok_or_else(|| span.error("unclosed directive, expected '}'"))
 }
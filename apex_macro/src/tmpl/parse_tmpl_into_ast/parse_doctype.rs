@@ -0,0 +1,44 @@
+use super::tmpl_chars::TmplChars;
+
+use crate::tmpl::TmplAst;
+
+/// Parse a `<!DOCTYPE ...>` declaration
+pub(crate) fn parse_doctype(chars: &mut TmplChars) -> syn::Result<TmplAst> {
+    let opening_span = chars.span();
+
+    if chars.next() != Some('<') || chars.next() != Some('!') {
+        return Err(opening_span.error("expected '<!' at the start of a doctype"));
+    }
+
+    let mut content = String::new();
+
+    loop {
+        match chars.next() {
+            Some('>') => break,
+            Some(ch) => content.push(ch),
+            None => {
+                return Err(opening_span.error("unclosed doctype, expected '>'"));
+            }
+        }
+    }
+
+    let content = content.split_whitespace().collect::<Vec<_>>().join(" ");
+
+    Ok(TmplAst::Doctype(content))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn html5_doctype() {
+        let mut chars = TmplChars::from("<!DOCTYPE html><html></html>");
+
+        assert_eq!(
+            parse_doctype(&mut chars).unwrap(),
+            TmplAst::Doctype("DOCTYPE html".to_owned())
+        );
+        assert_eq!(chars.collect::<String>(), "<html></html>");
+    }
+}
@@ -1,6 +1,6 @@
-use std::str::Chars;
+use super::tmpl_chars::{TmplChars, TmplSpan};
 
-use crate::tmpl::{Attribute, Attributes};
+use crate::tmpl::{Attribute, Attributes, TmplExpr};
 
 #[derive(PartialEq)]
 enum ElementOpeningTagState {
@@ -14,32 +14,69 @@ fn insert_attribute(
     element_attrs: &mut Attributes,
     attribute_name: &mut String,
     attribute_value: &mut Attribute,
-) {
+    attribute_span: &TmplSpan,
+) -> syn::Result<()> {
     if !attribute_name.is_empty() {
+        // `class:active` is shorthand for `class:active={active}`
+        if *attribute_value == Attribute::Empty {
+            if let Some(name) = attribute_name.strip_prefix("class:") {
+                if syn::parse_str::<syn::Ident>(name).is_ok() {
+                    let ident = syn::Ident::new(name, attribute_span.span());
+                    *attribute_value = Attribute::Expression(TmplExpr::new(
+                        name.to_owned(),
+                        quote::quote!(#ident),
+                    ));
+                }
+            }
+        }
+
+        if let Attribute::Expression(expr)
+        | Attribute::EventListener(expr)
+        | Attribute::Spread(expr) = attribute_value
+        {
+            if let Err(error) = expr.to_expr() {
+                return Err(attribute_span.error(format!(
+                    "invalid expression in attribute `{attribute_name}`: {error}"
+                )));
+            }
+        }
+
         element_attrs.insert(attribute_name.clone(), attribute_value.clone());
         *attribute_name = String::new();
         *attribute_value = Attribute::Empty;
     }
+
+    Ok(())
 }
 
 pub(crate) fn parse_element_opening_tag(
-    chars: &mut std::iter::Peekable<Chars<'_>>,
-) -> (String, Attributes, bool) {
+    chars: &mut TmplChars,
+) -> syn::Result<(String, Attributes, bool)> {
+    let opening_span = chars.span();
     let mut state = ElementOpeningTagState::ElementName;
     let mut element_name = String::new();
     let mut element_attrs = Attributes::new();
     let mut attribute_name = String::new();
     let mut attribute_value = Attribute::Empty;
     let mut is_self_closing = false;
-    let mut expression_nesting_level = 0;
+    let mut spread_counter = 0;
+    let mut attribute_span = opening_span.clone();
 
     if chars.peek() == Some(&'<') {
         chars.next(); // consume '<'
     } else {
-        panic!("Unexpected character: {:?}, expected '<'", chars.peek());
+        return Err(chars.error(format!(
+            "unexpected character: {:?}, expected '<'",
+            chars.peek()
+        )));
     }
 
-    for ch in chars.by_ref() {
+    loop {
+        let span = chars.span();
+        let Some(ch) = chars.next() else {
+            return Err(opening_span.error(format!("unclosed tag `<{element_name}`, expected '>'")));
+        };
+
         if is_self_closing && ch != '>' {
             continue;
         }
@@ -49,7 +86,8 @@ pub(crate) fn parse_element_opening_tag(
                 &mut element_attrs,
                 &mut attribute_name,
                 &mut attribute_value,
-            );
+                &attribute_span,
+            )?;
 
             break;
         } else if ch == '/' {
@@ -61,7 +99,8 @@ pub(crate) fn parse_element_opening_tag(
                     &mut element_attrs,
                     &mut attribute_name,
                     &mut attribute_value,
-                );
+                    &attribute_span,
+                )?;
             }
         } else if ch == ' ' {
             match state {
@@ -70,12 +109,12 @@ pub(crate) fn parse_element_opening_tag(
                     state = ElementOpeningTagState::AttributeName;
                 }
                 ElementOpeningTagState::AttributeName => {
-                    state = ElementOpeningTagState::AttributeValue;
                     insert_attribute(
                         &mut element_attrs,
                         &mut attribute_name,
                         &mut attribute_value,
-                    );
+                        &attribute_span,
+                    )?;
                 }
                 ElementOpeningTagState::AttributeValue => {
                     attribute_value.push(ch);
@@ -91,7 +130,9 @@ pub(crate) fn parse_element_opening_tag(
                     attribute_value.push(ch);
                 }
                 ElementOpeningTagState::ElementName => {
-                    panic!("Unexpected character: {ch}, is not allowed in element name");
+                    return Err(span.error(format!(
+                        "unexpected character: {ch}, is not allowed in element name"
+                    )));
                 }
             }
         } else if ch == '"' {
@@ -103,7 +144,8 @@ pub(crate) fn parse_element_opening_tag(
                             &mut element_attrs,
                             &mut attribute_name,
                             &mut attribute_value,
-                        );
+                            &attribute_span,
+                        )?;
                     }
                     Attribute::Empty => {
                         attribute_value = Attribute::Literal(String::new());
@@ -114,57 +156,61 @@ pub(crate) fn parse_element_opening_tag(
                 }
             }
         } else if ch == '{' {
-            if state == ElementOpeningTagState::AttributeValue {
-                match &attribute_value {
-                    Attribute::Expression(_) | Attribute::EventListener(_) => {
-                        expression_nesting_level += 1;
-                        attribute_value.push(ch);
-                    }
-                    Attribute::Empty => {
-                        if attribute_name.starts_with("on") {
-                            attribute_value = Attribute::EventListener(String::new());
-                        } else {
-                            attribute_value = Attribute::Expression(String::new());
-                        }
-                    }
-                    Attribute::Literal(_) => {
-                        attribute_value.push(ch);
-                    }
+            let is_spread = matches!(
+                state,
+                ElementOpeningTagState::AttributeName | ElementOpeningTagState::Void
+            ) && attribute_name.is_empty();
+
+            if is_spread || state == ElementOpeningTagState::AttributeValue {
+                if let Attribute::Literal(_) = attribute_value {
+                    attribute_value.push(ch);
+                    continue;
                 }
+
+                let Some(expr) = chars.take_until_closing_brace() else {
+                    return Err(span.error("unclosed expression, expected '}'"));
+                };
+
+                if is_spread {
+                    // `{..attrs}` spread, keyed by its position so several spreads can coexist
+                    attribute_span = span;
+                    attribute_name = format!("..{spread_counter}");
+                    attribute_value = Attribute::Spread(expr.strip_spread());
+                    spread_counter += 1;
+                } else if attribute_name.starts_with("on") {
+                    attribute_value = Attribute::EventListener(expr);
+                } else {
+                    attribute_value = Attribute::Expression(expr);
+                }
+
+                state = ElementOpeningTagState::Void;
+                insert_attribute(
+                    &mut element_attrs,
+                    &mut attribute_name,
+                    &mut attribute_value,
+                    &attribute_span,
+                )?;
             }
         } else if ch == '}' {
-            if state == ElementOpeningTagState::AttributeValue {
-                match &attribute_value {
-                    Attribute::Expression(_) | Attribute::EventListener(_) => {
-                        if expression_nesting_level == 0 {
-                            state = ElementOpeningTagState::Void;
-                            insert_attribute(
-                                &mut element_attrs,
-                                &mut attribute_name,
-                                &mut attribute_value,
-                            );
-                        } else {
-                            attribute_value.push(ch);
-                            expression_nesting_level -= 1;
-                        }
-                    }
-                    _ => {
-                        attribute_value.push(ch);
-                    }
-                }
+            if let Attribute::Literal(_) = attribute_value {
+                attribute_value.push(ch);
             }
         } else if state == ElementOpeningTagState::ElementName {
             element_name.push(ch);
         } else if state == ElementOpeningTagState::AttributeName {
+            if attribute_name.is_empty() {
+                attribute_span = span;
+            }
+
             attribute_name.push(ch);
         } else if state == ElementOpeningTagState::AttributeValue {
             attribute_value.push(ch);
         } else {
-            panic!("Unexpected character: {ch}");
+            return Err(span.error(format!("unexpected character: {ch}")));
         }
     }
 
-    (element_name, element_attrs, is_self_closing)
+    Ok((element_name, element_attrs, is_self_closing))
 }
 
 #[cfg(test)]
@@ -173,8 +219,9 @@ mod tests {
 
     #[test]
     fn test_simple_tag() {
-        let mut chars = "<div>".chars().peekable();
-        let (element_name, element_attrs, is_self_closing) = parse_element_opening_tag(&mut chars);
+        let mut chars = TmplChars::from("<div>");
+        let (element_name, element_attrs, is_self_closing) =
+            parse_element_opening_tag(&mut chars).unwrap();
 
         assert_eq!(element_name, "div");
         assert_eq!(element_attrs, Attributes::new());
@@ -183,8 +230,9 @@ mod tests {
 
     #[test]
     fn test_self_closing_tag() {
-        let mut chars = "<div />".chars().peekable();
-        let (element_name, element_attrs, is_self_closing) = parse_element_opening_tag(&mut chars);
+        let mut chars = TmplChars::from("<div />");
+        let (element_name, element_attrs, is_self_closing) =
+            parse_element_opening_tag(&mut chars).unwrap();
 
         assert_eq!(element_name, "div");
         assert_eq!(element_attrs, Attributes::new());
@@ -193,8 +241,9 @@ mod tests {
 
     #[test]
     fn test_tag_with_one_literal_attribute() {
-        let mut chars = "<div class=\"container\">".chars().peekable();
-        let (element_name, element_attrs, is_self_closing) = parse_element_opening_tag(&mut chars);
+        let mut chars = TmplChars::from("<div class=\"container\">");
+        let (element_name, element_attrs, is_self_closing) =
+            parse_element_opening_tag(&mut chars).unwrap();
 
         assert_eq!(element_name, "div");
         assert_eq!(
@@ -209,11 +258,10 @@ mod tests {
 
     #[test]
     fn tag_with_one_literal_attribute_and_one_expression_attribute() {
-        let mut chars = "<div class=\"container\" onclick={handle_click}>"
-            .chars()
-            .peekable();
+        let mut chars = TmplChars::from("<div class=\"container\" onclick={handle_click}>");
 
-        let (element_name, element_attrs, is_self_closing) = parse_element_opening_tag(&mut chars);
+        let (element_name, element_attrs, is_self_closing) =
+            parse_element_opening_tag(&mut chars).unwrap();
 
         assert_eq!(element_name, "div");
         assert_eq!(
@@ -225,7 +273,7 @@ mod tests {
                 ),
                 (
                     "onclick".to_owned(),
-                    Attribute::EventListener("handle_click".to_owned())
+                    Attribute::EventListener("handle_click".into())
                 )
             ])
         );
@@ -234,8 +282,9 @@ mod tests {
 
     #[test]
     fn test_tag_with_path_attribute() {
-        let mut chars = "<a href=\"/path\">".chars().peekable();
-        let (element_name, element_attrs, is_self_closing) = parse_element_opening_tag(&mut chars);
+        let mut chars = TmplChars::from("<a href=\"/path\">");
+        let (element_name, element_attrs, is_self_closing) =
+            parse_element_opening_tag(&mut chars).unwrap();
 
         assert_eq!(element_name, "a");
         assert_eq!(
@@ -247,17 +296,64 @@ mod tests {
 
     #[test]
     fn tag_with_expression_attribute() {
-        let mut chars = "<div data-test={1 + 1}></div>".chars().peekable();
-        let (element_name, element_attrs, is_self_closing) = parse_element_opening_tag(&mut chars);
+        let mut chars = TmplChars::from("<div data-test={1 + 1}></div>");
+        let (element_name, element_attrs, is_self_closing) =
+            parse_element_opening_tag(&mut chars).unwrap();
 
         assert_eq!(element_name, "div");
         assert_eq!(
             element_attrs,
             Attributes::from([(
                 "data-test".to_owned(),
-                Attribute::Expression("1 + 1".to_owned())
+                Attribute::Expression("1 + 1".into())
             )])
         );
         assert!(!is_self_closing);
     }
+
+    #[test]
+    fn tag_with_class_and_style_directives() {
+        let mut chars =
+            TmplChars::from("<div class:active={is_active} class:wide style:color={color}></div>");
+        let (element_name, element_attrs, is_self_closing) =
+            parse_element_opening_tag(&mut chars).unwrap();
+
+        assert_eq!(element_name, "div");
+        assert_eq!(
+            element_attrs,
+            Attributes::from([
+                (
+                    "class:active".to_owned(),
+                    Attribute::Expression("is_active".into())
+                ),
+                (
+                    "class:wide".to_owned(),
+                    Attribute::Expression("wide".into())
+                ),
+                (
+                    "style:color".to_owned(),
+                    Attribute::Expression("color".into())
+                ),
+            ])
+        );
+        assert!(!is_self_closing);
+    }
+
+    #[test]
+    fn tag_with_spread_attributes() {
+        let mut chars = TmplChars::from("<div {..attrs} id=\"test\" {.. extra.clone()}></div>");
+        let (element_name, element_attrs, is_self_closing) =
+            parse_element_opening_tag(&mut chars).unwrap();
+
+        assert_eq!(element_name, "div");
+        assert_eq!(
+            element_attrs,
+            Attributes::from([
+                ("..0".to_owned(), Attribute::Spread("attrs".into())),
+                ("id".to_owned(), Attribute::Literal("test".to_owned())),
+                ("..1".to_owned(), Attribute::Spread("extra.clone()".into())),
+            ])
+        );
+        assert!(!is_self_closing);
+    }
 }
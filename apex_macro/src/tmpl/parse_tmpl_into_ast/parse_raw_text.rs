@@ -0,0 +1,85 @@
+use super::tmpl_chars::TmplChars;
+
+use crate::tmpl::TmplAst;
+
+use super::match_chars::match_chars;
+
+/// Parse the raw text content of `<script>`/`<style>` up to the closing tag.
+/// Braces are kept as-is; `#{expr}` interpolates an expression.
+pub(crate) fn parse_raw_text(
+    chars: &mut TmplChars,
+    closing_tag: &str,
+) -> syn::Result<Vec<TmplAst>> {
+    let opening_span = chars.span();
+    let mut ast = Vec::new();
+    let mut text = String::new();
+
+    loop {
+        if match_chars(chars, closing_tag) {
+            break;
+        }
+
+        if match_chars(chars, "#{") {
+            if !text.is_empty() {
+                ast.push(TmplAst::Text(std::mem::take(&mut text)));
+            }
+
+            let expression_span = chars.span();
+            let Some(expression) = chars.take_until_closing_brace() else {
+                return Err(expression_span.error("unclosed expression, expected '}'"));
+            };
+
+            if let Err(error) = expression.to_expr() {
+                return Err(expression_span.error(format!(
+                    "invalid expression `{}`: {error}",
+                    expression.as_str()
+                )));
+            }
+
+            ast.push(TmplAst::Expression(expression));
+            continue;
+        }
+
+        let Some(ch) = chars.next() else {
+            return Err(opening_span.error(format!("unclosed element, expected `{closing_tag}`")));
+        };
+
+        text.push(ch);
+    }
+
+    if !text.is_empty() {
+        ast.push(TmplAst::Text(text));
+    }
+
+    Ok(ast)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn braces_are_raw_text() {
+        let mut chars = TmplChars::from("function f() { return 1; }</script>");
+
+        assert_eq!(
+            parse_raw_text(&mut chars, "</script>").unwrap(),
+            vec![TmplAst::Text("function f() { return 1; }".to_owned())]
+        );
+    }
+
+    #[test]
+    fn expression_escape() {
+        let mut chars =
+            TmplChars::from("const config = #{ config_json }; if (a) { b(); }</script>");
+
+        assert_eq!(
+            parse_raw_text(&mut chars, "</script>").unwrap(),
+            vec![
+                TmplAst::Text("const config = ".to_owned()),
+                TmplAst::Expression("config_json".into()),
+                TmplAst::Text("; if (a) { b(); }".to_owned()),
+            ]
+        );
+    }
+}
@@ -1,9 +1,11 @@
 use crate::tmpl::TmplAst;
-use std::str::Chars;
 
 use super::process_chars_until::process_chars_until;
+use super::tmpl_chars::TmplChars;
+
+pub(crate) fn parse_slot_interpolation(chars: &mut TmplChars) -> syn::Result<TmplAst> {
+    let opening_span = chars.span();
 
-pub(crate) fn parse_slot_interpolation(chars: &mut std::iter::Peekable<Chars<'_>>) -> TmplAst {
     // At this point, we've already determined this is a slot interpolation starting with <#slot
 
     // Consume "<#slot"
@@ -22,12 +24,12 @@ pub(crate) fn parse_slot_interpolation(chars: &mut std::iter::Peekable<Chars<'_>
     let mut slot_name = None;
 
     // Check if there's a slot name after "slot"
-    if chars.peek() != Some(&'>') && chars.peek() != Some(&'/') {
+    if chars.peek() != Some(&'>') && chars.peek() != Some(&'/') && chars.peek() != Some(&'{') {
         let mut name = String::new();
 
         // Parse slot name until we hit '>' or '/>' or whitespace
         while let Some(ch) = chars.peek() {
-            if *ch == '>' || *ch == '/' || ch.is_whitespace() {
+            if *ch == '>' || *ch == '/' || *ch == '{' || ch.is_whitespace() {
                 break;
             }
             name.push(chars.next().unwrap());
@@ -43,6 +45,28 @@ pub(crate) fn parse_slot_interpolation(chars: &mut std::iter::Peekable<Chars<'_>
         chars.next();
     }
 
+    // Value a typed slot is rendered with
+    let mut props = None;
+
+    if chars.peek() == Some(&'{') {
+        let span = chars.span();
+        chars.next(); // consume '{'
+
+        let Some(expr) = chars.take_until_closing_brace() else {
+            return Err(span.error("unclosed expression, expected '}'"));
+        };
+
+        if slot_name.is_none() {
+            return Err(span.error("only named slots can be rendered with a value"));
+        }
+
+        props = Some(expr);
+
+        while chars.peek() == Some(&' ') || chars.peek() == Some(&'\t') {
+            chars.next();
+        }
+    }
+
     // Check if it's self-closing
     let is_self_closing = if chars.peek() == Some(&'/') {
         chars.next(); // consume '/'
@@ -50,13 +74,13 @@ pub(crate) fn parse_slot_interpolation(chars: &mut std::iter::Peekable<Chars<'_>
             chars.next(); // consume '>'
             true
         } else {
-            panic!("Expected '>' after '/' in self-closing slot tag");
+            return Err(chars.error("expected '>' after '/' in self-closing slot tag"));
         }
     } else if chars.peek() == Some(&'>') {
         chars.next(); // consume '>'
         false
     } else {
-        panic!("Expected '>' or '/>' in slot tag");
+        return Err(chars.error("expected '>' or '/>' in slot tag"));
     };
 
     let default_children = if is_self_closing {
@@ -67,7 +91,11 @@ pub(crate) fn parse_slot_interpolation(chars: &mut std::iter::Peekable<Chars<'_>
     } else {
         // Parse children until we find the closing tag
         let closing_tag = "</#slot>";
-        let (children, _) = process_chars_until(chars, Some(&[closing_tag]));
+        let (children, matched_end_of_block) = process_chars_until(chars, Some(&[closing_tag]))?;
+
+        if matched_end_of_block.is_empty() {
+            return Err(opening_span.error("unclosed slot, expected `</#slot>`"));
+        }
 
         if children.is_empty() {
             None
@@ -76,10 +104,17 @@ pub(crate) fn parse_slot_interpolation(chars: &mut std::iter::Peekable<Chars<'_>
         }
     };
 
-    TmplAst::SlotInterpolation {
+    if props.is_some() && default_children.is_some() {
+        return Err(opening_span.error(
+            "slots rendered with a value can't have default content, use `#[slot(default)]`",
+        ));
+    }
+
+    Ok(TmplAst::SlotInterpolation {
         slot_name,
+        props,
         default_children,
-    }
+    })
 }
 
 #[cfg(test)]
@@ -89,13 +124,14 @@ mod tests {
 
     #[test]
     fn test_self_closing_unnamed_slot() {
-        let mut chars = "<#slot />".chars().peekable();
-        let ast = parse_slot_interpolation(&mut chars);
+        let mut chars = TmplChars::from("<#slot />");
+        let ast = parse_slot_interpolation(&mut chars).unwrap();
 
         assert_eq!(
             ast,
             TmplAst::SlotInterpolation {
                 slot_name: None,
+                props: None,
                 default_children: None,
             }
         );
@@ -103,13 +139,14 @@ mod tests {
 
     #[test]
     fn test_self_closing_named_slot() {
-        let mut chars = "<#slot my_slot />".chars().peekable();
-        let ast = parse_slot_interpolation(&mut chars);
+        let mut chars = TmplChars::from("<#slot my_slot />");
+        let ast = parse_slot_interpolation(&mut chars).unwrap();
 
         assert_eq!(
             ast,
             TmplAst::SlotInterpolation {
                 slot_name: Some("my_slot".to_owned()),
+                props: None,
                 default_children: None,
             }
         );
@@ -117,15 +154,41 @@ mod tests {
 
     #[test]
     fn test_slot_with_default_children() {
-        let mut chars = "<#slot>Hello, world!</#slot>".chars().peekable();
-        let ast = parse_slot_interpolation(&mut chars);
+        let mut chars = TmplChars::from("<#slot>Hello, world!</#slot>");
+        let ast = parse_slot_interpolation(&mut chars).unwrap();
 
         assert_eq!(
             ast,
             TmplAst::SlotInterpolation {
                 slot_name: None,
+                props: None,
                 default_children: Some(vec![TmplAst::Text("Hello, world!".to_owned())]),
             }
         );
     }
+
+    #[test]
+    fn test_named_slot_with_props() {
+        let mut chars = TmplChars::from("<#slot row {items[index]} />");
+        let ast = parse_slot_interpolation(&mut chars).unwrap();
+
+        assert_eq!(
+            ast,
+            TmplAst::SlotInterpolation {
+                slot_name: Some("row".to_owned()),
+                props: Some("items[index]".into()),
+                default_children: None,
+            }
+        );
+    }
+
+    #[test]
+    fn test_unnamed_slot_with_props_should_fail() {
+        let error = parse_slot_interpolation(&mut TmplChars::from("<#slot {item} />")).unwrap_err();
+
+        assert_eq!(
+            error.to_string(),
+            "only named slots can be rendered with a value"
+        );
+    }
 }
@@ -1,16 +1,23 @@
-use std::str::Chars;
+use super::match_chars::match_chars;
+use super::tmpl_chars::TmplChars;
 
 fn is_valid_slot_name_char(ch: char) -> bool {
     ch.is_alphanumeric() || ch == '_'
 }
 
-pub(crate) fn parse_slot_name(chars: &mut std::iter::Peekable<Chars<'_>>) -> String {
+/// Parse a slot tag, returning its name and the pattern of its `let:` binding, which
+/// receives the value a typed slot is rendered with, as in `<#row let:item>`
+pub(crate) fn parse_slot_name(chars: &mut TmplChars) -> syn::Result<(String, Option<String>)> {
     let mut slot_name = String::new();
+    let mut binding = None;
 
     if chars.peek() == Some(&'<') {
         chars.next(); // consume '<'
     } else {
-        panic!("Unexpected character: {:?}, expected '<'", chars.peek());
+        return Err(chars.error(format!(
+            "unexpected character: {:?}, expected '<'",
+            chars.peek()
+        )));
     }
 
     if chars.peek() == Some(&'/') {
@@ -20,10 +27,18 @@ pub(crate) fn parse_slot_name(chars: &mut std::iter::Peekable<Chars<'_>>) -> Str
     if chars.peek() == Some(&'#') {
         chars.next(); // consume '#'
     } else {
-        panic!("Unexpected character: {:?}, expected '#'", chars.peek());
+        return Err(chars.error(format!(
+            "unexpected character: {:?}, expected '#'",
+            chars.peek()
+        )));
     }
 
-    while let Some(ch) = chars.next() {
+    loop {
+        let span = chars.span();
+        let Some(ch) = chars.next() else {
+            return Err(chars.error("unclosed slot tag, expected '>'"));
+        };
+
         if ch == '>' {
             break;
         }
@@ -33,16 +48,43 @@ pub(crate) fn parse_slot_name(chars: &mut std::iter::Peekable<Chars<'_>>) -> Str
             break;
         }
 
+        if ch == ' ' && match_chars(chars, "let:") {
+            binding = Some(parse_slot_binding(chars)?);
+            continue;
+        }
+
         if ch != ' ' && !is_valid_slot_name_char(ch) {
-            panic!(
-                "Invalid slot name character: {ch}, slot name must contain only alphanumeric or '_'"
-            );
+            return Err(span.error(format!(
+                "invalid slot name character: {ch}, slot name must contain only alphanumeric or '_'"
+            )));
         }
 
         slot_name.push(ch);
     }
 
-    slot_name
+    Ok((slot_name.trim_end().to_owned(), binding))
+}
+
+/// Pattern after `let:`, up to the end of the tag
+fn parse_slot_binding(chars: &mut TmplChars) -> syn::Result<String> {
+    let span = chars.span();
+    let mut binding = String::new();
+
+    while let Some(&ch) = chars.peek() {
+        if ch == '>' || ch == '/' {
+            break;
+        }
+
+        binding.push(ch);
+        chars.next();
+    }
+
+    let binding = binding.trim().to_owned();
+
+    syn::parse::Parser::parse_str(syn::Pat::parse_single, &binding)
+        .map_err(|error| span.error(format!("invalid slot binding `let:{binding}`: {error}")))?;
+
+    Ok(binding)
 }
 
 #[cfg(test)]
@@ -51,33 +93,69 @@ mod tests {
 
     #[test]
     fn test_parse_slot_name() {
-        let mut chars = "<#header>".chars().peekable();
-        assert_eq!(parse_slot_name(&mut chars), "header");
+        let mut chars = TmplChars::from("<#header>");
+        assert_eq!(
+            parse_slot_name(&mut chars).unwrap(),
+            ("header".to_owned(), None)
+        );
     }
 
     #[test]
     fn test_parse_slot_name_closing_tag() {
-        let mut chars = "</#header>".chars().peekable();
-        assert_eq!(parse_slot_name(&mut chars), "header");
+        let mut chars = TmplChars::from("</#header>");
+        assert_eq!(
+            parse_slot_name(&mut chars).unwrap(),
+            ("header".to_owned(), None)
+        );
+    }
+
+    #[test]
+    fn test_parse_slot_name_with_binding() {
+        let mut chars = TmplChars::from("<#row let:(index, item)>");
+        assert_eq!(
+            parse_slot_name(&mut chars).unwrap(),
+            ("row".to_owned(), Some("(index, item)".to_owned()))
+        );
     }
 
     #[test]
-    #[should_panic(expected = "Unexpected character: Some('#'), expected '<'")]
-    fn test_parse_slot_name_without_opening_tag_should_panic() {
-        parse_slot_name(&mut "#header>".chars().peekable());
+    fn test_parse_slot_name_with_invalid_binding_should_fail() {
+        let error = parse_slot_name(&mut TmplChars::from("<#row let:1 +>")).unwrap_err();
+
+        assert!(
+            error
+                .to_string()
+                .starts_with("invalid slot binding `let:1 +`")
+        );
     }
 
     #[test]
-    #[should_panic(expected = "Unexpected character: Some('h'), expected '#'")]
-    fn test_parse_slot_name_without_hash_should_panic() {
-        parse_slot_name(&mut "</header>".chars().peekable());
+    fn test_parse_slot_name_without_opening_tag_should_fail() {
+        let error = parse_slot_name(&mut TmplChars::from("#header>")).unwrap_err();
+
+        assert_eq!(
+            error.to_string(),
+            "unexpected character: Some('#'), expected '<'"
+        );
     }
 
     #[test]
-    #[should_panic(
-        expected = "Invalid slot name character: #, slot name must contain only alphanumeric or '_'"
-    )]
-    fn test_parse_slot_name_with_double_hash_should_panic() {
-        parse_slot_name(&mut "<##header>".chars().peekable());
+    fn test_parse_slot_name_without_hash_should_fail() {
+        let error = parse_slot_name(&mut TmplChars::from("</header>")).unwrap_err();
+
+        assert_eq!(
+            error.to_string(),
+            "unexpected character: Some('h'), expected '#'"
+        );
+    }
+
+    #[test]
+    fn test_parse_slot_name_with_double_hash_should_fail() {
+        let error = parse_slot_name(&mut TmplChars::from("<##header>")).unwrap_err();
+
+        assert_eq!(
+            error.to_string(),
+            "invalid slot name character: #, slot name must contain only alphanumeric or '_'"
+        );
     }
 }
@@ -1,12 +1,17 @@
-use std::str::Chars;
+use super::tmpl_chars::TmplChars;
 
-use crate::tmpl::TmplAst;
+use crate::tmpl::{TmplAst, TmplTag, is_raw_text_element, is_void_element, preserves_whitespace};
 
 use super::is_pascal_case::is_pascal_case;
+use super::lint_element::{lint_element, lint_element_a11y};
 use super::match_chars::match_chars;
+use super::parse_comment::parse_comment;
+use super::parse_component_directive::parse_component_directive;
 use super::parse_conditional_directive::parse_conditional_directive;
 use super::parse_directive_name::parse_directive_name;
+use super::parse_doctype::parse_doctype;
 use super::parse_element_opening_tag::parse_element_opening_tag;
+use super::parse_raw_text::parse_raw_text;
 use super::parse_slot_interpolation::parse_slot_interpolation;
 use super::parse_slot_name::parse_slot_name;
 
@@ -27,25 +32,35 @@ enum ProcessCharsUntilState {
 }
 
 pub(crate) fn process_chars_until(
-    chars: &mut std::iter::Peekable<Chars<'_>>,
+    chars: &mut TmplChars,
     end_of_block: Option<&[&str]>,
-) -> (Vec<TmplAst>, String) {
+) -> syn::Result<(Vec<TmplAst>, String)> {
+    process_chars(chars, end_of_block, false)
+}
+
+/// Like [`process_chars_until`], but keeps every whitespace character as text,
+/// used for the content of `<pre>` and `<textarea>`
+pub(crate) fn process_chars_until_preserving_whitespace(
+    chars: &mut TmplChars,
+    end_of_block: Option<&[&str]>,
+) -> syn::Result<(Vec<TmplAst>, String)> {
+    process_chars(chars, end_of_block, true)
+}
+
+fn process_chars(
+    chars: &mut TmplChars,
+    end_of_block: Option<&[&str]>,
+    preserve_whitespace: bool,
+) -> syn::Result<(Vec<TmplAst>, String)> {
     let mut ast = Vec::new();
     let mut text = String::new();
     let mut state = ProcessCharsUntilState::Unknown;
     let mut expression_type = ExpressionType::Ordinary;
     let mut matched_end_of_block = "".to_owned();
     let mut has_temp_whitespace = false;
+    let mut expression_span = chars.span();
 
     'outer: while chars.peek().is_some() {
-        if chars.peek().is_none() {
-            if let Some(end_of_block) = end_of_block {
-                panic!("Expected end of block: {end_of_block:?}");
-            }
-
-            break;
-        }
-
         if let Some(end_of_block) = end_of_block {
             for end in end_of_block {
                 if match_chars(chars, end) {
@@ -59,8 +74,9 @@ pub(crate) fn process_chars_until(
             ProcessCharsUntilState::Unknown
             | ProcessCharsUntilState::Text
             | ProcessCharsUntilState::AfterExpression => {
-                if state == ProcessCharsUntilState::Unknown
-                    || state == ProcessCharsUntilState::AfterExpression
+                if !preserve_whitespace
+                    && (state == ProcessCharsUntilState::Unknown
+                        || state == ProcessCharsUntilState::AfterExpression)
                 {
                     // Skip whitespace between elements only when not inside a Text node
                     while chars.peek() == Some(&' ')
@@ -110,8 +126,25 @@ pub(crate) fn process_chars_until(
                 let mut lookahead = chars.clone();
                 lookahead.next(); // consume '<'
 
+                if lookahead.peek() == Some(&'!') {
+                    has_temp_whitespace = false;
+
+                    let mut lookahead2 = lookahead.clone();
+                    lookahead2.next(); // consume '!'
+
+                    if lookahead2.peek() == Some(&'-') {
+                        if let Some(comment) = parse_comment(chars)? {
+                            ast.push(comment);
+                        }
+                    } else {
+                        ast.push(parse_doctype(chars)?);
+                    }
+
+                    state = ProcessCharsUntilState::Unknown;
+                } else if lookahead.peek() == Some(&'/') {
+                    return Err(unexpected_closing_tag(chars, end_of_block));
                 // Check if it's a slot tag
-                if lookahead.peek() == Some(&'#') {
+                } else if lookahead.peek() == Some(&'#') {
                     // Check if it's slot interpolation (<#slot) or slot definition (<#slot_name)
                     let mut lookahead2 = lookahead.clone();
                     lookahead2.next(); // consume '#'
@@ -135,17 +168,26 @@ pub(crate) fn process_chars_until(
                             has_temp_whitespace = false;
                         }
 
-                        ast.push(parse_slot_interpolation(chars));
+                        ast.push(parse_slot_interpolation(chars)?);
                         state = ProcessCharsUntilState::AfterExpression;
                     } else {
                         // It's a slot definition
-                        let slot_name = parse_slot_name(chars);
+                        let opening_span = chars.span();
+                        let (slot_name, binding) = parse_slot_name(chars)?;
                         let closing_tag = format!("</#{slot_name}>");
 
-                        let (children, _) = process_chars_until(chars, Some(&[&closing_tag]));
+                        let (children, matched) =
+                            process_chars_until(chars, Some(&[&closing_tag]))?;
+
+                        if matched.is_empty() {
+                            return Err(opening_span.error(format!(
+                                "unclosed slot `<#{slot_name}>`, expected `{closing_tag}`"
+                            )));
+                        }
 
                         ast.push(TmplAst::Slot {
-                            name: Some(slot_name),
+                            name: Some(TmplTag::new(slot_name, opening_span.span())),
+                            binding,
                             children,
                         });
 
@@ -155,21 +197,57 @@ pub(crate) fn process_chars_until(
                     // Clear temp whitespace for regular elements since they don't preserve it
                     has_temp_whitespace = false;
 
+                    let opening_span = chars.span();
                     let (element_name, element_attrs, is_self_closing) =
-                        parse_element_opening_tag(chars);
+                        parse_element_opening_tag(chars)?;
+
+                    // Void elements never have a closing tag, `<input>` is the same as `<input />`
+                    let is_self_closing = is_self_closing || is_void_element(&element_name);
 
                     let is_component = element_name
                         .chars()
                         .next()
                         .is_some_and(|c| c.is_uppercase());
 
+                    if !is_component {
+                        lint_element(
+                            chars,
+                            &opening_span,
+                            parent_element(end_of_block),
+                            &element_name,
+                            &element_attrs,
+                        )?;
+                    }
+
                     if !is_self_closing {
                         let closing_tag = format!("</{element_name}>");
-                        let (children, _) = process_chars_until(chars, Some(&[&closing_tag]));
+                        let (children, matched) = if is_raw_text_element(&element_name) {
+                            (parse_raw_text(chars, &closing_tag)?, closing_tag.clone())
+                        } else if preserve_whitespace || preserves_whitespace(&element_name) {
+                            process_chars_until_preserving_whitespace(chars, Some(&[&closing_tag]))?
+                        } else {
+                            process_chars_until(chars, Some(&[&closing_tag]))?
+                        };
+
+                        if matched.is_empty() {
+                            return Err(opening_span.error(format!(
+                                "unclosed element `<{element_name}>`, expected `{closing_tag}`"
+                            )));
+                        }
                         let is_component = is_pascal_case(&element_name);
 
+                        if !is_component {
+                            lint_element_a11y(
+                                chars,
+                                &opening_span,
+                                &element_name,
+                                &element_attrs,
+                                &children,
+                            );
+                        }
+
                         ast.push(TmplAst::Element {
-                            tag: element_name,
+                            tag: TmplTag::new(element_name, opening_span.span()),
                             attributes: element_attrs,
                             is_component,
                             self_closing: is_self_closing,
@@ -178,8 +256,18 @@ pub(crate) fn process_chars_until(
 
                         state = ProcessCharsUntilState::AfterExpression;
                     } else {
+                        if !is_component {
+                            lint_element_a11y(
+                                chars,
+                                &opening_span,
+                                &element_name,
+                                &element_attrs,
+                                &[],
+                            );
+                        }
+
                         ast.push(TmplAst::Element {
-                            tag: element_name,
+                            tag: TmplTag::new(element_name, opening_span.span()),
                             attributes: element_attrs,
                             is_component,
                             self_closing: is_self_closing,
@@ -192,6 +280,7 @@ pub(crate) fn process_chars_until(
             }
             ProcessCharsUntilState::Expression => {
                 if chars.peek() == Some(&'{') {
+                    expression_span = chars.span();
                     chars.next(); // consume '{'
                 }
 
@@ -204,12 +293,15 @@ pub(crate) fn process_chars_until(
                     // Clear temp whitespace for directives since they don't preserve it
                     has_temp_whitespace = false;
 
+                    let directive_span = chars.span();
                     let directive_name = parse_directive_name(chars);
 
                     if directive_name == "if" {
                         ast.push(TmplAst::ConditionalDirective(parse_conditional_directive(
                             chars,
-                        )));
+                        )?));
+                    } else if directive_name == "component" {
+                        ast.push(parse_component_directive(chars)?);
                     } else if directive_name == "outlet" {
                         // For outlet directive, we need to consume the closing brace
                         // The directive_name parsing should have stopped at the '}'
@@ -217,6 +309,10 @@ pub(crate) fn process_chars_until(
                             chars.next(); // consume the '}'
                         }
                         ast.push(TmplAst::Outlet);
+                    } else {
+                        return Err(directive_span.error(format!(
+                            "unknown directive `#{directive_name}`, expected `#if`, `#component` or `#outlet`"
+                        )));
                     }
 
                     state = ProcessCharsUntilState::Unknown;
@@ -225,14 +321,6 @@ pub(crate) fn process_chars_until(
 
                     if !text.is_empty() {
                         match expression_type {
-                            ExpressionType::Ordinary => {
-                                // Add whitespace for regular expressions
-                                if has_temp_whitespace {
-                                    ast.push(TmplAst::Text(" ".to_owned()));
-                                    has_temp_whitespace = false;
-                                }
-                                ast.push(TmplAst::Expression(text));
-                            }
                             ExpressionType::Slot => {
                                 // Add whitespace for slot expressions
                                 if has_temp_whitespace {
@@ -241,10 +329,12 @@ pub(crate) fn process_chars_until(
                                 }
                                 ast.push(TmplAst::SlotInterpolation {
                                     slot_name: Some(text),
+                                    props: None,
                                     default_children: None,
                                 });
                             }
-                            ExpressionType::Directive => {
+                            // Ordinary expressions are taken whole, see below
+                            ExpressionType::Directive | ExpressionType::Ordinary => {
                                 // Don't add whitespace for directives
                                 has_temp_whitespace = false;
                             }
@@ -254,10 +344,28 @@ pub(crate) fn process_chars_until(
                         text = String::new();
                     }
 
+                    state = ProcessCharsUntilState::AfterExpression;
+                } else if expression_type == ExpressionType::Ordinary {
+                    let Some(expr) = chars.take_until_closing_brace() else {
+                        return Err(expression_span.error("unclosed expression, expected '}'"));
+                    };
+
+                    if let Err(error) = expr.to_expr() {
+                        return Err(expression_span
+                            .error(format!("invalid expression `{}`: {error}", expr.as_str())));
+                    }
+
+                    // Add whitespace for regular expressions
+                    if has_temp_whitespace {
+                        ast.push(TmplAst::Text(" ".to_owned()));
+                        has_temp_whitespace = false;
+                    }
+                    ast.push(TmplAst::Expression(expr));
+
                     state = ProcessCharsUntilState::AfterExpression;
                 } else {
                     let Some(ch) = chars.next() else {
-                        panic!("Invalid expression syntax")
+                        return Err(expression_span.error("unclosed expression, expected '}'"));
                     };
 
                     text.push(ch);
@@ -266,38 +374,70 @@ pub(crate) fn process_chars_until(
         }
     }
 
-    text = text.trim_end().to_owned();
+    if !preserve_whitespace {
+        text = text.trim_end().to_owned();
+    }
 
     if !text.is_empty() {
         ast.push(TmplAst::Text(text));
     }
 
-    (ast, matched_end_of_block)
+    Ok((ast, matched_end_of_block))
+}
+
+/// Element whose children are being parsed, known from its closing tag. Children of
+/// directives and slots have no known parent.
+fn parent_element<'a>(end_of_block: Option<&[&'a str]>) -> Option<&'a str> {
+    end_of_block?
+        .iter()
+        .find_map(|end| end.strip_prefix("</")?.strip_suffix('>'))
+        .filter(|tag| !tag.starts_with('#'))
+}
+
+/// Error for a closing tag that doesn't close the element being parsed
+fn unexpected_closing_tag(chars: &TmplChars, end_of_block: Option<&[&str]>) -> syn::Error {
+    let closing_tag = chars
+        .clone()
+        .take_while(|ch| *ch != '>')
+        .chain(std::iter::once('>'))
+        .collect::<String>();
+
+    let expected = end_of_block
+        .unwrap_or_default()
+        .iter()
+        .find(|end| end.starts_with("</"));
+
+    match expected {
+        Some(expected) => chars.error(format!(
+            "mismatched closing tag `{closing_tag}`, expected `{expected}`"
+        )),
+        None => chars.error(format!("unexpected closing tag `{closing_tag}`")),
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::tmpl::{Attribute, Attributes, ConditionalBlock};
+    use crate::tmpl::{Attribute, Attributes, ConditionalBlock, TmplLints};
 
     use super::*;
 
     #[test]
     fn text() {
-        let mut chars = "Hello, world!".chars().peekable();
-        let (ast, _) = process_chars_until(&mut chars, None);
+        let mut chars = TmplChars::from("Hello, world!");
+        let (ast, _) = process_chars_until(&mut chars, None).unwrap();
 
         assert_eq!(ast, vec![TmplAst::Text("Hello, world!".to_owned())]);
     }
 
     #[test]
     fn single_element() {
-        let mut chars = "<div></div>".chars().peekable();
-        let (ast, _) = process_chars_until(&mut chars, None);
+        let mut chars = TmplChars::from("<div></div>");
+        let (ast, _) = process_chars_until(&mut chars, None).unwrap();
 
         assert_eq!(
             ast,
             vec![TmplAst::Element {
-                tag: "div".to_owned(),
+                tag: "div".into(),
                 is_component: false,
                 attributes: Attributes::new(),
                 self_closing: false,
@@ -308,13 +448,13 @@ mod tests {
 
     #[test]
     fn element_with_text() {
-        let mut chars = "<div>Hello, world!</div>".chars().peekable();
-        let (ast, _) = process_chars_until(&mut chars, None);
+        let mut chars = TmplChars::from("<div>Hello, world!</div>");
+        let (ast, _) = process_chars_until(&mut chars, None).unwrap();
 
         assert_eq!(
             ast,
             vec![TmplAst::Element {
-                tag: "div".to_owned(),
+                tag: "div".into(),
                 attributes: Attributes::new(),
                 self_closing: false,
                 is_component: false,
@@ -325,22 +465,20 @@ mod tests {
 
     #[test]
     fn element_with_text_that_contains_path() {
-        let mut chars = "<div>Hello, <a href=\"/path\">world</a>!</div>"
-            .chars()
-            .peekable();
-        let (ast, _) = process_chars_until(&mut chars, None);
+        let mut chars = TmplChars::from("<div>Hello, <a href=\"/path\">world</a>!</div>");
+        let (ast, _) = process_chars_until(&mut chars, None).unwrap();
 
         assert_eq!(
             ast,
             vec![TmplAst::Element {
-                tag: "div".to_owned(),
+                tag: "div".into(),
                 attributes: Attributes::new(),
                 self_closing: false,
                 is_component: false,
                 children: vec![
                     TmplAst::Text("Hello, ".to_owned()),
                     TmplAst::Element {
-                        tag: "a".to_owned(),
+                        tag: "a".into(),
                         attributes: Attributes::from([(
                             "href".to_owned(),
                             Attribute::Literal("/path".to_owned()),
@@ -357,13 +495,13 @@ mod tests {
 
     #[test]
     fn element_with_text_and_whitespace() {
-        let mut chars = "<div>  Hello, world!  </div>".chars().peekable();
-        let (ast, _) = process_chars_until(&mut chars, None);
+        let mut chars = TmplChars::from("<div>  Hello, world!  </div>");
+        let (ast, _) = process_chars_until(&mut chars, None).unwrap();
 
         assert_eq!(
             ast,
             vec![TmplAst::Element {
-                tag: "div".to_owned(),
+                tag: "div".into(),
                 attributes: Attributes::new(),
                 self_closing: false,
                 is_component: false,
@@ -374,18 +512,18 @@ mod tests {
 
     #[test]
     fn nested_elements() {
-        let mut chars = "<div><p>Hello, world!</p></div>".chars().peekable();
-        let (ast, _) = process_chars_until(&mut chars, None);
+        let mut chars = TmplChars::from("<div><p>Hello, world!</p></div>");
+        let (ast, _) = process_chars_until(&mut chars, None).unwrap();
 
         assert_eq!(
             ast,
             vec![TmplAst::Element {
-                tag: "div".to_owned(),
+                tag: "div".into(),
                 attributes: Attributes::new(),
                 self_closing: false,
                 is_component: false,
                 children: vec![TmplAst::Element {
-                    tag: "p".to_owned(),
+                    tag: "p".into(),
                     attributes: Attributes::new(),
                     self_closing: false,
                     is_component: false,
@@ -397,13 +535,14 @@ mod tests {
 
     #[test]
     fn slot_with_text() {
-        let mut chars = "<#slot_name>Hello, world!</#slot_name>".chars().peekable();
-        let (ast, _) = process_chars_until(&mut chars, None);
+        let mut chars = TmplChars::from("<#slot_name>Hello, world!</#slot_name>");
+        let (ast, _) = process_chars_until(&mut chars, None).unwrap();
 
         assert_eq!(
             ast,
             vec![TmplAst::Slot {
-                name: Some("slot_name".to_owned()),
+                name: Some("slot_name".into()),
+                binding: None,
                 children: vec![TmplAst::Text("Hello, world!".to_owned())],
             }]
         );
@@ -411,17 +550,16 @@ mod tests {
 
     #[test]
     fn slots_with_nested_elements() {
-        let mut chars = "<#slot_name><p>Hello, world!</p></#slot_name>"
-            .chars()
-            .peekable();
-        let (ast, _) = process_chars_until(&mut chars, None);
+        let mut chars = TmplChars::from("<#slot_name><p>Hello, world!</p></#slot_name>");
+        let (ast, _) = process_chars_until(&mut chars, None).unwrap();
 
         assert_eq!(
             ast,
             vec![TmplAst::Slot {
-                name: Some("slot_name".to_owned()),
+                name: Some("slot_name".into()),
+                binding: None,
                 children: vec![TmplAst::Element {
-                    tag: "p".to_owned(),
+                    tag: "p".into(),
                     attributes: Attributes::new(),
                     self_closing: false,
                     is_component: false,
@@ -433,21 +571,21 @@ mod tests {
 
     #[test]
     fn expression() {
-        let mut chars = "{1 + 1}".chars().peekable();
-        let (ast, _) = process_chars_until(&mut chars, None);
+        let mut chars = TmplChars::from("{1 + 1}");
+        let (ast, _) = process_chars_until(&mut chars, None).unwrap();
 
-        assert_eq!(ast, vec![TmplAst::Expression("1 + 1".to_owned())]);
+        assert_eq!(ast, vec![TmplAst::Expression("1 + 1".into())]);
     }
 
     #[test]
     fn expression_with_text() {
-        let mut chars = "{1 + 1}Hello, world!".chars().peekable();
-        let (ast, _) = process_chars_until(&mut chars, None);
+        let mut chars = TmplChars::from("{1 + 1}Hello, world!");
+        let (ast, _) = process_chars_until(&mut chars, None).unwrap();
 
         assert_eq!(
             ast,
             vec![
-                TmplAst::Expression("1 + 1".to_owned()),
+                TmplAst::Expression("1 + 1".into()),
                 TmplAst::Text("Hello, world!".to_owned())
             ]
         );
@@ -455,35 +593,35 @@ mod tests {
 
     #[test]
     fn expression_inside_element() {
-        let mut chars = "<div>{1 + 1}</div>".chars().peekable();
-        let (ast, _) = process_chars_until(&mut chars, None);
+        let mut chars = TmplChars::from("<div>{1 + 1}</div>");
+        let (ast, _) = process_chars_until(&mut chars, None).unwrap();
 
         assert_eq!(
             ast,
             vec![TmplAst::Element {
-                tag: "div".to_owned(),
+                tag: "div".into(),
                 attributes: Attributes::new(),
                 self_closing: false,
                 is_component: false,
-                children: vec![TmplAst::Expression("1 + 1".to_owned())],
+                children: vec![TmplAst::Expression("1 + 1".into())],
             }]
         );
     }
 
     #[test]
     fn expression_inside_element_with_text() {
-        let mut chars = "<div>{1 + 1}Hello, world!</div>".chars().peekable();
-        let (ast, _) = process_chars_until(&mut chars, None);
+        let mut chars = TmplChars::from("<div>{1 + 1}Hello, world!</div>");
+        let (ast, _) = process_chars_until(&mut chars, None).unwrap();
 
         assert_eq!(
             ast,
             vec![TmplAst::Element {
-                tag: "div".to_owned(),
+                tag: "div".into(),
                 attributes: Attributes::new(),
                 self_closing: false,
                 is_component: false,
                 children: vec![
-                    TmplAst::Expression("1 + 1".to_owned()),
+                    TmplAst::Expression("1 + 1".into()),
                     TmplAst::Text("Hello, world!".to_owned())
                 ],
             }]
@@ -492,15 +630,14 @@ mod tests {
 
     #[test]
     fn expression_inside_element_with_attrs() {
-        let mut chars = "<div id=\"container-id\" onclick={handle_click}>{1 + 1}</div>"
-            .chars()
-            .peekable();
-        let (ast, _) = process_chars_until(&mut chars, None);
+        let mut chars =
+            TmplChars::from("<div id=\"container-id\" onclick={handle_click}>{1 + 1}</div>");
+        let (ast, _) = process_chars_until(&mut chars, None).unwrap();
 
         assert_eq!(
             ast,
             vec![TmplAst::Element {
-                tag: "div".to_owned(),
+                tag: "div".into(),
                 attributes: Attributes::from([
                     (
                         "id".to_owned(),
@@ -508,28 +645,28 @@ mod tests {
                     ),
                     (
                         "onclick".to_owned(),
-                        Attribute::EventListener("handle_click".to_owned())
+                        Attribute::EventListener("handle_click".into())
                     ),
                 ]),
                 self_closing: false,
                 is_component: false,
-                children: vec![TmplAst::Expression("1 + 1".to_owned())],
+                children: vec![TmplAst::Expression("1 + 1".into())],
             }]
         );
     }
 
     #[test]
     fn expression_as_element_attribute() {
-        let mut chars = "<div data-test={1 + 1}></div>".chars().peekable();
-        let (ast, _) = process_chars_until(&mut chars, None);
+        let mut chars = TmplChars::from("<div data-test={1 + 1}></div>");
+        let (ast, _) = process_chars_until(&mut chars, None).unwrap();
 
         assert_eq!(
             ast,
             vec![TmplAst::Element {
-                tag: "div".to_owned(),
+                tag: "div".into(),
                 attributes: Attributes::from([(
                     "data-test".to_owned(),
-                    Attribute::Expression("1 + 1".to_owned())
+                    Attribute::Expression("1 + 1".into())
                 )]),
                 self_closing: false,
                 is_component: false,
@@ -540,20 +677,20 @@ mod tests {
 
     #[test]
     fn text_inside_element_with_nested_elements() {
-        let mut chars = "<div>Hello, <span>world</span>!</div>".chars().peekable();
-        let (ast, _) = process_chars_until(&mut chars, None);
+        let mut chars = TmplChars::from("<div>Hello, <span>world</span>!</div>");
+        let (ast, _) = process_chars_until(&mut chars, None).unwrap();
 
         assert_eq!(
             ast,
             vec![TmplAst::Element {
-                tag: "div".to_owned(),
+                tag: "div".into(),
                 attributes: Attributes::new(),
                 self_closing: false,
                 is_component: false,
                 children: vec![
                     TmplAst::Text("Hello, ".to_owned()),
                     TmplAst::Element {
-                        tag: "span".to_owned(),
+                        tag: "span".into(),
                         attributes: Attributes::new(),
                         self_closing: false,
                         is_component: false,
@@ -567,19 +704,19 @@ mod tests {
 
     #[test]
     fn text_inside_element_with_nested_expression() {
-        let mut chars = "<div>Hello, {1 + 1}!</div>".chars().peekable();
-        let (ast, _) = process_chars_until(&mut chars, None);
+        let mut chars = TmplChars::from("<div>Hello, {1 + 1}!</div>");
+        let (ast, _) = process_chars_until(&mut chars, None).unwrap();
 
         assert_eq!(
             ast,
             vec![TmplAst::Element {
-                tag: "div".to_owned(),
+                tag: "div".into(),
                 attributes: Attributes::new(),
                 self_closing: false,
                 is_component: false,
                 children: vec![
                     TmplAst::Text("Hello, ".to_owned()),
-                    TmplAst::Expression("1 + 1".to_owned()),
+                    TmplAst::Expression("1 + 1".into()),
                     TmplAst::Text("!".to_owned()),
                 ],
             }]
@@ -588,13 +725,13 @@ mod tests {
 
     #[test]
     fn single_component() {
-        let mut chars = "<MyComponent></MyComponent>".chars().peekable();
-        let (ast, _) = process_chars_until(&mut chars, None);
+        let mut chars = TmplChars::from("<MyComponent></MyComponent>");
+        let (ast, _) = process_chars_until(&mut chars, None).unwrap();
 
         assert_eq!(
             ast,
             vec![TmplAst::Element {
-                tag: "MyComponent".to_owned(),
+                tag: "MyComponent".into(),
                 attributes: Attributes::new(),
                 is_component: true,
                 self_closing: false,
@@ -605,15 +742,13 @@ mod tests {
 
     #[test]
     fn component_with_text() {
-        let mut chars = "<MyComponent>Hello, world!</MyComponent>"
-            .chars()
-            .peekable();
-        let (ast, _) = process_chars_until(&mut chars, None);
+        let mut chars = TmplChars::from("<MyComponent>Hello, world!</MyComponent>");
+        let (ast, _) = process_chars_until(&mut chars, None).unwrap();
 
         assert_eq!(
             ast,
             vec![TmplAst::Element {
-                tag: "MyComponent".to_owned(),
+                tag: "MyComponent".into(),
                 attributes: Attributes::new(),
                 is_component: true,
                 self_closing: false,
@@ -624,13 +759,13 @@ mod tests {
 
     #[test]
     fn self_closing_component() {
-        let mut chars = "<MyComponent />".chars().peekable();
-        let (ast, _) = process_chars_until(&mut chars, None);
+        let mut chars = TmplChars::from("<MyComponent />");
+        let (ast, _) = process_chars_until(&mut chars, None).unwrap();
 
         assert_eq!(
             ast,
             vec![TmplAst::Element {
-                tag: "MyComponent".to_owned(),
+                tag: "MyComponent".into(),
                 attributes: Attributes::new(),
                 is_component: true,
                 self_closing: true,
@@ -641,15 +776,15 @@ mod tests {
 
     #[test]
     fn component_with_attrs() {
-        let mut chars = "<MyComponent id=\"container-id\" onclick={handle_click}></MyComponent>"
-            .chars()
-            .peekable();
-        let (ast, _) = process_chars_until(&mut chars, None);
+        let mut chars = TmplChars::from(
+            "<MyComponent id=\"container-id\" onclick={handle_click}></MyComponent>",
+        );
+        let (ast, _) = process_chars_until(&mut chars, None).unwrap();
 
         assert_eq!(
             ast,
             vec![TmplAst::Element {
-                tag: "MyComponent".to_owned(),
+                tag: "MyComponent".into(),
                 attributes: Attributes::from([
                     (
                         "id".to_owned(),
@@ -657,7 +792,7 @@ mod tests {
                     ),
                     (
                         "onclick".to_owned(),
-                        Attribute::EventListener("handle_click".to_owned())
+                        Attribute::EventListener("handle_click".into())
                     ),
                 ]),
                 is_component: true,
@@ -669,20 +804,18 @@ mod tests {
 
     #[test]
     fn component_with_nested_elements() {
-        let mut chars = "<MyComponent><p>Hello, world!</p></MyComponent>"
-            .chars()
-            .peekable();
-        let (ast, _) = process_chars_until(&mut chars, None);
+        let mut chars = TmplChars::from("<MyComponent><p>Hello, world!</p></MyComponent>");
+        let (ast, _) = process_chars_until(&mut chars, None).unwrap();
 
         assert_eq!(
             ast,
             vec![TmplAst::Element {
-                tag: "MyComponent".to_owned(),
+                tag: "MyComponent".into(),
                 attributes: Attributes::new(),
                 is_component: true,
                 self_closing: false,
                 children: vec![TmplAst::Element {
-                    tag: "p".to_owned(),
+                    tag: "p".into(),
                     attributes: Attributes::new(),
                     is_component: false,
                     self_closing: false,
@@ -694,19 +827,20 @@ mod tests {
 
     #[test]
     fn unnamed_slot_interpolation() {
-        let mut chars = "<div><#slot>{1 + 1}</#slot></div>".chars().peekable();
-        let (ast, _) = process_chars_until(&mut chars, None);
+        let mut chars = TmplChars::from("<div><#slot>{1 + 1}</#slot></div>");
+        let (ast, _) = process_chars_until(&mut chars, None).unwrap();
 
         assert_eq!(
             ast,
             vec![TmplAst::Element {
-                tag: "div".to_owned(),
+                tag: "div".into(),
                 attributes: Attributes::new(),
                 is_component: false,
                 self_closing: false,
                 children: vec![TmplAst::SlotInterpolation {
                     slot_name: None,
-                    default_children: Some(vec![TmplAst::Expression("1 + 1".to_owned())]),
+                    props: None,
+                    default_children: Some(vec![TmplAst::Expression("1 + 1".into())]),
                 }],
             }]
         );
@@ -714,18 +848,19 @@ mod tests {
 
     #[test]
     fn slot_interpolation() {
-        let mut chars = "<div><#slot slot_name /></div>".chars().peekable();
-        let (ast, _) = process_chars_until(&mut chars, None);
+        let mut chars = TmplChars::from("<div><#slot slot_name /></div>");
+        let (ast, _) = process_chars_until(&mut chars, None).unwrap();
 
         assert_eq!(
             ast,
             vec![TmplAst::Element {
-                tag: "div".to_owned(),
+                tag: "div".into(),
                 attributes: Attributes::new(),
                 is_component: false,
                 self_closing: false,
                 children: vec![TmplAst::SlotInterpolation {
                     slot_name: Some("slot_name".to_owned()),
+                    props: None,
                     default_children: None,
                 }],
             }]
@@ -734,27 +869,26 @@ mod tests {
 
     #[test]
     fn expression_with_slot_interpolation_and_whitespace() {
-        let mut chars = "<div>{1 + 1} <#slot slot_name /> {2 + 2}</div>"
-            .chars()
-            .peekable();
-        let (ast, _) = process_chars_until(&mut chars, None);
+        let mut chars = TmplChars::from("<div>{1 + 1} <#slot slot_name /> {2 + 2}</div>");
+        let (ast, _) = process_chars_until(&mut chars, None).unwrap();
 
         assert_eq!(
             ast,
             vec![TmplAst::Element {
-                tag: "div".to_owned(),
+                tag: "div".into(),
                 attributes: Attributes::new(),
                 is_component: false,
                 self_closing: false,
                 children: vec![
-                    TmplAst::Expression("1 + 1".to_owned()),
+                    TmplAst::Expression("1 + 1".into()),
                     TmplAst::Text(" ".to_owned()),
                     TmplAst::SlotInterpolation {
                         slot_name: Some("slot_name".to_owned()),
+                        props: None,
                         default_children: None,
                     },
                     TmplAst::Text(" ".to_owned()),
-                    TmplAst::Expression("2 + 2".to_owned()),
+                    TmplAst::Expression("2 + 2".into()),
                 ],
             }]
         );
@@ -762,15 +896,13 @@ mod tests {
 
     #[test]
     fn slot_interpolation_with_default_children() {
-        let mut chars = "<div>Hello, <#slot>John Doe</#slot>!</div>"
-            .chars()
-            .peekable();
-        let (ast, _) = process_chars_until(&mut chars, None);
+        let mut chars = TmplChars::from("<div>Hello, <#slot>John Doe</#slot>!</div>");
+        let (ast, _) = process_chars_until(&mut chars, None).unwrap();
 
         assert_eq!(
             ast,
             vec![TmplAst::Element {
-                tag: "div".to_owned(),
+                tag: "div".into(),
                 attributes: Attributes::new(),
                 is_component: false,
                 self_closing: false,
@@ -778,6 +910,7 @@ mod tests {
                     TmplAst::Text("Hello, ".to_owned()),
                     TmplAst::SlotInterpolation {
                         slot_name: None,
+                        props: None,
                         default_children: Some(vec![TmplAst::Text("John Doe".to_owned())]),
                     },
                     TmplAst::Text("!".to_owned()),
@@ -788,20 +921,20 @@ mod tests {
 
     #[test]
     fn two_expression_split_by_whitespace() {
-        let mut chars = "<div>{1 + 1} {2 + 2}</div>".chars().peekable();
-        let (ast, _) = process_chars_until(&mut chars, None);
+        let mut chars = TmplChars::from("<div>{1 + 1} {2 + 2}</div>");
+        let (ast, _) = process_chars_until(&mut chars, None).unwrap();
 
         assert_eq!(
             ast,
             vec![TmplAst::Element {
-                tag: "div".to_owned(),
+                tag: "div".into(),
                 attributes: Attributes::new(),
                 is_component: false,
                 self_closing: false,
                 children: vec![
-                    TmplAst::Expression("1 + 1".to_owned()),
+                    TmplAst::Expression("1 + 1".into()),
                     TmplAst::Text(" ".to_owned()),
-                    TmplAst::Expression("2 + 2".to_owned()),
+                    TmplAst::Expression("2 + 2".into()),
                 ],
             }]
         );
@@ -809,22 +942,22 @@ mod tests {
 
     #[test]
     fn multiple_expressions_with_whitespace() {
-        let mut chars = "<div>{1 + 1} {2 + 2} {3 + 3}</div>".chars().peekable();
-        let (ast, _) = process_chars_until(&mut chars, None);
+        let mut chars = TmplChars::from("<div>{1 + 1} {2 + 2} {3 + 3}</div>");
+        let (ast, _) = process_chars_until(&mut chars, None).unwrap();
 
         assert_eq!(
             ast,
             vec![TmplAst::Element {
-                tag: "div".to_owned(),
+                tag: "div".into(),
                 attributes: Attributes::new(),
                 is_component: false,
                 self_closing: false,
                 children: vec![
-                    TmplAst::Expression("1 + 1".to_owned()),
+                    TmplAst::Expression("1 + 1".into()),
                     TmplAst::Text(" ".to_owned()),
-                    TmplAst::Expression("2 + 2".to_owned()),
+                    TmplAst::Expression("2 + 2".into()),
                     TmplAst::Text(" ".to_owned()),
-                    TmplAst::Expression("3 + 3".to_owned()),
+                    TmplAst::Expression("3 + 3".into()),
                 ],
             }]
         );
@@ -832,21 +965,21 @@ mod tests {
 
     #[test]
     fn expression_with_text_and_whitespace() {
-        let mut chars = "<div>Hello {1 + 1} World {2 + 2}!</div>".chars().peekable();
-        let (ast, _) = process_chars_until(&mut chars, None);
+        let mut chars = TmplChars::from("<div>Hello {1 + 1} World {2 + 2}!</div>");
+        let (ast, _) = process_chars_until(&mut chars, None).unwrap();
 
         assert_eq!(
             ast,
             vec![TmplAst::Element {
-                tag: "div".to_owned(),
+                tag: "div".into(),
                 attributes: Attributes::new(),
                 is_component: false,
                 self_closing: false,
                 children: vec![
                     TmplAst::Text("Hello ".to_owned()),
-                    TmplAst::Expression("1 + 1".to_owned()),
+                    TmplAst::Expression("1 + 1".into()),
                     TmplAst::Text(" World ".to_owned()),
-                    TmplAst::Expression("2 + 2".to_owned()),
+                    TmplAst::Expression("2 + 2".into()),
                     TmplAst::Text("!".to_owned()),
                 ],
             }]
@@ -855,20 +988,20 @@ mod tests {
 
     #[test]
     fn expression_with_multiple_whitespace_chars() {
-        let mut chars = "<div>{1 + 1}  {2 + 2}</div>".chars().peekable();
-        let (ast, _) = process_chars_until(&mut chars, None);
+        let mut chars = TmplChars::from("<div>{1 + 1}  {2 + 2}</div>");
+        let (ast, _) = process_chars_until(&mut chars, None).unwrap();
 
         assert_eq!(
             ast,
             vec![TmplAst::Element {
-                tag: "div".to_owned(),
+                tag: "div".into(),
                 attributes: Attributes::new(),
                 is_component: false,
                 self_closing: false,
                 children: vec![
-                    TmplAst::Expression("1 + 1".to_owned()),
+                    TmplAst::Expression("1 + 1".into()),
                     TmplAst::Text(" ".to_owned()),
-                    TmplAst::Expression("2 + 2".to_owned()),
+                    TmplAst::Expression("2 + 2".into()),
                 ],
             }]
         );
@@ -876,22 +1009,22 @@ mod tests {
 
     #[test]
     fn expression_with_tab_and_newline() {
-        let mut chars = "<div>{1 + 1}\t{2 + 2}\n{3 + 3}</div>".chars().peekable();
-        let (ast, _) = process_chars_until(&mut chars, None);
+        let mut chars = TmplChars::from("<div>{1 + 1}\t{2 + 2}\n{3 + 3}</div>");
+        let (ast, _) = process_chars_until(&mut chars, None).unwrap();
 
         assert_eq!(
             ast,
             vec![TmplAst::Element {
-                tag: "div".to_owned(),
+                tag: "div".into(),
                 attributes: Attributes::new(),
                 is_component: false,
                 self_closing: false,
                 children: vec![
-                    TmplAst::Expression("1 + 1".to_owned()),
+                    TmplAst::Expression("1 + 1".into()),
                     TmplAst::Text(" ".to_owned()),
-                    TmplAst::Expression("2 + 2".to_owned()),
+                    TmplAst::Expression("2 + 2".into()),
                     TmplAst::Text(" ".to_owned()),
-                    TmplAst::Expression("3 + 3".to_owned()),
+                    TmplAst::Expression("3 + 3".into()),
                 ],
             }]
         );
@@ -899,25 +1032,25 @@ mod tests {
 
     #[test]
     fn nested_elements_with_whitespace() {
-        let mut chars = "<div><span>{1 + 1} {2 + 2}</span></div>".chars().peekable();
-        let (ast, _) = process_chars_until(&mut chars, None);
+        let mut chars = TmplChars::from("<div><span>{1 + 1} {2 + 2}</span></div>");
+        let (ast, _) = process_chars_until(&mut chars, None).unwrap();
 
         assert_eq!(
             ast,
             vec![TmplAst::Element {
-                tag: "div".to_owned(),
+                tag: "div".into(),
                 attributes: Attributes::new(),
                 is_component: false,
                 self_closing: false,
                 children: vec![TmplAst::Element {
-                    tag: "span".to_owned(),
+                    tag: "span".into(),
                     attributes: Attributes::new(),
                     is_component: false,
                     self_closing: false,
                     children: vec![
-                        TmplAst::Expression("1 + 1".to_owned()),
+                        TmplAst::Expression("1 + 1".into()),
                         TmplAst::Text(" ".to_owned()),
-                        TmplAst::Expression("2 + 2".to_owned()),
+                        TmplAst::Expression("2 + 2".into()),
                     ],
                 }],
             }]
@@ -926,22 +1059,20 @@ mod tests {
 
     #[test]
     fn component_with_whitespace_between_expressions() {
-        let mut chars = "<MyComponent>{1 + 1} {2 + 2}</MyComponent>"
-            .chars()
-            .peekable();
-        let (ast, _) = process_chars_until(&mut chars, None);
+        let mut chars = TmplChars::from("<MyComponent>{1 + 1} {2 + 2}</MyComponent>");
+        let (ast, _) = process_chars_until(&mut chars, None).unwrap();
 
         assert_eq!(
             ast,
             vec![TmplAst::Element {
-                tag: "MyComponent".to_owned(),
+                tag: "MyComponent".into(),
                 attributes: Attributes::new(),
                 is_component: true,
                 self_closing: false,
                 children: vec![
-                    TmplAst::Expression("1 + 1".to_owned()),
+                    TmplAst::Expression("1 + 1".into()),
                     TmplAst::Text(" ".to_owned()),
-                    TmplAst::Expression("2 + 2".to_owned()),
+                    TmplAst::Expression("2 + 2".into()),
                 ],
             }]
         );
@@ -949,19 +1080,18 @@ mod tests {
 
     #[test]
     fn slot_with_whitespace_between_expressions() {
-        let mut chars = "<#slot_name>{1 + 1} {2 + 2}</#slot_name>"
-            .chars()
-            .peekable();
-        let (ast, _) = process_chars_until(&mut chars, None);
+        let mut chars = TmplChars::from("<#slot_name>{1 + 1} {2 + 2}</#slot_name>");
+        let (ast, _) = process_chars_until(&mut chars, None).unwrap();
 
         assert_eq!(
             ast,
             vec![TmplAst::Slot {
-                name: Some("slot_name".to_owned()),
+                name: Some("slot_name".into()),
+                binding: None,
                 children: vec![
-                    TmplAst::Expression("1 + 1".to_owned()),
+                    TmplAst::Expression("1 + 1".into()),
                     TmplAst::Text(" ".to_owned()),
-                    TmplAst::Expression("2 + 2".to_owned()),
+                    TmplAst::Expression("2 + 2".into()),
                 ],
             }]
         );
@@ -969,23 +1099,21 @@ mod tests {
 
     #[test]
     fn top_level_whitespace_skipping() {
-        let mut chars = "  <div>Hello</div>  <span>World</span>  "
-            .chars()
-            .peekable();
-        let (ast, _) = process_chars_until(&mut chars, None);
+        let mut chars = TmplChars::from("  <div>Hello</div>  <span>World</span>  ");
+        let (ast, _) = process_chars_until(&mut chars, None).unwrap();
 
         assert_eq!(
             ast,
             vec![
                 TmplAst::Element {
-                    tag: "div".to_owned(),
+                    tag: "div".into(),
                     attributes: Attributes::new(),
                     is_component: false,
                     self_closing: false,
                     children: vec![TmplAst::Text("Hello".to_owned())],
                 },
                 TmplAst::Element {
-                    tag: "span".to_owned(),
+                    tag: "span".into(),
                     attributes: Attributes::new(),
                     is_component: false,
                     self_closing: false,
@@ -997,23 +1125,21 @@ mod tests {
 
     #[test]
     fn mixed_content_with_whitespace() {
-        let mut chars = "<div>Text {1 + 1} More {2 + 2} End</div>"
-            .chars()
-            .peekable();
-        let (ast, _) = process_chars_until(&mut chars, None);
+        let mut chars = TmplChars::from("<div>Text {1 + 1} More {2 + 2} End</div>");
+        let (ast, _) = process_chars_until(&mut chars, None).unwrap();
 
         assert_eq!(
             ast,
             vec![TmplAst::Element {
-                tag: "div".to_owned(),
+                tag: "div".into(),
                 attributes: Attributes::new(),
                 is_component: false,
                 self_closing: false,
                 children: vec![
                     TmplAst::Text("Text ".to_owned()),
-                    TmplAst::Expression("1 + 1".to_owned()),
+                    TmplAst::Expression("1 + 1".into()),
                     TmplAst::Text(" More ".to_owned()),
-                    TmplAst::Expression("2 + 2".to_owned()),
+                    TmplAst::Expression("2 + 2".into()),
                     TmplAst::Text(" End".to_owned()),
                 ],
             }]
@@ -1021,13 +1147,13 @@ mod tests {
     }
     #[test]
     fn conditional_directive() {
-        let mut chars = "{#if true}Hello, world!{/if}".chars().peekable();
-        let (ast, _) = process_chars_until(&mut chars, None);
+        let mut chars = TmplChars::from("{#if true}Hello, world!{/if}");
+        let (ast, _) = process_chars_until(&mut chars, None).unwrap();
 
         assert_eq!(
             ast,
             vec![TmplAst::ConditionalDirective(vec![ConditionalBlock::If {
-                condition: "true".to_owned(),
+                condition: "true".into(),
                 children: vec![TmplAst::Text("Hello, world!".to_owned())],
             }])]
         );
@@ -1035,27 +1161,27 @@ mod tests {
 
     #[test]
     fn conditional_directive_inside_element() {
-        let mut chars = r#"
+        let mut chars = TmplChars::from(
+            r#"
             <div>
                 {#if 1 + 1 == 2}
                     Hello, world!
                 {/if}
             </div>
-        "#
-        .chars()
-        .peekable();
+        "#,
+        );
 
-        let (ast, _) = process_chars_until(&mut chars, None);
+        let (ast, _) = process_chars_until(&mut chars, None).unwrap();
 
         assert_eq!(
             ast,
             vec![TmplAst::Element {
-                tag: "div".to_owned(),
+                tag: "div".into(),
                 attributes: Attributes::new(),
                 is_component: false,
                 self_closing: false,
                 children: vec![TmplAst::ConditionalDirective(vec![ConditionalBlock::If {
-                    condition: "1 + 1 == 2".to_owned(),
+                    condition: "1 + 1 == 2".into(),
                     children: vec![TmplAst::Text("Hello, world!".to_owned())],
                 }])],
             }]
@@ -1064,7 +1190,8 @@ mod tests {
 
     #[test]
     fn several_conditional_directives() {
-        let mut chars = r#"
+        let mut chars = TmplChars::from(
+            r#"
             <div>
                 {#if 1 + 1 == 2}
                     <span>
@@ -1078,24 +1205,23 @@ mod tests {
                     </span>
                 {/if}
             </div>
-        "#
-        .chars()
-        .peekable();
+        "#,
+        );
 
-        let (ast, _) = process_chars_until(&mut chars, None);
+        let (ast, _) = process_chars_until(&mut chars, None).unwrap();
 
         assert_eq!(
             ast,
             vec![TmplAst::Element {
-                tag: "div".to_owned(),
+                tag: "div".into(),
                 attributes: Attributes::new(),
                 is_component: false,
                 self_closing: false,
                 children: vec![
                     TmplAst::ConditionalDirective(vec![ConditionalBlock::If {
-                        condition: "1 + 1 == 2".to_owned(),
+                        condition: "1 + 1 == 2".into(),
                         children: vec![TmplAst::Element {
-                            tag: "span".to_owned(),
+                            tag: "span".into(),
                             attributes: Attributes::new(),
                             is_component: false,
                             self_closing: false,
@@ -1103,9 +1229,9 @@ mod tests {
                         }],
                     }]),
                     TmplAst::ConditionalDirective(vec![ConditionalBlock::If {
-                        condition: "1 + 1 == 2".to_owned(),
+                        condition: "1 + 1 == 2".into(),
                         children: vec![TmplAst::Element {
-                            tag: "span".to_owned(),
+                            tag: "span".into(),
                             attributes: Attributes::new(),
                             is_component: false,
                             self_closing: false,
@@ -1119,7 +1245,8 @@ mod tests {
 
     #[test]
     fn conditions_with_after_elements() {
-        let mut chars = r#"
+        let mut chars = TmplChars::from(
+            r#"
             <div>
                 {#if true}
                     <span>Hello, world!</span>
@@ -1127,24 +1254,23 @@ mod tests {
 
                 <span>Hello, world 2!</span>
             </div>
-        "#
-        .chars()
-        .peekable();
+        "#,
+        );
 
-        let (ast, _) = process_chars_until(&mut chars, None);
+        let (ast, _) = process_chars_until(&mut chars, None).unwrap();
 
         assert_eq!(
             ast,
             vec![TmplAst::Element {
-                tag: "div".to_owned(),
+                tag: "div".into(),
                 attributes: Attributes::new(),
                 is_component: false,
                 self_closing: false,
                 children: vec![
                     TmplAst::ConditionalDirective(vec![ConditionalBlock::If {
-                        condition: "true".to_owned(),
+                        condition: "true".into(),
                         children: vec![TmplAst::Element {
-                            tag: "span".to_owned(),
+                            tag: "span".into(),
                             attributes: Attributes::new(),
                             is_component: false,
                             self_closing: false,
@@ -1152,7 +1278,7 @@ mod tests {
                         }],
                     }]),
                     TmplAst::Element {
-                        tag: "span".to_owned(),
+                        tag: "span".into(),
                         attributes: Attributes::new(),
                         is_component: false,
                         self_closing: false,
@@ -1165,21 +1291,21 @@ mod tests {
 
     #[test]
     fn outlet_directive() {
-        let mut chars = "{#outlet}".chars().peekable();
-        let (ast, _) = process_chars_until(&mut chars, None);
+        let mut chars = TmplChars::from("{#outlet}");
+        let (ast, _) = process_chars_until(&mut chars, None).unwrap();
 
         assert_eq!(ast, vec![TmplAst::Outlet]);
     }
 
     #[test]
     fn outlet_directive_in_element() {
-        let mut chars = "<div>{#outlet}</div>".chars().peekable();
-        let (ast, _) = process_chars_until(&mut chars, None);
+        let mut chars = TmplChars::from("<div>{#outlet}</div>");
+        let (ast, _) = process_chars_until(&mut chars, None).unwrap();
 
         assert_eq!(
             ast,
             vec![TmplAst::Element {
-                tag: "div".to_owned(),
+                tag: "div".into(),
                 attributes: Attributes::new(),
                 is_component: false,
                 self_closing: false,
@@ -1187,4 +1313,121 @@ mod tests {
             }]
         );
     }
+
+    #[test]
+    fn unclosed_element() {
+        let mut chars = TmplChars::from("<div><span>Hello</span>");
+        let error = process_chars_until(&mut chars, None).unwrap_err();
+
+        assert_eq!(
+            error.to_string(),
+            "unclosed element `<div>`, expected `</div>`"
+        );
+    }
+
+    #[test]
+    fn mismatched_closing_tag() {
+        let mut chars = TmplChars::from("<div><span>Hello</div></span>");
+        let error = process_chars_until(&mut chars, None).unwrap_err();
+
+        assert_eq!(
+            error.to_string(),
+            "mismatched closing tag `</div>`, expected `</span>`"
+        );
+    }
+
+    #[test]
+    fn unexpected_closing_tag() {
+        let mut chars = TmplChars::from("Hello</div>");
+        let error = process_chars_until(&mut chars, None).unwrap_err();
+
+        assert_eq!(error.to_string(), "unexpected closing tag `</div>`");
+    }
+
+    #[test]
+    fn unknown_directive() {
+        let mut chars = TmplChars::from("{#each items}");
+        let error = process_chars_until(&mut chars, None).unwrap_err();
+
+        assert_eq!(
+            error.to_string(),
+            "unknown directive `#each`, expected `#if`, `#component` or `#outlet`"
+        );
+    }
+
+    #[test]
+    fn invalid_expression() {
+        let mut chars = TmplChars::from("<p>{1 +}</p>");
+        let error = process_chars_until(&mut chars, None).unwrap_err();
+
+        assert!(error.to_string().starts_with("invalid expression `1 +`"));
+    }
+
+    #[test]
+    fn unknown_element() {
+        let mut chars = TmplChars::from("<div><buton>Save</buton></div>");
+        let error = process_chars_until(&mut chars, None).unwrap_err();
+
+        assert_eq!(
+            error.to_string(),
+            "unknown element `<buton>`, did you mean `<button>`?"
+        );
+    }
+
+    #[test]
+    fn unknown_attribute() {
+        let mut chars = TmplChars::from(r#"<div clas="card"></div>"#);
+        let error = process_chars_until(&mut chars, None).unwrap_err();
+
+        assert_eq!(
+            error.to_string(),
+            "unknown attribute `clas` on `<div>`, did you mean `class`?"
+        );
+    }
+
+    #[test]
+    fn invalid_nesting() {
+        let mut chars = TmplChars::from("<p><div>Hello</div></p>");
+        let error = process_chars_until(&mut chars, None).unwrap_err();
+
+        assert!(
+            error
+                .to_string()
+                .starts_with("`<div>` can't be a child of `<p>`")
+        );
+    }
+
+    #[test]
+    fn allowed_lints() {
+        let lints = TmplLints::from_attributes(&[
+            syn::parse_quote!(#![allow(unknown_elements, unknown_attributes, invalid_nesting)]),
+        ])
+        .unwrap();
+        let mut chars = TmplChars::from(
+            r#"<p><x-card clas="card"><div foo="bar"></div></x-card><buton></buton></p>"#,
+        )
+        .with_lints(lints);
+
+        assert!(process_chars_until(&mut chars, None).is_ok());
+    }
+
+    #[test]
+    fn a11y_warnings() {
+        let mut chars = TmplChars::from(
+            r#"<img src="/a.png"><label>Name</label><div onclick={save}>Save</div>"#,
+        );
+        process_chars_until(&mut chars, None).unwrap();
+        let warnings = chars.lints().warning_tokens().to_string();
+
+        assert!(warnings.contains("`<img>` is missing an `alt` attribute"));
+        assert!(warnings.contains("`<label>` has no `for` attribute"));
+        assert!(warnings.contains("`<div>` has a click handler"));
+
+        let mut chars = TmplChars::from(
+            r#"<img src="/a.png" alt=""><label>Name <input></label><button onclick={save}>Save</button>"#,
+        );
+        process_chars_until(&mut chars, None).unwrap();
+
+        assert!(chars.lints().warning_tokens().is_empty());
+    }
 }
@@ -0,0 +1,306 @@
+use std::rc::Rc;
+
+use proc_macro2::{Delimiter, LineColumn, Span, TokenStream, TokenTree};
+
+use crate::tmpl::{TmplExpr, lints::TmplLints};
+
+/// Character of the template source along with the span of the token it came from and
+/// the index of that token, `None` for whitespace between tokens. Characters read from a
+/// template file also know their line and column in that file.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct SourceChar {
+    pub(crate) ch: char,
+    pub(crate) span: Span,
+    pub(crate) token: Option<usize>,
+    pub(crate) location: Option<LineColumn>,
+}
+
+/// Position in the template to report errors at: a span of the macro input, plus the
+/// file location for templates read from a file, as spans can't point into those
+#[derive(Clone)]
+pub(crate) struct TmplSpan {
+    span: Span,
+    location: Option<(Rc<str>, LineColumn)>,
+}
+
+impl TmplSpan {
+    pub(crate) fn span(&self) -> Span {
+        self.span
+    }
+
+    pub(crate) fn error(&self, message: impl std::fmt::Display) -> syn::Error {
+        syn::Error::new(self.span, self.message(message))
+    }
+
+    /// Message with the file location appended, when there is one
+    pub(crate) fn message(&self, message: impl std::fmt::Display) -> String {
+        match &self.location {
+            Some((file, location)) => format!(
+                "{message}\n --> {file}:{}:{}",
+                location.line,
+                location.column + 1
+            ),
+            None => message.to_string(),
+        }
+    }
+}
+
+/// Character cursor over the template source that remembers the span of the token
+/// every character came from, so parse errors point at the original template and
+/// expressions keep their original tokens
+#[derive(Clone)]
+pub(crate) struct TmplChars {
+    chars: Rc<[SourceChar]>,
+    /// Tokens the characters were written from; empty when the source is a plain string
+    tokens: Rc<[TokenTree]>,
+    /// Template file the characters were read from
+    file: Option<Rc<str>>,
+    lints: Rc<TmplLints>,
+    position: usize,
+}
+
+impl TmplChars {
+    pub(crate) fn new(chars: Vec<SourceChar>, tokens: Vec<TokenTree>) -> Self {
+        Self {
+            chars: chars.into(),
+            tokens: tokens.into(),
+            file: None,
+            lints: Rc::default(),
+            position: 0,
+        }
+    }
+
+    pub(crate) fn with_lints(self, lints: TmplLints) -> Self {
+        Self {
+            lints: Rc::new(lints),
+            ..self
+        }
+    }
+
+    pub(crate) fn lints(&self) -> &Rc<TmplLints> {
+        &self.lints
+    }
+
+    /// Characters of a template file, all pointing at `span` of the macro input that
+    /// names the file
+    pub(crate) fn from_file(file: &str, content: &str, span: Span) -> Self {
+        let mut location = LineColumn { line: 1, column: 0 };
+        let chars = content
+            .chars()
+            .map(|ch| {
+                let source_char = SourceChar {
+                    ch,
+                    span,
+                    token: None,
+                    location: Some(location),
+                };
+
+                if ch == '\n' {
+                    location = LineColumn {
+                        line: location.line + 1,
+                        column: 0,
+                    };
+                } else {
+                    location.column += 1;
+                }
+
+                source_char
+            })
+            .collect::<Vec<_>>();
+
+        Self {
+            file: Some(file.into()),
+            ..Self::new(chars, Vec::new())
+        }
+    }
+
+    pub(crate) fn peek(&self) -> Option<&char> {
+        self.chars
+            .get(self.position)
+            .map(|source_char| &source_char.ch)
+    }
+
+    /// Span of the next character, or of the last one once the input is exhausted
+    pub(crate) fn span(&self) -> TmplSpan {
+        let source_char = self.chars.get(self.position).or_else(|| self.chars.last());
+
+        TmplSpan {
+            span: source_char.map_or_else(Span::call_site, |source_char| source_char.span),
+            location: self.file.clone().zip(source_char.and_then(|c| c.location)),
+        }
+    }
+
+    /// Error pointing at the next character
+    pub(crate) fn error(&self, message: impl std::fmt::Display) -> syn::Error {
+        self.span().error(message)
+    }
+
+    /// Apply `f` to the remaining characters, keeping the tokens they refer to
+    pub(crate) fn map_chars(self, f: impl FnOnce(Vec<SourceChar>) -> Vec<SourceChar>) -> Self {
+        Self {
+            chars: f(self.chars[self.position..].to_vec()).into(),
+            tokens: self.tokens,
+            file: self.file,
+            lints: self.lints,
+            position: 0,
+        }
+    }
+
+    /// Consume everything up to and including the `}` closing the current brace group and
+    /// return it as an expression. Nested groups and braces in literals are skipped, as the
+    /// boundaries come from the original token trees.
+    pub(crate) fn take_until_closing_brace(&mut self) -> Option<TmplExpr> {
+        let start = self.position;
+        let mut nesting_level = 0;
+        let mut in_string = false;
+
+        loop {
+            let source_char = *self.chars.get(self.position)?;
+
+            // Without tokens, string literals are the only place braces are told apart in
+            if self.tokens.is_empty() && source_char.ch == '"' {
+                let escaped = self.position > start && self.chars[self.position - 1].ch == '\\';
+
+                if !escaped {
+                    in_string = !in_string;
+                }
+            }
+
+            if !in_string && self.is_brace(source_char) {
+                match source_char.ch {
+                    '{' => nesting_level += 1,
+                    '}' if nesting_level == 0 => break,
+                    '}' => nesting_level -= 1,
+                    _ => {}
+                }
+            }
+
+            self.position += 1;
+        }
+
+        let expr = self.expr_between(start, self.position);
+        self.position += 1; // consume '}'
+
+        Some(expr)
+    }
+
+    /// Whether the character is a delimiter of a brace group rather than, say, part of a
+    /// string literal. Without tokens every brace counts.
+    fn is_brace(&self, source_char: SourceChar) -> bool {
+        if !matches!(source_char.ch, '{' | '}') {
+            return false;
+        }
+
+        if self.tokens.is_empty() {
+            return true;
+        }
+
+        source_char.token.is_some_and(|token| {
+            matches!(&self.tokens[token], TokenTree::Group(group) if group.delimiter() == Delimiter::Brace)
+        })
+    }
+
+    /// Expression made of the characters in `start..end`, rebuilt from the original tokens
+    fn expr_between(&self, start: usize, end: usize) -> TmplExpr {
+        let chars = &self.chars[start..end];
+        let source = chars
+            .iter()
+            .map(|source_char| source_char.ch)
+            .collect::<String>()
+            .trim()
+            .to_owned();
+
+        if self.tokens.is_empty() {
+            return TmplExpr::new(source.clone(), source.parse().unwrap_or_default());
+        }
+
+        let mut tokens = TokenStream::new();
+        let mut last_token = None;
+        let mut i = 0;
+
+        while i < chars.len() {
+            if let Some(token) = chars[i].token {
+                if last_token != Some(token) {
+                    last_token = Some(token);
+                    tokens.extend([self.tokens[token].clone()]);
+
+                    // A group is taken whole, skip to its closing delimiter
+                    if let TokenTree::Group(_) = &self.tokens[token] {
+                        i = (i + 1..chars.len())
+                            .find(|&j| chars[j].token == Some(token))
+                            .unwrap_or(chars.len());
+                    }
+                }
+            }
+
+            i += 1;
+        }
+
+        TmplExpr::new(source, tokens)
+    }
+}
+
+impl Iterator for TmplChars {
+    type Item = char;
+
+    fn next(&mut self) -> Option<char> {
+        let source_char = self.chars.get(self.position)?;
+        self.position += 1;
+
+        Some(source_char.ch)
+    }
+}
+
+impl From<&str> for TmplChars {
+    fn from(input: &str) -> Self {
+        let chars = input
+            .chars()
+            .map(|ch| SourceChar {
+                ch,
+                span: Span::call_site(),
+                token: None,
+                location: None,
+            })
+            .collect();
+
+        Self::new(chars, Vec::new())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tmpl::tokens_to_source::tokens_to_source;
+
+    #[test]
+    fn test_take_until_closing_brace() {
+        let mut chars = TmplChars::from("{ {a} + b } rest");
+        chars.next(); // consume '{'
+
+        let expr = chars.take_until_closing_brace().unwrap();
+
+        assert_eq!(expr.as_str(), "{a} + b");
+        assert_eq!(chars.collect::<String>(), " rest");
+    }
+
+    #[test]
+    fn test_take_until_closing_brace_skips_braces_in_literals() {
+        let tokens = r#"<p>{format!("}{", name)}</p>"#.parse().unwrap();
+        let mut chars = tokens_to_source(tokens);
+
+        while chars.next() != Some('{') {}
+
+        let expr = chars.take_until_closing_brace().unwrap();
+
+        assert_eq!(expr.as_str(), r#"format!("}{", name)"#);
+        assert!(expr.to_expr().is_ok());
+        assert_eq!(chars.collect::<String>(), "</p>");
+    }
+
+    #[test]
+    fn test_unclosed_brace() {
+        let mut chars = TmplChars::from("a + b");
+
+        assert!(chars.take_until_closing_brace().is_none());
+    }
+}
@@ -1,7 +1,10 @@
 use std::collections::HashSet;
 
-use crate::tmpl::{Attribute, ConditionalBlock, TmplAst};
-use quote::quote;
+use crate::tmpl::{
+    Attribute, ConditionalBlock, TmplAst, TmplExpr, TmplTag, is_escapable_raw_text_element,
+    is_raw_text_element, is_void_element,
+};
+use quote::{format_ident, quote, quote_spanned};
 use syn::{Ident, visit::Visit};
 
 struct IdentifierVisitor {
@@ -24,7 +27,7 @@ pub(crate) fn collect_variables_from_ast(ast_nodes: &[TmplAst]) -> Vec<Ident> {
     fn visit_ast_node(node: &TmplAst, visitor: &mut IdentifierVisitor) {
         match node {
             TmplAst::Expression(expr) => {
-                if let Ok(expr_tokens) = syn::parse_str::<syn::Expr>(expr) {
+                if let Ok(expr_tokens) = expr.to_expr() {
                     visitor.visit_expr(&expr_tokens);
                 }
             }
@@ -39,8 +42,10 @@ pub(crate) fn collect_variables_from_ast(ast_nodes: &[TmplAst]) -> Vec<Ident> {
 
                 for attr in attributes.values() {
                     match attr {
-                        Attribute::Expression(expr) | Attribute::EventListener(expr) => {
-                            if let Ok(expr_tokens) = syn::parse_str::<syn::Expr>(expr) {
+                        Attribute::Expression(expr)
+                        | Attribute::EventListener(expr)
+                        | Attribute::Spread(expr) => {
+                            if let Ok(expr_tokens) = expr.to_expr() {
                                 visitor.visit_expr(&expr_tokens);
                             }
                         }
@@ -48,15 +53,60 @@ pub(crate) fn collect_variables_from_ast(ast_nodes: &[TmplAst]) -> Vec<Ident> {
                     }
                 }
             }
+            TmplAst::DynamicComponent {
+                component,
+                candidates,
+                children,
+            } => {
+                for expr in std::iter::once(component).chain(candidates) {
+                    if let Ok(expr) = expr.to_expr() {
+                        visitor.visit_expr(&expr);
+                    }
+                }
+
+                for child in children {
+                    visit_ast_node(child, visitor);
+                }
+            }
             TmplAst::SlotInterpolation {
-                default_children, ..
+                slot_name,
+                props,
+                default_children,
             } => {
+                if let Some(props) = props {
+                    if let Ok(props) = props.to_expr() {
+                        visitor.visit_expr(&props);
+                    }
+
+                    if let Some(slot_name) = slot_name {
+                        visitor.visit_path(
+                            &syn::Ident::new(slot_name, proc_macro2::Span::call_site()).into(),
+                        );
+                    }
+                }
+
                 if let Some(children) = default_children {
                     for child in children {
                         visit_ast_node(child, visitor);
                     }
                 }
             }
+            TmplAst::Slot {
+                binding: Some(binding),
+                children,
+                ..
+            } => {
+                // Variables bound by `let:` come from the slot, not the surrounding scope
+                let bound = syn::parse::Parser::parse_str(syn::Pat::parse_single, binding)
+                    .map(|binding| pattern_identifiers(&binding))
+                    .unwrap_or_default();
+
+                for var in collect_variables_from_ast(children) {
+                    if !bound.contains(&var) && visitor.seen.insert(var.to_string()) {
+                        visitor.identifiers.push(var);
+                    }
+                }
+            }
             TmplAst::Slot { children, .. } => {
                 for child in children {
                     visit_ast_node(child, visitor);
@@ -75,7 +125,7 @@ pub(crate) fn collect_variables_from_ast(ast_nodes: &[TmplAst]) -> Vec<Ident> {
                     }
                 }
             }
-            TmplAst::Text(_) | TmplAst::Outlet => {}
+            TmplAst::Text(_) | TmplAst::Outlet | TmplAst::Comment(_) | TmplAst::Doctype(_) => {}
         }
     }
 
@@ -106,8 +156,12 @@ impl<'ast> Visit<'ast> for IdentifierVisitor {
                 return;
             }
 
+            // The captured clones are macro plumbing, so they get the call-site span rather
+            // than the one of the expression, which keeps lints on them out of user code
             if self.seen.insert(ident_str) {
-                self.identifiers.push(ident.clone());
+                let mut ident = ident.clone();
+                ident.set_span(proc_macro2::Span::call_site());
+                self.identifiers.push(ident);
             }
         }
 
@@ -126,6 +180,173 @@ impl<'ast> Visit<'ast> for IdentifierVisitor {
     }
 }
 
+/// Variables bound by the `let:` pattern of a slot
+fn pattern_identifiers(pattern: &syn::Pat) -> Vec<Ident> {
+    struct PatternVisitor(Vec<Ident>);
+
+    impl<'ast> Visit<'ast> for PatternVisitor {
+        fn visit_pat_ident(&mut self, pat_ident: &'ast syn::PatIdent) {
+            self.0.push(pat_ident.ident.clone());
+            syn::visit::visit_pat_ident(self, pat_ident);
+        }
+    }
+
+    let mut visitor = PatternVisitor(Vec::new());
+    visitor.visit_pat(pattern);
+    visitor.0
+}
+
+fn is_class_directive(name: &str) -> bool {
+    name.starts_with("class:")
+}
+
+fn is_style_directive(name: &str) -> bool {
+    name.starts_with("style:")
+}
+
+/// Collects `class:name={cond}` or `style:prop={value}` directives as `(name, expr)` pairs
+/// sorted by name
+fn collect_directives(
+    attributes: &crate::tmpl::Attributes,
+    is_directive: fn(&str) -> bool,
+) -> Vec<(String, syn::Expr)> {
+    let mut directives = attributes
+        .iter()
+        .filter(|(k, _)| is_directive(k))
+        .filter_map(|(k, v)| {
+            let (_, name) = k.split_once(':')?;
+
+            match v {
+                Attribute::Expression(expr) => {
+                    expr.to_expr().ok().map(|expr| (name.to_owned(), expr))
+                }
+                _ => None,
+            }
+        })
+        .collect::<Vec<_>>();
+
+    directives.sort_by(|(a, _), (b, _)| a.cmp(b));
+    directives
+}
+
+/// Builds an expression evaluating to `Option<String>` with the base `class`/`style` value
+/// merged with the values of its directives
+fn merged_attribute_value(
+    name: &str,
+    base: Option<&Attribute>,
+    directives: &[(String, syn::Expr)],
+) -> proc_macro2::TokenStream {
+    let base = match base {
+        Some(Attribute::Literal(value)) => quote! { apex_attrs.set(#name, #value); },
+        Some(Attribute::Expression(expr)) => match expr.to_expr() {
+            Ok(expr_tokens) => quote! { apex_attrs.set(#name, (#expr_tokens).to_string()); },
+            Err(_) => quote! {},
+        },
+        _ => quote! {},
+    };
+
+    let directives = directives.iter().map(|(directive, expr)| {
+        if name == "class" {
+            quote! {
+                if #expr {
+                    apex_attrs.set("class", #directive);
+                }
+            }
+        } else {
+            quote! {
+                let value = (#expr).to_string();
+                if !value.is_empty() {
+                    apex_attrs.set("style", format!("{}: {}", #directive, value));
+                }
+            }
+        }
+    });
+
+    quote! {
+        {
+            let mut apex_attrs = apex::helpers::HtmlAttributes::new();
+            #base
+            #(#directives)*
+            apex_attrs.get(#name).filter(|value| !value.is_empty()).cloned()
+        }
+    }
+}
+
+/// Builds an `apex::helpers::HtmlAttributes` expression for an element with `{..attrs}` spreads.
+/// Spreads are applied first, so explicit attributes override them (`class`/`style` are merged)
+fn spread_attribute_set(
+    attributes: &crate::tmpl::Attributes,
+    class_directives: &[(String, syn::Expr)],
+    style_directives: &[(String, syn::Expr)],
+) -> proc_macro2::TokenStream {
+    let mut spreads = attributes
+        .iter()
+        .filter_map(|(k, v)| match v {
+            Attribute::Spread(expr) => {
+                let position = k
+                    .trim_start_matches("..")
+                    .parse::<usize>()
+                    .unwrap_or_default();
+
+                expr.to_expr().ok().map(|expr| (position, expr))
+            }
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    spreads.sort_by_key(|(position, _)| *position);
+
+    let spreads = spreads.into_iter().map(|(_, expr)| {
+        quote! {
+            apex_attrs.merge(apex::helpers::into_html_attrs((#expr).clone()));
+        }
+    });
+
+    let mut sorted_attributes: Vec<_> = attributes.iter().collect();
+    sorted_attributes.sort_by_key(|(k, _)| *k);
+
+    let setters = sorted_attributes
+        .into_iter()
+        .filter(|(k, _)| !is_class_directive(k) && !is_style_directive(k))
+        .filter_map(|(k, v)| match v {
+            Attribute::Empty => Some(quote! { apex_attrs.set(#k, ""); }),
+            Attribute::Literal(value) => Some(quote! { apex_attrs.set(#k, #value); }),
+            Attribute::Expression(expr) => expr
+                .to_expr()
+                .ok()
+                .map(|expr| quote! { apex_attrs.set(#k, (#expr).to_string()); }),
+            _ => None,
+        });
+
+    let class = (!class_directives.is_empty()).then(|| {
+        let merged = merged_attribute_value("class", None, class_directives);
+        quote! {
+            if let Some(value) = #merged {
+                apex_attrs.set("class", value);
+            }
+        }
+    });
+
+    let style = (!style_directives.is_empty()).then(|| {
+        let merged = merged_attribute_value("style", None, style_directives);
+        quote! {
+            if let Some(value) = #merged {
+                apex_attrs.set("style", value);
+            }
+        }
+    });
+
+    quote! {
+        {
+            let mut apex_attrs = apex::helpers::HtmlAttributes::new();
+            #(#spreads)*
+            #(#setters)*
+            #class
+            #style
+            apex_attrs
+        }
+    }
+}
+
 fn trim_whitespace_around_slots(content: &[TmplAst]) -> Vec<TmplAst> {
     let mut result = Vec::new();
     let mut i = 0;
@@ -188,7 +409,7 @@ pub(crate) fn render_ast(
                 });
             }
             TmplAst::Expression(expr) => {
-                if let Ok(expr_tokens) = syn::parse_str::<syn::Expr>(expr) {
+                if let Ok(expr_tokens) = expr.to_expr() {
                     let mut visitor = IdentifierVisitor::new();
                     visitor.visit_expr(&expr_tokens);
 
@@ -229,27 +450,45 @@ pub(crate) fn render_ast(
                 tag,
                 attributes,
                 is_component,
-                self_closing,
+                self_closing: _,
                 children,
             } => {
                 if *is_component {
-                    let component_name = syn::Ident::new(tag, proc_macro2::Span::call_site());
+                    // The component and its `build()` are spanned at the tag, so a missing
+                    // required prop is reported there
+                    let component_name = syn::Ident::new(tag, tag.span());
+                    let build = quote_spanned! {tag.span()=> builder.build()};
 
                     // Generate builder method calls for each attribute
                     let mut builder_chain = quote! { #component_name::builder() };
 
-                    for (key, value) in attributes {
+                    let mut sorted_attributes: Vec<_> = attributes.iter().collect();
+                    sorted_attributes.sort_by_key(|(k, _)| *k);
+
+                    for (key, value) in sorted_attributes {
+                        if let Attribute::Spread(expr) = value {
+                            if let Ok(expr_tokens) = expr.to_expr() {
+                                builder_chain = quote! {
+                                    #builder_chain.spread_attrs(apex::helpers::into_html_attrs((#expr_tokens).clone()))
+                                };
+                            }
+
+                            continue;
+                        }
+
                         let method_name = syn::Ident::new(key, proc_macro2::Span::call_site());
 
                         builder_chain = match value {
                             Attribute::Empty => continue,
                             Attribute::Literal(literal) => {
+                                let literal_method_name = format_ident!("__literal_{key}");
+
                                 quote! {
-                                    #builder_chain.#method_name(#literal.into())
+                                    #builder_chain.#literal_method_name(#literal)
                                 }
                             }
                             Attribute::Expression(expr) => {
-                                if let Ok(expr_tokens) = syn::parse_str::<syn::Expr>(expr) {
+                                if let Ok(expr_tokens) = expr.to_expr() {
                                     quote! {
                                         #builder_chain.#method_name(#expr_tokens)
                                     }
@@ -258,7 +497,7 @@ pub(crate) fn render_ast(
                                 }
                             }
                             Attribute::EventListener(handler) => {
-                                if let Ok(handler_tokens) = syn::parse_str::<syn::Expr>(handler) {
+                                if let Ok(handler_tokens) = handler.to_expr() {
                                     quote! {
                                         #builder_chain.#method_name(#handler_tokens)
                                     }
@@ -266,147 +505,17 @@ pub(crate) fn render_ast(
                                     continue;
                                 }
                             }
+                            Attribute::Spread(_) => continue,
                         };
                     }
 
-                    let mut render_slots_map = quote! { std::collections::HashMap::new() };
-                    let mut hydrate_slots_map = quote! { std::collections::HashMap::new() };
-                    let mut regular_children = Vec::new();
-                    let mut all_slot_expressions = Vec::new();
-
-                    for child in children {
-                        if let TmplAst::Slot {
-                            name: Some(slot_name),
-                            children: slot_children,
-                        } = child
-                        {
-                            let (slot_instructions, slot_expressions) = render_ast(slot_children);
-                            let slot_vars = collect_variables_from_ast(slot_children);
-
-                            if slot_vars.is_empty() {
-                                // No variables to capture, create simple closures
-                                render_slots_map = quote! {
-                                    {
-                                        let mut map = #render_slots_map;
-
-                                        map.insert(#slot_name.to_string(), std::rc::Rc::new(Box::new(move |buffer: &mut String, data: std::rc::Rc<std::cell::RefCell<std::collections::HashMap<String, serde_json::Value>>>| {
-                                            #(#slot_instructions)*
-                                        }) as Box<dyn for<'a> Fn(&'a mut String, std::rc::Rc<std::cell::RefCell<std::collections::HashMap<String, serde_json::Value>>>) + 'static>));
-
-                                        map
-                                    }
-                                };
-                                // Hydration closures for named slots
-                                hydrate_slots_map = quote! {
-                                    {
-                                        let mut map = #hydrate_slots_map;
-
-                                        map.insert(#slot_name.to_string(), std::rc::Rc::new(Box::new(move |
-                                            state: std::rc::Rc<std::cell::RefCell<apex_router::client_router::State>>
-                                        | {
-                                            #(#slot_expressions)*
-                                        }) as Box<dyn Fn(std::rc::Rc<std::cell::RefCell<apex_router::client_router::State>>) + 'static>));
-
-                                        map
-                                    }
-                                };
-                            } else {
-                                // Variables need to be captured - each slot gets its own closure with cloned variables
-                                render_slots_map = quote! {
-                                    {
-                                        let mut map = #render_slots_map;
-
-                                        map.insert(#slot_name.to_string(), std::rc::Rc::new(Box::new({
-                                            // Clone variables for this specific slot closure
-                                            #(let #slot_vars = #slot_vars.clone();)*
-
-                                            move |buffer: &mut String, data: std::rc::Rc<std::cell::RefCell<std::collections::HashMap<String, serde_json::Value>>>| {
-                                                #(#slot_instructions)*
-                                            }
-                                        }) as Box<dyn for<'a> Fn(&'a mut String, std::rc::Rc<std::cell::RefCell<std::collections::HashMap<String, serde_json::Value>>>) + 'static>));
-
-                                        map
-                                    }
-                                };
-
-                                // Hydration closures for named slots with captured vars
-                                hydrate_slots_map = quote! {
-                                    {
-                                        let mut map = #hydrate_slots_map;
-
-                                        map.insert(#slot_name.to_string(), std::rc::Rc::new(Box::new({
-                                            #(let #slot_vars = #slot_vars.clone();)*
-
-                                            move |
-                                                state: std::rc::Rc<std::cell::RefCell<apex_router::client_router::State>>
-                                            | {
-                                                #(#slot_expressions)*
-                                            }
-                                        }) as Box<dyn Fn(std::rc::Rc<std::cell::RefCell<apex_router::client_router::State>>) + 'static>));
-
-                                        map
-                                    }
-                                };
-                            }
-
-                            all_slot_expressions.extend(slot_expressions);
-                        } else {
-                            regular_children.push(child.clone());
-                        }
-                    }
-
-                    // Handle regular children (unnamed slot)
-                    let (children_instructions, children_expressions) =
-                        render_ast(&regular_children);
-
-                    if !regular_children.is_empty() {
-                        let children_vars = collect_variables_from_ast(&regular_children);
-
-                        if children_vars.is_empty() {
-                            // No variables to capture, create a simple closure
-                            builder_chain = quote! {
-                                #builder_chain
-                                    .render_children(Box::new(move |buffer: &mut String, data: std::rc::Rc<std::cell::RefCell<std::collections::HashMap<String, serde_json::Value>>>| {
-                                        #(#children_instructions)*
-                                    }))
-                                    .hydrate_children(Box::new(move |
-                                        state: std::rc::Rc<std::cell::RefCell<apex_router::client_router::State>>
-                                    | {
-                                        #(#children_expressions)*
-                                    }))
-                            };
-                        } else {
-                            // Variables need to be captured
-                            builder_chain = quote! {
-                                #builder_chain
-                                    .render_children(Box::new({
-                                        #(let #children_vars = #children_vars.clone();)*
-
-                                        move |buffer: &mut String, data: std::rc::Rc<std::cell::RefCell<std::collections::HashMap<String, serde_json::Value>>>| {
-                                            #(#children_instructions)*
-                                        }
-                                    }))
-                                    .hydrate_children(Box::new({
-                                        #(let #children_vars = #children_vars.clone();)*
-
-                                        move |
-                                            state: std::rc::Rc<std::cell::RefCell<apex_router::client_router::State>>
-                                        | {
-                                            #(#children_expressions)*
-                                        }
-                                    }))
-                            };
-                        }
-                    }
-
-                    builder_chain = quote! {
-                        #builder_chain.named_slots(#render_slots_map).hydrate_named_slots(#hydrate_slots_map)
-                    };
+                    let builder_chain = slots_builder_chain(builder_chain, children, true);
 
                     instructions.push(quote! {
                         #[cfg(not(target_arch = "wasm32"))]
                         {
-                            let component_instance = #builder_chain.build();
+                            let builder = #builder_chain;
+                            let component_instance = #build;
                             // Data is passed from the route, so it might not exist if this component is rendered not in the route
                             let component_html = component_instance.render(data.clone());
 
@@ -417,7 +526,8 @@ pub(crate) fn render_ast(
                     expressions.push(quote! {
                         #[cfg(target_arch = "wasm32")]
                         {
-                            let component_instance = #builder_chain.build();
+                            let builder = #builder_chain;
+                            let component_instance = #build;
                             let hydrate = component_instance.hydrate();
 
                             hydrate(state.clone())
@@ -427,9 +537,26 @@ pub(crate) fn render_ast(
                     let tag_name = tag.clone();
                     let element_counter = quote! { apex::apex_utils::next_element_counter() };
 
-                    let comment_element = if attributes.iter().any(|(_, v)| {
-                        matches!(v, Attribute::EventListener(_) | Attribute::Expression(_))
-                    }) {
+                    let has_spread = attributes
+                        .values()
+                        .any(|v| matches!(v, Attribute::Spread(_)));
+
+                    // The text of `<title>` and `<textarea>` is updated as a whole
+                    let has_text_expressions = is_escapable_raw_text_element(&tag_name)
+                        && children
+                            .iter()
+                            .any(|child| matches!(child, TmplAst::Expression(_)));
+
+                    let comment_element = if has_text_expressions
+                        || attributes.iter().any(|(k, v)| {
+                            matches!(
+                                v,
+                                Attribute::EventListener(_)
+                                    | Attribute::Expression(_)
+                                    | Attribute::Spread(_)
+                            ) || is_class_directive(k)
+                                || is_style_directive(k)
+                        }) {
                         quote! {
                             {
                                 buffer.push_str("<!-- @element:");
@@ -443,42 +570,168 @@ pub(crate) fn render_ast(
 
                     // Sort attributes for consistent ordering in tests
                     let mut sorted_attributes: Vec<_> = attributes.iter().collect();
-                    sorted_attributes.sort_by(|(k1, _), (k2, _)| k1.cmp(k2));
+                    sorted_attributes.sort_by_key(|(k, _)| *k);
+
+                    let class_directives = collect_directives(attributes, is_class_directive);
+                    let style_directives = collect_directives(attributes, is_style_directive);
+
+                    // `class` and `style` are emitted at their sorted position even when only
+                    // directives are present, so the merged value has a place to go
+                    let mut emitted_attributes: Vec<(&String, Option<&Attribute>)> =
+                        sorted_attributes
+                            .iter()
+                            .filter(|(k, _)| !is_class_directive(k) && !is_style_directive(k))
+                            .map(|(k, v)| (*k, Some(*v)))
+                            .collect();
+
+                    let class_key = "class".to_owned();
+                    let style_key = "style".to_owned();
+
+                    if !class_directives.is_empty() && !attributes.contains_key("class") {
+                        emitted_attributes.push((&class_key, None));
+                    }
+
+                    if !style_directives.is_empty() && !attributes.contains_key("style") {
+                        emitted_attributes.push((&style_key, None));
+                    }
+
+                    emitted_attributes.sort_by_key(|(k, _)| *k);
+
+                    let attr_setters = if has_spread {
+                        let attribute_set =
+                            spread_attribute_set(attributes, &class_directives, &style_directives);
+
+                        vec![quote! {
+                            {
+                                let attrs = #attribute_set;
+                                attrs.render_into(&mut buffer);
+                            }
+                        }]
+                    } else {
+                        emitted_attributes
+                            .iter()
+                            .filter_map(|(k, v)| match (k.as_str(), v) {
+                                ("class", _) if !class_directives.is_empty() => {
+                                    let merged =
+                                        merged_attribute_value("class", *v, &class_directives);
 
-                    let attr_setters = sorted_attributes
-                        .iter()
-                        .filter_map(|(k, v)| match v {
-                            Attribute::Empty | Attribute::EventListener(_) => None,
-                            Attribute::Literal(val) => Some(quote! {
-                                buffer.push_str(&(#k));
-                                buffer.push_str("=\"");
-                                buffer.push_str(&(#val));
-                                buffer.push_str("\"");
-                            }),
-                            Attribute::Expression(expr) => {
-                                if let Ok(expr_tokens) = syn::parse_str::<syn::Expr>(expr) {
                                     Some(quote! {
+                                        if let Some(value) = #merged {
+                                            buffer.push_str(" class=\"");
+                                            buffer.push_str(&value);
+                                            buffer.push_str("\"");
+                                        }
+                                    })
+                                }
+                                ("style", _) if !style_directives.is_empty() => {
+                                    let merged =
+                                        merged_attribute_value("style", *v, &style_directives);
+
+                                    Some(quote! {
+                                        if let Some(value) = #merged {
+                                            buffer.push_str(" style=\"");
+                                            buffer.push_str(&value);
+                                            buffer.push_str("\"");
+                                        }
+                                    })
+                                }
+                                (_, None) => None,
+                                (_, Some(v)) => match v {
+                                    // Boolean attributes render as a bare name
+                                    Attribute::Empty => Some(quote! {
+                                        buffer.push_str(" ");
+                                        buffer.push_str(&(#k));
+                                    }),
+                                    Attribute::EventListener(_) | Attribute::Spread(_) => None,
+                                    Attribute::Literal(val) => Some(quote! {
+                                        buffer.push_str(" ");
                                         buffer.push_str(&(#k));
                                         buffer.push_str("=\"");
-                                        buffer.push_str(&(#expr_tokens).to_string());
+                                        buffer.push_str(&(#val));
                                         buffer.push_str("\"");
-                                    })
-                                } else {
-                                    None
-                                }
-                            }
-                        })
-                        .collect::<Vec<_>>();
+                                    }),
+                                    Attribute::Expression(expr) => {
+                                        if let Ok(expr_tokens) = expr.to_expr() {
+                                            Some(quote! {
+                                                buffer.push_str(" ");
+                                                buffer.push_str(&(#k));
+                                                buffer.push_str("=\"");
+                                                buffer.push_str(&(#expr_tokens).to_string());
+                                                buffer.push_str("\"");
+                                            })
+                                        } else {
+                                            None
+                                        }
+                                    }
+                                },
+                            })
+                            .collect::<Vec<_>>()
+                    };
 
                     expressions.push(quote! {
                         let element_counter = #element_counter;
                     });
 
+                    if has_spread {
+                        let attribute_set =
+                            spread_attribute_set(attributes, &class_directives, &style_directives);
+
+                        let mut visitor = IdentifierVisitor::new();
+                        for value in attributes.values() {
+                            if let Attribute::Expression(expr) | Attribute::Spread(expr) = value {
+                                if let Ok(expr_tokens) = expr.to_expr() {
+                                    visitor.visit_expr(&expr_tokens);
+                                }
+                            }
+                        }
+                        let vars = visitor.identifiers;
+
+                        // `applied` holds the attributes set by the previous run, so ones dropped
+                        // from a spread get removed
+                        expressions.push(quote! {
+                            {
+                                #(let #vars = #vars.clone();)*
+
+                                if let Some(element) = state.borrow().elements_map.borrow().get(&element_counter.to_string()).cloned() {
+                                    let applied: std::rc::Rc<std::cell::RefCell<Vec<String>>> = Default::default();
+
+                                    apex::effect!({
+                                        let attrs = #attribute_set;
+                                        let mut applied = applied.borrow_mut();
+
+                                        for name in applied.iter() {
+                                            if attrs.get(name).is_none() {
+                                                let _ = element.remove_attribute(name);
+                                            }
+                                        }
+
+                                        applied.clear();
+
+                                        for (name, value) in attrs.iter() {
+                                            let _ = element.set_attribute(name, value);
+                                            applied.push(name.clone());
+                                        }
+                                    });
+                                } else {
+                                    apex::web_sys::console::warn_1(&format!("Warning: element {} not found during hydration", element_counter.to_string()).into());
+                                }
+                            }
+                        });
+                    }
+
                     let attr_setters_expressions = sorted_attributes
                         .iter()
+                        .filter(|_| !has_spread)
+                        .filter(|(k, _)| {
+                            let merged_by_directives = (k.as_str() == "class"
+                                && !class_directives.is_empty())
+                                || (k.as_str() == "style" && !style_directives.is_empty());
+
+                            !is_class_directive(k) && !is_style_directive(k) && !merged_by_directives
+                        })
                         .filter_map(|(k, v)| match v {
                             Attribute::Expression(expr) => {
-                                if let Ok(expr_tokens) = syn::parse_str::<syn::Expr>(expr) {
+                                if let Ok(expr_tokens) = expr.to_expr() {
                                     let mut visitor = IdentifierVisitor::new();
                                     visitor.visit_expr(&expr_tokens);
 
@@ -507,6 +760,106 @@ pub(crate) fn render_ast(
 
                     expressions.extend(attr_setters_expressions);
 
+                    // A dynamic base `class`/`style` is re-applied together with its directives
+                    for (name, directives) in
+                        [("class", &class_directives), ("style", &style_directives)]
+                    {
+                        let Some(Attribute::Expression(expr)) = attributes.get(name) else {
+                            continue;
+                        };
+
+                        if directives.is_empty() || has_spread {
+                            continue;
+                        }
+
+                        let Ok(expr_tokens) = expr.to_expr() else {
+                            continue;
+                        };
+
+                        let merged = merged_attribute_value(name, attributes.get(name), directives);
+
+                        let mut visitor = IdentifierVisitor::new();
+                        visitor.visit_expr(&expr_tokens);
+                        for (_, condition) in directives {
+                            visitor.visit_expr(condition);
+                        }
+                        let vars = visitor.identifiers;
+
+                        expressions.push(quote! {
+                            {
+                                #(let #vars = #vars.clone();)*
+
+                                if let Some(element) = state.borrow().elements_map.borrow().get(&element_counter.to_string()).cloned() {
+                                    apex::effect!({
+                                        let _ = element.set_attribute(#name, &#merged.unwrap_or_default());
+                                    });
+                                } else {
+                                    apex::web_sys::console::warn_1(&format!("Warning: element {} not found during hydration", element_counter.to_string()).into());
+                                }
+                            }
+                        });
+                    }
+
+                    let class_directive_expressions = class_directives.iter().filter(|_| !has_spread).map(|(class_name, condition)| {
+                        let mut visitor = IdentifierVisitor::new();
+                        visitor.visit_expr(condition);
+                        let vars = visitor.identifiers;
+
+                        quote! {
+                            {
+                                #(let #vars = #vars.clone();)*
+
+                                if let Some(element) = state.borrow().elements_map.borrow().get(&element_counter.to_string()).cloned() {
+                                    apex::effect!({
+                                        let _ = element.class_list().toggle_with_force(#class_name, #condition);
+                                    });
+                                } else {
+                                    apex::web_sys::console::warn_1(&format!("Warning: element {} not found during hydration", element_counter.to_string()).into());
+                                }
+                            }
+                        }
+                    });
+
+                    expressions.extend(class_directive_expressions);
+
+                    let style_directive_expressions = style_directives.iter().filter(|_| !has_spread).map(|(property, value)| {
+                        let mut visitor = IdentifierVisitor::new();
+                        visitor.visit_expr(value);
+                        let vars = visitor.identifiers;
+
+                        quote! {
+                            {
+                                use apex::wasm_bindgen::JsCast;
+
+                                #(let #vars = #vars.clone();)*
+
+                                if let Some(element) = state.borrow().elements_map.borrow().get(&element_counter.to_string()).cloned() {
+                                    // HTML, SVG and MathML elements all have a `style`, but only
+                                    // HtmlElement exposes it in web_sys
+                                    let style = apex::js_sys::Reflect::get(&element, &"style".into())
+                                        .ok()
+                                        .and_then(|style| style.dyn_into::<apex::web_sys::CssStyleDeclaration>().ok());
+
+                                    if let Some(style) = style {
+                                        apex::effect!({
+                                            let value = (#value).to_string();
+
+                                            if value.is_empty() {
+                                                let _ = style.remove_property(#property);
+                                            } else {
+                                                let _ = style.set_property(#property, &value);
+                                            }
+                                        });
+                                    }
+                                } else {
+                                    apex::web_sys::console::warn_1(&format!("Warning: element {} not found during hydration", element_counter.to_string()).into());
+                                }
+                            }
+                        }
+                    });
+
+                    expressions.extend(style_directive_expressions);
+
                     // Extract event handlers for server-side to prevent unused warnings
                     // This only runs at compile time and generates no runtime overhead
                     #[cfg(not(target_arch = "wasm32"))]
@@ -516,9 +869,7 @@ pub(crate) fn render_ast(
                             .filter_map(|(_k, v)| {
                                 match v {
                                     Attribute::EventListener(handler) => {
-                                        if let Ok(handler_tokens) =
-                                            syn::parse_str::<syn::Expr>(handler)
-                                        {
+                                        if let Ok(handler_tokens) = handler.to_expr() {
                                             // Extract identifiers to create a usage in server-side code
                                             let mut visitor = IdentifierVisitor::new();
                                             visitor.visit_expr(&handler_tokens);
@@ -569,7 +920,7 @@ pub(crate) fn render_ast(
                                     }
                                 };
 
-                                if let Ok(handler_tokens) = syn::parse_str::<syn::Expr>(handler) {
+                                if let Ok(handler_tokens) = handler.to_expr() {
                                     Some(quote! {
                                         {
                                             use apex::wasm_bindgen::prelude::*;
@@ -600,23 +951,61 @@ pub(crate) fn render_ast(
 
                     expressions.extend(event_listeners);
 
-                    let (children_instructions, children_expressions) = render_ast(children);
+                    // Raw text content can't hold marker comments, so expressions are rendered
+                    // as plain text. Those of `<title>` and `<textarea>` set the text content
+                    let (children_instructions, children_expressions) = if is_raw_text_element(
+                        &tag_name,
+                    ) {
+                        (render_raw_text(children), Vec::new())
+                    } else if is_escapable_raw_text_element(&tag_name) {
+                        let text = render_raw_text(children);
+                        let mut text_expressions = Vec::new();
+
+                        if has_text_expressions {
+                            let mut visitor = IdentifierVisitor::new();
+                            for child in children {
+                                if let TmplAst::Expression(expr) = child
+                                    && let Ok(expr_tokens) = expr.to_expr()
+                                {
+                                    visitor.visit_expr(&expr_tokens);
+                                }
+                            }
+                            let vars = visitor.identifiers;
 
-                    expressions.extend(children_expressions);
+                            text_expressions.push(quote! {
+                                    {
+                                        #(let #vars = #vars.clone();)*
+
+                                        if let Some(element) = state.borrow().elements_map.borrow().get(&element_counter.to_string()).cloned() {
+                                            apex::effect!({
+                                                let mut buffer = String::new();
+                                                #(#text)*
+                                                element.set_text_content(Some(&buffer));
+                                            });
+                                        } else {
+                                            apex::web_sys::console::warn_1(&format!("Warning: element {} not found during hydration", element_counter.to_string()).into());
+                                        }
+                                    }
+                                });
+                        }
 
-                    let open_tag = if attributes.is_empty() {
-                        format!("<{tag_name}")
+                        (text, text_expressions)
                     } else {
-                        format!("<{tag_name} ")
+                        render_ast(children)
                     };
 
-                    if *self_closing {
+                    expressions.extend(children_expressions);
+
+                    // Each attribute is rendered with its leading space
+                    let open_tag = format!("<{tag_name}");
+
+                    if is_void_element(&tag_name) {
                         instructions.push(quote! {
                             let element_counter = #element_counter;
                             #comment_element
                             buffer.push_str(&(#open_tag));
                             #(#attr_setters)*
-                            buffer.push_str("/>");
+                            buffer.push_str(">");
                         });
                     } else {
                         let close_tag = format!("</{tag_name}>");
@@ -633,9 +1022,27 @@ pub(crate) fn render_ast(
                     }
                 }
             }
+            TmplAst::SlotInterpolation {
+                slot_name: Some(slot_name),
+                props: Some(props),
+                ..
+            } => {
+                // Typed slot, a `Slot<T>` parameter of the component rendered with a value
+                if let Ok(props) = props.to_expr() {
+                    let slot = syn::Ident::new(slot_name, proc_macro2::Span::call_site());
+
+                    instructions.push(quote! {
+                        #slot.render(&mut buffer, data.clone(), #props);
+                    });
+                    expressions.push(quote! {
+                        #slot.hydrate(state.clone(), #props);
+                    });
+                }
+            }
             TmplAst::SlotInterpolation {
                 slot_name,
                 default_children,
+                ..
             } => {
                 if let Some(slot_name) = slot_name {
                     // Handle named slots
@@ -802,17 +1209,19 @@ pub(crate) fn render_ast(
                                 }
                             };
 
+                            // Inside `<svg>` or `<math>` a `<template>` is a foreign element rather
+                            // than inert, so it's hidden explicitly
                             templates = quote! {
                                 #templates
                                 let template_id = format!("{}/{}", conditional_counter, #templates_counter);
                                 buffer.push_str("<template id=\"");
                                 buffer.push_str(&template_id);
-                                buffer.push_str("\">");
+                                buffer.push_str("\" style=\"display:none\">");
                                 buffer.push_str(&#children_instructions_ident);
                                 buffer.push_str("</template>");
                             };
 
-                            if let Ok(expr_tokens) = syn::parse_str::<syn::Expr>(condition) {
+                            if let Ok(expr_tokens) = condition.to_expr() {
                                 conditional_render = quote! {
                                     #conditional_render
 
@@ -923,12 +1332,12 @@ pub(crate) fn render_ast(
                                 let template_id = format!("{}/{}", conditional_counter, #templates_counter);
                                 buffer.push_str("<template id=\"");
                                 buffer.push_str(&template_id);
-                                buffer.push_str("\">");
+                                buffer.push_str("\" style=\"display:none\">");
                                 buffer.push_str(&#children_instructions_ident);
                                 buffer.push_str("</template>");
                             };
 
-                            if let Ok(expr_tokens) = syn::parse_str::<syn::Expr>(condition) {
+                            if let Ok(expr_tokens) = condition.to_expr() {
                                 conditional_render = quote! {
                                     #conditional_render
                                     else if #expr_tokens {
@@ -1036,7 +1445,7 @@ pub(crate) fn render_ast(
                                 let template_id = format!("{}/{}", conditional_counter, #templates_counter);
                                 buffer.push_str("<template id=\"");
                                 buffer.push_str(&template_id);
-                                buffer.push_str("\">");
+                                buffer.push_str("\" style=\"display:none\">");
                                 buffer.push_str(&#children_instructions_ident);
                                 buffer.push_str("</template>");
                             };
@@ -1148,6 +1557,180 @@ pub(crate) fn render_ast(
                     }
                 });
             }
+            TmplAst::DynamicComponent {
+                component,
+                candidates,
+                children,
+            } => {
+                let Ok(component_tokens) = component.to_expr() else {
+                    continue;
+                };
+
+                let candidates_tokens = match candidates.as_ref().map(TmplExpr::to_expr) {
+                    Some(Ok(candidates_tokens)) => quote! { #candidates_tokens },
+                    Some(Err(_)) => continue,
+                    None => quote! { std::iter::empty() },
+                };
+
+                // Rendered like a conditional block with one branch per candidate, so the
+                // router swaps and rehydrates it the same way
+                let slots = slots_builder_chain(
+                    quote! { apex::helpers::DynamicSlots::new() },
+                    children,
+                    false,
+                );
+                let component_vars = collect_variables_from_ast(std::slice::from_ref(ast));
+
+                instructions.push(quote! {
+                    {
+                        let conditional_counter = apex::apex_utils::next_conditional_counter();
+                        let slots = #slots;
+                        let component: apex::helpers::DynamicComponent = #component_tokens;
+                        let mut component_html = String::new();
+
+                        for candidate in component.candidates(#candidates_tokens) {
+                            let template_id = format!("{}/{}", conditional_counter, candidate.key());
+                            let candidate_html = candidate.build(slots.clone()).render_any(data.clone());
+
+                            buffer.push_str("<template id=\"");
+                            buffer.push_str(&template_id);
+                            buffer.push_str("\" style=\"display:none\">");
+                            buffer.push_str(&candidate_html);
+                            buffer.push_str("</template>");
+
+                            if candidate.key() == component.key() {
+                                component_html = candidate_html;
+                            }
+                        }
+
+                        buffer.push_str("<!-- @conditional-begin:");
+                        buffer.push_str(&conditional_counter.to_string());
+                        buffer.push_str(" -->");
+                        buffer.push_str(&component_html);
+                        buffer.push_str("<!-- @conditional-end:");
+                        buffer.push_str(&conditional_counter.to_string());
+                        buffer.push_str(" -->");
+                    }
+                });
+
+                expressions.push(quote! {
+                    {
+                        let conditional_counter = apex::apex_utils::next_conditional_counter();
+                        let slots = #slots;
+                        let component: apex::helpers::DynamicComponent = #component_tokens;
+                        let current_template_id = apex::signal!(Some(format!("{}/{}", conditional_counter, component.key())));
+
+                        // Every candidate is hydrated in the order the server rendered them,
+                        // the counters each one starts at are kept to rehydrate it once swapped in
+                        let mut candidate_counters = Vec::new();
+
+                        for candidate in component.candidates(#candidates_tokens) {
+                            candidate_counters.push((
+                                candidate.key(),
+                                apex::apex_utils::get_text_node_counter(),
+                                apex::apex_utils::get_element_counter(),
+                                apex::apex_utils::get_conditional_counter(),
+                            ));
+
+                            candidate.build(slots.clone()).hydrate_any()(state.clone());
+                        }
+
+                        let candidate_counters = std::rc::Rc::new(candidate_counters);
+
+                        let window = apex::web_sys::window().expect("window not found");
+                        let document = window.document().expect("document not found");
+
+                        let component_rehydration_callback = {
+                            #(let #component_vars = #component_vars.clone();)*
+                            let slots = slots.clone();
+                            let state = state.clone();
+                            let candidate_counters = candidate_counters.clone();
+
+                            apex::wasm_bindgen::prelude::Closure::wrap(Box::new(move |event: apex::web_sys::CustomEvent| {
+                                let event_detail: apex::wasm_bindgen::JsValue = event.detail();
+
+                                let Some(template_id) = apex::js_sys::Reflect::get(&event_detail, &"template_id".into())
+                                    .ok()
+                                    .and_then(|template_id| template_id.as_string())
+                                else {
+                                    return;
+                                };
+
+                                let component: apex::helpers::DynamicComponent = #component_tokens;
+
+                                if template_id != format!("{}/{}", conditional_counter, component.key()) {
+                                    return;
+                                }
+
+                                let Some((_, text_node_start, element_start, conditional_start)) = candidate_counters
+                                    .iter()
+                                    .find(|(key, ..)| *key == component.key())
+                                    .copied()
+                                else {
+                                    return;
+                                };
+
+                                apex::apex_utils::reset_text_node_counter(text_node_start.into());
+                                apex::apex_utils::reset_element_counter(element_start.into());
+                                apex::apex_utils::reset_conditional_counter(conditional_start.into());
+                                component.build(slots.clone()).hydrate_any()(state.clone());
+                            }) as Box<dyn FnMut(_)>)
+                        };
+
+                        let _ = document.add_event_listener_with_callback(
+                            format!("apex:rehydrate-conditional-{conditional_counter}").as_str(),
+                            component_rehydration_callback.as_ref().unchecked_ref(),
+                        );
+
+                        component_rehydration_callback.forget();
+
+                        #(let #component_vars = #component_vars.clone();)*
+
+                        apex::effect!({
+                            let component: apex::helpers::DynamicComponent = #component_tokens;
+                            let template_id = format!("{}/{}", conditional_counter, component.key());
+
+                            if current_template_id.get().as_deref() == Some(template_id.as_str()) {
+                                return;
+                            }
+
+                            // Only the candidates have a template the router can swap in
+                            if !candidate_counters.iter().any(|(key, ..)| *key == component.key()) {
+                                apex::web_sys::console::error_1(
+                                    &format!(
+                                        "`{{#component}}` can't switch to `{}`, it isn't one of the candidates listed after `in`",
+                                        component.key(),
+                                    )
+                                    .into(),
+                                );
+                                return;
+                            }
+
+                            current_template_id.set(Some(template_id.clone()));
+
+                            let event_init = apex::web_sys::CustomEventInit::new();
+                            let detail = apex::js_sys::Object::new();
+
+                            let _ = apex::js_sys::Reflect::set(
+                                &detail,
+                                &"template_id".into(),
+                                &template_id.into(),
+                            );
+
+                            event_init.set_detail(&detail);
+
+                            if let Ok(custom_event) =
+                                apex::web_sys::CustomEvent::new_with_event_init_dict(
+                                    "apex:rerender-conditional",
+                                    &event_init,
+                                )
+                            {
+                                let _ = document.dispatch_event(&custom_event);
+                            }
+                        });
+                    }
+                });
+            }
             TmplAst::Outlet => {
                 instructions.push(quote! {
                     #[cfg(not(target_arch = "wasm32"))]
@@ -1156,9 +1739,299 @@ pub(crate) fn render_ast(
                     }
                 });
             }
+            TmplAst::Comment(comment) => {
+                let comment = format!("<!-- {comment} -->");
+
+                instructions.push(quote! {
+                    buffer.push_str(#comment);
+                });
+            }
+            TmplAst::Doctype(doctype) => {
+                let doctype = format!("<!{doctype}>");
+
+                instructions.push(quote! {
+                    buffer.push_str(#doctype);
+                });
+            }
             TmplAst::Slot { .. } => {}
         }
     }
 
     (instructions, expressions)
 }
+
+/// Add the slots and children given to a component to its builder chain, typed slots
+/// through their setters and the others as render and hydration closures
+/// Types naming a slot for the slot traits of the component builder: the tuple of its
+/// characters, which the component implements the traits for, and an empty enum named after
+/// the slot, which the error messages show. The enum is declared in a module so it doesn't
+/// shadow anything the slot content uses.
+fn slot_name_types(
+    slot_name: &TmplTag,
+) -> (
+    proc_macro2::TokenStream,
+    proc_macro2::TokenStream,
+    proc_macro2::TokenStream,
+) {
+    let span = slot_name.span();
+    let chars = crate::common::slot_name_chars(slot_name, span);
+
+    let sanitized = slot_name
+        .chars()
+        .map(|ch| if ch.is_alphanumeric() { ch } else { '_' })
+        .collect::<String>();
+    let marker = syn::parse_str::<Ident>(&sanitized)
+        .map(|ident| Ident::new(&ident.to_string(), span))
+        .unwrap_or_else(|_| match sanitized.as_str() {
+            "self" | "Self" | "super" | "crate" | "_" => format_ident!("{sanitized}_", span = span),
+            _ if sanitized.starts_with(|ch: char| ch.is_ascii_digit()) => {
+                format_ident!("_{sanitized}", span = span)
+            }
+            _ => Ident::new_raw(&sanitized, span),
+        });
+
+    let module = quote_spanned! {span=>
+        mod __apex_slot {
+            #[allow(non_camel_case_types, dead_code)]
+            pub(super) enum #marker {}
+        }
+    };
+
+    (chars, module, quote_spanned! {span=> __apex_slot::#marker })
+}
+
+/// Builder chain setting the children and slots given to a component. The slots of a
+/// `#[component]` are checked against the slots it has, `{#component}` takes any slots.
+fn slots_builder_chain(
+    mut builder_chain: proc_macro2::TokenStream,
+    children: &[TmplAst],
+    check_slots: bool,
+) -> proc_macro2::TokenStream {
+    let mut render_slots_map = quote! { std::collections::HashMap::new() };
+    let mut hydrate_slots_map = quote! { std::collections::HashMap::new() };
+    let mut regular_children = Vec::new();
+
+    for child in children {
+        if let TmplAst::Slot {
+            name: Some(slot_name),
+            binding: Some(binding),
+            children: slot_children,
+        } = child
+        {
+            // Typed slot, set through the `SetTypedSlot` impl of the slot, so unknown and
+            // missing slots are compile errors
+            let Ok(binding) = syn::parse::Parser::parse_str(syn::Pat::parse_single, binding) else {
+                continue;
+            };
+
+            let (slot_instructions, slot_expressions) = render_ast(slot_children);
+            let bound = pattern_identifiers(&binding);
+            let slot_vars = collect_variables_from_ast(slot_children)
+                .into_iter()
+                .filter(|var| !bound.contains(var))
+                .collect::<Vec<_>>();
+            let (slot_chars, slot_marker, slot_marker_path) = slot_name_types(slot_name);
+            let set_typed_slot = quote_spanned! {slot_name.span()=>
+                apex::helpers::SetTypedSlot::<#slot_chars, #slot_marker_path>::set_typed_slot
+            };
+
+            // The call, its builder argument and its closures are spanned at the slot, an
+            // unknown slot is reported there
+            let render = quote_spanned! {slot_name.span()=>
+                {
+                    #(let #slot_vars = #slot_vars.clone();)*
+
+                    move |buffer, data, #binding| {
+                        let _ = (&buffer, &data);
+                        #(let _ = &#bound;)*
+                        #(#slot_instructions)*
+                    }
+                }
+            };
+            let hydrate = quote_spanned! {slot_name.span()=>
+                {
+                    #(let #slot_vars = #slot_vars.clone();)*
+
+                    move |state, #binding| {
+                        let _ = &state;
+                        #(let _ = &#bound;)*
+                        #(#slot_expressions)*
+                    }
+                }
+            };
+
+            let set_slot = quote_spanned! {slot_name.span()=>
+                #set_typed_slot(builder, #render, #hydrate)
+            };
+
+            builder_chain = quote! {
+                {
+                    let builder = #builder_chain;
+                    #slot_marker
+
+                    #set_slot
+                }
+            };
+        } else if let TmplAst::Slot {
+            name: Some(slot_name),
+            binding: None,
+            children: slot_children,
+        } = child
+        {
+            if check_slots {
+                let (slot_chars, slot_marker, slot_marker_path) = slot_name_types(slot_name);
+                let assert_named_slot = quote_spanned! {slot_name.span()=>
+                    apex::helpers::assert_named_slot::<#slot_chars, #slot_marker_path, _>
+                };
+
+                builder_chain = quote! {
+                    {
+                        let builder = #builder_chain;
+                        #slot_marker
+
+                        #assert_named_slot(&builder);
+                        builder
+                    }
+                };
+            }
+
+            let slot_name: &str = slot_name;
+            let (slot_instructions, slot_expressions) = render_ast(slot_children);
+            let slot_vars = collect_variables_from_ast(slot_children);
+
+            if slot_vars.is_empty() {
+                // No variables to capture, create simple closures
+                render_slots_map = quote! {
+                    {
+                        let mut map = #render_slots_map;
+
+                        map.insert(#slot_name.to_string(), std::rc::Rc::new(Box::new(move |buffer: &mut String, data: std::rc::Rc<std::cell::RefCell<std::collections::HashMap<String, serde_json::Value>>>| {
+                            #(#slot_instructions)*
+                        }) as Box<dyn for<'a> Fn(&'a mut String, std::rc::Rc<std::cell::RefCell<std::collections::HashMap<String, serde_json::Value>>>) + 'static>));
+
+                        map
+                    }
+                };
+                // Hydration closures for named slots
+                hydrate_slots_map = quote! {
+                    {
+                        let mut map = #hydrate_slots_map;
+
+                        map.insert(#slot_name.to_string(), std::rc::Rc::new(Box::new(move |
+                            state: std::rc::Rc<std::cell::RefCell<apex_router::client_router::State>>
+                        | {
+                            #(#slot_expressions)*
+                        }) as Box<dyn Fn(std::rc::Rc<std::cell::RefCell<apex_router::client_router::State>>) + 'static>));
+
+                        map
+                    }
+                };
+            } else {
+                // Variables need to be captured - each slot gets its own closure with cloned variables
+                render_slots_map = quote! {
+                    {
+                        let mut map = #render_slots_map;
+
+                        map.insert(#slot_name.to_string(), std::rc::Rc::new(Box::new({
+                            // Clone variables for this specific slot closure
+                            #(let #slot_vars = #slot_vars.clone();)*
+
+                            move |buffer: &mut String, data: std::rc::Rc<std::cell::RefCell<std::collections::HashMap<String, serde_json::Value>>>| {
+                                #(#slot_instructions)*
+                            }
+                        }) as Box<dyn for<'a> Fn(&'a mut String, std::rc::Rc<std::cell::RefCell<std::collections::HashMap<String, serde_json::Value>>>) + 'static>));
+
+                        map
+                    }
+                };
+
+                // Hydration closures for named slots with captured vars
+                hydrate_slots_map = quote! {
+                    {
+                        let mut map = #hydrate_slots_map;
+
+                        map.insert(#slot_name.to_string(), std::rc::Rc::new(Box::new({
+                            #(let #slot_vars = #slot_vars.clone();)*
+
+                            move |
+                                state: std::rc::Rc<std::cell::RefCell<apex_router::client_router::State>>
+                            | {
+                                #(#slot_expressions)*
+                            }
+                        }) as Box<dyn Fn(std::rc::Rc<std::cell::RefCell<apex_router::client_router::State>>) + 'static>));
+
+                        map
+                    }
+                };
+            }
+        } else {
+            regular_children.push(child.clone());
+        }
+    }
+
+    // Handle regular children (unnamed slot)
+    let (children_instructions, children_expressions) = render_ast(&regular_children);
+
+    if !regular_children.is_empty() {
+        let children_vars = collect_variables_from_ast(&regular_children);
+
+        if children_vars.is_empty() {
+            // No variables to capture, create a simple closure
+            builder_chain = quote! {
+                #builder_chain
+                    .render_children(Box::new(move |buffer: &mut String, data: std::rc::Rc<std::cell::RefCell<std::collections::HashMap<String, serde_json::Value>>>| {
+                        #(#children_instructions)*
+                    }))
+                    .hydrate_children(Box::new(move |
+                        state: std::rc::Rc<std::cell::RefCell<apex_router::client_router::State>>
+                    | {
+                        #(#children_expressions)*
+                    }))
+            };
+        } else {
+            // Variables need to be captured
+            builder_chain = quote! {
+                #builder_chain
+                    .render_children(Box::new({
+                        #(let #children_vars = #children_vars.clone();)*
+
+                        move |buffer: &mut String, data: std::rc::Rc<std::cell::RefCell<std::collections::HashMap<String, serde_json::Value>>>| {
+                            #(#children_instructions)*
+                        }
+                    }))
+                    .hydrate_children(Box::new({
+                        #(let #children_vars = #children_vars.clone();)*
+
+                        move |
+                            state: std::rc::Rc<std::cell::RefCell<apex_router::client_router::State>>
+                        | {
+                            #(#children_expressions)*
+                        }
+                    }))
+            };
+        }
+    }
+
+    quote! {
+        #builder_chain.named_slots(#render_slots_map).hydrate_named_slots(#hydrate_slots_map)
+    }
+}
+
+/// Render the content of `<script>`, `<style>`, `<textarea>` and `<title>` without expression
+/// markers
+fn render_raw_text(content: &[TmplAst]) -> Vec<proc_macro2::TokenStream> {
+    content
+        .iter()
+        .filter_map(|node| match node {
+            TmplAst::Text(text) => Some(quote! {
+                buffer.push_str(#text);
+            }),
+            TmplAst::Expression(expr) => expr.to_expr().ok().map(|expr_tokens| {
+                quote! {
+                    buffer.push_str(&(#expr_tokens).to_string());
+                }
+            }),
+            _ => None,
+        })
+        .collect()
+}
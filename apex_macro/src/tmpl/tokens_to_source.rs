@@ -0,0 +1,114 @@
+use proc_macro2::{Delimiter, LineColumn, Span, TokenStream, TokenTree};
+
+use crate::tmpl::parse_tmpl_into_ast::{SourceChar, TmplChars};
+
+/// Reconstruct the template source from its tokens, restoring the original whitespace
+/// from token spans so `<pre>`/`<textarea>` contents survive. Every character keeps the
+/// span and index of the token it belongs to (whitespace takes the span of the following
+/// token), which lets expressions be rebuilt from the original tokens.
+/// Falls back to `TokenStream::to_string` when span locations are unavailable.
+pub(crate) fn tokens_to_source(input: TokenStream) -> TmplChars {
+    let mut writer = SourceWriter {
+        chars: Vec::new(),
+        tokens: Vec::new(),
+        last: None,
+    };
+
+    if writer.write_stream(input.clone()).is_none() {
+        return TmplChars::from(input.to_string().as_str());
+    }
+
+    TmplChars::new(writer.chars, writer.tokens)
+}
+
+struct SourceWriter {
+    chars: Vec<SourceChar>,
+    tokens: Vec<TokenTree>,
+    last: Option<LineColumn>,
+}
+
+impl SourceWriter {
+    fn write_stream(&mut self, input: TokenStream) -> Option<()> {
+        for token in input {
+            let index = self.tokens.len();
+            self.tokens.push(token.clone());
+
+            match token {
+                TokenTree::Group(group) => {
+                    let (open, close) = match group.delimiter() {
+                        Delimiter::Parenthesis => ("(", ")"),
+                        Delimiter::Brace => ("{", "}"),
+                        Delimiter::Bracket => ("[", "]"),
+                        Delimiter::None => ("", ""),
+                    };
+
+                    if group.delimiter() == Delimiter::None {
+                        self.write_stream(group.stream())?;
+                        continue;
+                    }
+
+                    self.write_token(group.span_open(), open, index)?;
+                    self.write_stream(group.stream())?;
+                    self.write_token(group.span_close(), close, index)?;
+                }
+                TokenTree::Ident(ident) => {
+                    self.write_token(ident.span(), &ident.to_string(), index)?
+                }
+                TokenTree::Punct(punct) => {
+                    self.write_token(punct.span(), &punct.as_char().to_string(), index)?
+                }
+                TokenTree::Literal(literal) => {
+                    self.write_token(literal.span(), &literal.to_string(), index)?
+                }
+            }
+        }
+
+        Some(())
+    }
+
+    fn write_token(&mut self, span: Span, text: &str, index: usize) -> Option<()> {
+        let start = span.start();
+        let end = span.end();
+
+        // Line 0 means the compiler does not expose span locations
+        if start.line == 0 {
+            return None;
+        }
+
+        let whitespace = |ch| SourceChar {
+            ch,
+            span,
+            token: None,
+            location: None,
+        };
+
+        match self.last {
+            Some(last) if start.line > last.line => {
+                for _ in last.line..start.line {
+                    self.chars.push(whitespace('\n'));
+                }
+                for _ in 0..start.column {
+                    self.chars.push(whitespace(' '));
+                }
+            }
+            Some(last) if start.line == last.line && start.column >= last.column => {
+                for _ in last.column..start.column {
+                    self.chars.push(whitespace(' '));
+                }
+            }
+            // Tokens coming from another expansion keep no relation to their neighbours
+            Some(_) => self.chars.push(whitespace(' ')),
+            None => {}
+        }
+
+        self.chars.extend(text.chars().map(|ch| SourceChar {
+            ch,
+            span,
+            token: Some(index),
+            location: None,
+        }));
+        self.last = Some(end);
+
+        Some(())
+    }
+}
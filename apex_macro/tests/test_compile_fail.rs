@@ -0,0 +1,7 @@
+#![allow(missing_docs)]
+
+#[test]
+fn test_compile_fail() {
+    let t = trybuild::TestCases::new();
+    t.compile_fail("tests/ui/*.rs");
+}
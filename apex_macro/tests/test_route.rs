@@ -0,0 +1,467 @@
+#![allow(missing_docs)]
+
+use apex::apex_router::ApexServerRouter;
+use apex::prelude::*;
+use apex_components::Form;
+
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+struct UserData {
+    name: String,
+}
+
+#[route(component = UserProfile, path = "/users/{id}")]
+fn user_page(params: HashMap<String, String>) -> LoaderResult<UserData> {
+    match params.get("id").map(String::as_str) {
+        Some("1") => LoaderResult::ok(UserData {
+            name: "Mike".to_owned(),
+        }),
+        Some("me") => LoaderResult::redirect("/users/1"),
+        _ => LoaderResult::not_found(),
+    }
+}
+
+#[component]
+fn user_profile() {
+    let user = loader_data!(user_page);
+    let name = user.get().map_or("No user".to_owned(), |user| user.name);
+
+    tmpl! { <p>{name}</p> }
+}
+
+#[route(component = UserProfile, path = "/broken")]
+fn broken_page(_params: HashMap<String, String>) -> Result<UserData, String> {
+    Err("<script>alert('database is down')</script>".to_owned())
+}
+
+#[tokio::test]
+async fn test_route_loader_result() {
+    let router = ApexServerRouter::new(&UserPageRoute::new());
+
+    let response = router.handle_request("/users/1", "").await;
+    assert_eq!(response.status, 200);
+    assert!(response.body.contains("-->Mike<!-- @expr-text-end:"));
+
+    let response = router.handle_request("/users/me", "").await;
+    assert_eq!(response.status, 302);
+    assert_eq!(response.location(), Some("/users/1"));
+
+    let response = router.handle_request("/users/2", "").await;
+    assert_eq!(response.status, 404);
+
+    // Only the `has_exclude` parameter asks for JSON, not a value containing it
+    let response = router.handle_request("/users/1", "q=has_exclude").await;
+    assert_eq!(response.status, 200);
+    assert!(response.body.contains("-->Mike<!-- @expr-text-end:"));
+
+    let response = router
+        .handle_request("/users/me", "has_exclude&exclude=/users/1&")
+        .await;
+    assert_eq!(response.status, 200);
+    assert_eq!(
+        serde_json::from_str::<serde_json::Value>(&response.body).unwrap(),
+        serde_json::json!({ "redirect": "/users/1", "status": 302 })
+    );
+}
+
+#[tokio::test]
+async fn test_route_result() {
+    let router = ApexServerRouter::new(&BrokenPageRoute::new());
+
+    // The error is logged, not sent to the client
+    let response = router.handle_request("/broken", "").await;
+    assert_eq!(response.status, 500);
+    assert_eq!(response.body, "Internal Server Error");
+
+    let response = router
+        .handle_request("/broken", "has_exclude&exclude=/")
+        .await;
+    assert!(!response.body.contains("<script>"));
+    assert!(!response.body.contains("database is down"));
+}
+
+async fn find_user(id: &str) -> Option<UserData> {
+    tokio::task::yield_now().await;
+
+    (id == "1").then(|| UserData {
+        name: "Mike".to_owned(),
+    })
+}
+
+#[route(component = UserProfile, path = "/async/{id}")]
+async fn async_user_page(params: HashMap<String, String>) -> LoaderResult<UserData> {
+    let Some(id) = params.get("id") else {
+        return LoaderResult::not_found();
+    };
+
+    match find_user(id).await {
+        Some(user) => LoaderResult::ok(user),
+        None => LoaderResult::not_found(),
+    }
+}
+
+#[tokio::test]
+async fn test_async_route() {
+    let router = ApexServerRouter::new(&AsyncUserPageRoute::new());
+
+    // Spawned on the multi-threaded runtime, the request has to be `Send`
+    let response = tokio::spawn(async move {
+        let found = router.handle_request("/async/1", "").await;
+        let missing = router.handle_request("/async/2", "").await;
+        (found, missing)
+    })
+    .await
+    .unwrap();
+
+    assert_eq!(response.0.status, 200);
+    assert!(
+        response
+            .0
+            .body
+            .contains(r#""async_user_page":{"name":"Mike"}"#)
+    );
+    assert_eq!(response.1.status, 404);
+}
+
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+struct Settings {
+    theme: String,
+    page: u32,
+}
+
+#[derive(serde::Deserialize)]
+struct Pagination {
+    page: u32,
+}
+
+#[route(component = SettingsPanel, path = "/settings")]
+fn settings_page(
+    _params: HashMap<String, String>,
+    request: RequestContext,
+) -> LoaderResult<Settings> {
+    let Some(theme) = request.cookie("theme") else {
+        return LoaderResult::redirect("/login");
+    };
+
+    let page = request.query::<Pagination>().map_or(1, |query| query.page);
+
+    LoaderResult::ok(Settings {
+        theme: theme.to_owned(),
+        page,
+    })
+}
+
+#[component]
+fn settings_panel() {
+    let method = RequestContext::current().map_or("none".to_owned(), |request| request.method);
+
+    tmpl! { <p>{method}</p> }
+}
+
+#[tokio::test]
+async fn test_route_request_context() {
+    let router = ApexServerRouter::new(&SettingsPageRoute::new());
+
+    let request = RequestContext::new("GET", "/settings?page=3").header("Cookie", "theme=dark");
+    let response = router.handle(request).await;
+
+    assert_eq!(response.status, 200);
+    assert!(
+        response
+            .body
+            .contains(r#""settings_page":{"page":3,"theme":"dark"}"#)
+    );
+    assert!(response.body.contains("-->GET<!-- @expr-text-end:"));
+
+    let response = router.handle_request("/settings", "").await;
+    assert_eq!(response.location(), Some("/login"));
+}
+
+#[derive(serde::Deserialize)]
+struct PostParams {
+    id: u32,
+}
+
+#[route(component = UserProfile, path = "/posts/{id}")]
+fn post_page(Path(params): Path<PostParams>) -> u32 {
+    params.id
+}
+
+#[tokio::test]
+async fn test_route_typed_params() {
+    let router = ApexServerRouter::new(&PostPageRoute::new());
+
+    let response = router.handle_request("/posts/42", "").await;
+    assert_eq!(response.status, 200);
+    assert!(response.body.contains(r#""post_page":42"#));
+
+    let response = router.handle_request("/posts/latest", "").await;
+    assert_eq!(response.status, 404);
+}
+
+static STARTED_LOADERS: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
+
+/// Waits for the loader of the other route, which only finishes when both run concurrently
+async fn wait_for_both_loaders() -> bool {
+    STARTED_LOADERS.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+
+    for _ in 0..100 {
+        if STARTED_LOADERS.load(std::sync::atomic::Ordering::SeqCst) == 2 {
+            return true;
+        }
+
+        tokio::task::yield_now().await;
+    }
+
+    false
+}
+
+#[route(component = Dashboard, path = "/dashboard", children = [DashboardStatsPageRoute])]
+async fn dashboard_page(_params: HashMap<String, String>) -> bool {
+    wait_for_both_loaders().await
+}
+
+#[component]
+fn dashboard() {
+    tmpl! { <main>{#outlet}</main> }
+}
+
+#[route(component = UserProfile, path = "/stats")]
+async fn dashboard_stats_page(_params: HashMap<String, String>) -> bool {
+    wait_for_both_loaders().await
+}
+
+#[tokio::test]
+async fn test_nested_loaders_run_concurrently() {
+    let router = ApexServerRouter::new(&DashboardPageRoute::new());
+
+    let response = router.handle_request("/dashboard/stats", "").await;
+
+    assert_eq!(response.status, 200);
+    assert!(response.body.contains(r#""dashboard_page":true"#));
+    assert!(response.body.contains(r#""dashboard_stats_page":true"#));
+    assert!(
+        response
+            .body
+            .contains("<main><!-- @outlet-begin:/dashboard -->")
+    );
+}
+
+#[derive(serde::Deserialize)]
+struct NewTodo {
+    title: String,
+}
+
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+struct TodoErrors {
+    title: String,
+}
+
+#[route_action]
+async fn add_todo(request: RequestContext) -> LoaderResult<TodoErrors> {
+    let Ok(todo) = request.form::<NewTodo>() else {
+        return LoaderResult::response(HttpResponse::new(400).body("Bad Request"));
+    };
+
+    if todo.title.trim().is_empty() {
+        return LoaderResult::ok(TodoErrors {
+            title: "Title is required".to_owned(),
+        });
+    }
+
+    LoaderResult::redirect("/todos")
+}
+
+#[route(component = TodoList, path = "/todos", action = add_todo)]
+fn todos_page(_params: HashMap<String, String>) -> Vec<String> {
+    vec!["Write tests".to_owned()]
+}
+
+#[component]
+fn todo_list() {
+    let errors = action_data!(add_todo);
+    let title_error = errors.get().map_or(String::new(), |errors| errors.title);
+
+    tmpl! {
+        <Form action="/todos">
+            <input name="title" />
+            <p class="error">{title_error}</p>
+        </Form>
+    }
+}
+
+#[tokio::test]
+async fn test_route_action() {
+    let router = ApexServerRouter::new(&TodosPageRoute::new());
+
+    let request = RequestContext::new("POST", "/todos").body("title=");
+    let response = router.handle(request).await;
+
+    assert_eq!(response.status, 200);
+    assert!(
+        response
+            .body
+            .contains("-->Title is required<!-- @expr-text-end:")
+    );
+    assert!(response.body.contains(r#""todos_page":["Write tests"]"#));
+    assert!(
+        response
+            .body
+            .contains(r#"<form action="/todos" method="post">"#)
+    );
+
+    let request = RequestContext::new("POST", "/todos").body("title=Ship+it");
+    let response = router.handle(request).await;
+    assert_eq!(response.location(), Some("/todos"));
+
+    let request = RequestContext::new("POST", "/todos").body("name=Ship+it");
+    let response = router.handle(request).await;
+    assert_eq!(response.status, 400);
+
+    let response = router.handle_request("/todos", "").await;
+    assert_eq!(response.status, 200);
+    assert!(!response.body.contains("Title is required"));
+
+    let router = ApexServerRouter::new(&PostPageRoute::new());
+    let response = router
+        .handle(RequestContext::new("DELETE", "/posts/1"))
+        .await;
+    assert_eq!(response.status, 405);
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct CurrentUser(String);
+
+async fn require_auth(request: RequestContext) -> Result<RequestContext, HttpResponse> {
+    let Some(session) = request.cookie("session").map(ToOwned::to_owned) else {
+        return Err(HttpResponse::redirect("/login"));
+    };
+
+    Ok(request.extension(CurrentUser(session)))
+}
+
+struct PoweredBy;
+
+impl ApexMiddleware for PoweredBy {
+    fn after<'a>(
+        &'a self,
+        _request: &'a RequestContext,
+        response: HttpResponse,
+    ) -> apex::apex_router::MiddlewareFuture<'a, HttpResponse> {
+        Box::pin(async move { response.header("x-powered-by", "apex") })
+    }
+}
+
+#[route(component = Dashboard, path = "/admin", middleware = [require_auth], children = [AdminUsersPageRoute])]
+fn admin_page(_params: HashMap<String, String>) -> bool {
+    true
+}
+
+#[route(component = UserProfile, path = "/users")]
+fn admin_users_page(_params: HashMap<String, String>, request: RequestContext) -> UserData {
+    let CurrentUser(name) = request.extensions.get::<CurrentUser>().cloned().unwrap();
+
+    UserData { name }
+}
+
+#[tokio::test]
+async fn test_route_middleware() {
+    let mut router = ApexServerRouter::new(&AdminPageRoute::new());
+    router.add_middleware(PoweredBy);
+
+    let response = router
+        .handle(RequestContext::new("GET", "/admin/users"))
+        .await;
+
+    assert_eq!(response.status, 302);
+    assert_eq!(response.location(), Some("/login"));
+    assert_eq!(response.header_value("x-powered-by"), Some("apex"));
+
+    let response = router
+        .handle(RequestContext::new(
+            "GET",
+            "/admin/users?has_exclude=true&exclude=/admin",
+        ))
+        .await;
+
+    assert!(response.body.contains(r#""redirect":"/login""#));
+
+    let request = RequestContext::new("GET", "/admin/users").header("cookie", "session=alice");
+    let response = router.handle(request).await;
+
+    assert_eq!(response.status, 200);
+    assert!(
+        response
+            .body
+            .contains(r#""admin_users_page":{"name":"alice"}"#)
+    );
+    assert_eq!(response.header_value("x-powered-by"), Some("apex"));
+}
+
+async fn count_visits(visits: &std::cell::Cell<u32>) {
+    tokio::task::yield_now().await;
+    visits.set(visits.get() + 1);
+}
+
+// The `Rc` is held across an `.await`, so the future isn't `Send`
+#[route(component = UserProfile, path = "/visits", local)]
+async fn visits_page(_params: HashMap<String, String>) -> UserData {
+    let visits = std::rc::Rc::new(std::cell::Cell::new(0));
+
+    count_visits(&visits).await;
+    count_visits(&visits).await;
+
+    UserData {
+        name: format!("{} visits", visits.get()),
+    }
+}
+
+#[tokio::test]
+async fn test_local_route() {
+    let router = ApexServerRouter::new(&VisitsPageRoute::new());
+
+    let response = router.handle_request("/visits", "").await;
+
+    assert_eq!(response.status, 200);
+    assert!(
+        response
+            .body
+            .contains(r#""visits_page":{"name":"2 visits"}"#)
+    );
+}
+
+#[route(component = SlowProfile, path = "/slow/{id}")]
+async fn slow_page(params: HashMap<String, String>) -> UserData {
+    tokio::task::yield_now().await;
+    tokio::task::yield_now().await;
+
+    UserData {
+        name: params.get("id").cloned().unwrap_or_default(),
+    }
+}
+
+#[component]
+fn slow_profile() {
+    let user = loader_data!(slow_page);
+    let name = user.get().map_or(String::new(), |user| user.name);
+
+    tmpl! { <p>{name}</p> }
+}
+
+#[tokio::test]
+async fn test_concurrent_requests_render_the_same_ids() {
+    let router = ApexServerRouter::new(&SlowPageRoute::new());
+
+    // The loaders of both requests yield, so the requests run interleaved
+    let (first, second) = tokio::join!(
+        router.handle_request("/slow/1", ""),
+        router.handle_request("/slow/1", ""),
+    );
+
+    assert_eq!(first.status, 200);
+    assert!(
+        first
+            .body
+            .contains("<p><!-- @expr-text-begin:0 -->1<!-- @expr-text-end:0 -->")
+    );
+    assert_eq!(first.body, second.body);
+}
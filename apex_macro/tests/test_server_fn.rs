@@ -0,0 +1,78 @@
+#![allow(missing_docs)]
+
+use apex::apex_router::ApexServerRouter;
+use apex::prelude::*;
+
+#[route(path = "/")]
+fn index_page(_params: HashMap<String, String>) {}
+
+#[server]
+async fn divide(a: i32, b: i32) -> Result<i32, ServerFnError> {
+    a.checked_div(b)
+        .ok_or_else(|| ServerFnError::new("division by zero"))
+}
+
+#[server]
+async fn server_time() -> Result<String, ServerFnError> {
+    Ok("12:00".to_owned())
+}
+
+mod nested {
+    use apex::prelude::*;
+
+    #[server]
+    pub(crate) async fn greet(name: String) -> Result<String, ServerFnError> {
+        Ok(format!("Hello, {name}!"))
+    }
+}
+
+#[tokio::test]
+async fn test_server_fn() {
+    // Every `#[server]` function is registered by the router
+    let router = ApexServerRouter::new(&IndexPageRoute::new());
+
+    let path = apex::apex_router::ApexServerFn::path(&DivideServerFn::new());
+    assert_eq!(path, "/apex/server/test_server_fn::divide");
+
+    let response = router
+        .handle(RequestContext::new("POST", path).body("[6, 3]"))
+        .await;
+    assert_eq!(response.status, 200);
+    assert_eq!(response.body, r#"{"Ok":2}"#);
+
+    let response = router
+        .handle(RequestContext::new("POST", path).body("[6, 0]"))
+        .await;
+    assert_eq!(response.body, r#"{"Err":{"message":"division by zero"}}"#);
+
+    let response = router.handle(RequestContext::new("GET", path)).await;
+    assert_eq!(response.status, 405);
+
+    let response = router
+        .handle(
+            RequestContext::new("POST", "/apex/server/test_server_fn::server_time").body("null"),
+        )
+        .await;
+    assert_eq!(response.body, r#"{"Ok":"12:00"}"#);
+
+    let response = router
+        .handle(
+            RequestContext::new("POST", "/apex/server/test_server_fn::nested::greet")
+                .body(r#"["Mike"]"#),
+        )
+        .await;
+    assert_eq!(response.body, r#"{"Ok":"Hello, Mike!"}"#);
+
+    // Functions the server doesn't have aren't found, nor rendered as a route
+    let response = router
+        .handle(RequestContext::new("POST", "/apex/server/test_server_fn::unknown").body("null"))
+        .await;
+    assert_eq!(response.status, 404);
+
+    // The function is still called directly on the server
+    assert_eq!(divide(6, 2).await, Ok(3));
+    assert_eq!(
+        nested::greet("Mike".to_owned()).await,
+        Ok("Hello, Mike!".to_owned())
+    );
+}
@@ -19,7 +19,7 @@ fn test_element() {
 fn test_element_with_attributes() {
     assert_eq!(
         tmpl! { <div id="test" class="test">Hello, world!</div> },
-        "<div class=\"test\"id=\"test\">Hello, world!</div>"
+        "<div class=\"test\" id=\"test\">Hello, world!</div>"
     );
 }
 
@@ -80,6 +80,108 @@ fn test_component_with_dynamic_prop() {
     assert!(result.contains("-->!</div>"));
 }
 
+#[test]
+fn test_generic_component() {
+    #[component]
+    fn item_list<T>(#[prop] items: Vec<T>)
+    where
+        T: std::fmt::Display + 'static,
+    {
+        let text = items
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        tmpl! { <p>{text}</p> }
+    }
+
+    let data = std::rc::Rc::new(std::cell::RefCell::new(std::collections::HashMap::<
+        String,
+        serde_json::Value,
+    >::new()));
+
+    let result = tmpl! { <ItemList items={vec![1, 2, 3]} /><ItemList items={vec!["a", "b"]} /> };
+
+    assert!(result.contains("-->1, 2, 3<!-- @expr-text-end:"));
+    assert!(result.contains("-->a, b<!-- @expr-text-end:"));
+}
+
+#[test]
+fn test_component_with_non_clone_prop() {
+    struct Report {
+        title: String,
+    }
+
+    #[component]
+    fn report_view(#[prop] report: Report) {
+        let Report { title } = report;
+
+        tmpl! { <h1>{title}</h1> }
+    }
+
+    let data = std::rc::Rc::new(std::cell::RefCell::new(std::collections::HashMap::<
+        String,
+        serde_json::Value,
+    >::new()));
+
+    let result = tmpl! { <ReportView report={Report { title: "Sales".to_owned() }} /> };
+
+    assert!(result.contains("-->Sales<!-- @expr-text-end:"));
+}
+
+#[test]
+fn test_component_with_optional_props() {
+    #[component]
+    fn badge(
+        #[prop] label: &'static str,
+        #[prop] count: Option<u32>,
+        #[prop(default)] muted: bool,
+    ) {
+        let text = match count {
+            Some(count) => format!("{label} ({count})"),
+            None => label.to_owned(),
+        };
+        let muted = if muted { "muted" } else { "" };
+
+        tmpl! { <span class={muted}>{text}</span> }
+    }
+
+    let data = std::rc::Rc::new(std::cell::RefCell::new(std::collections::HashMap::<
+        String,
+        serde_json::Value,
+    >::new()));
+
+    let result = tmpl! { <Badge label="Inbox" /><Badge count={3} label="Sent" muted={true} /> };
+
+    assert!(result.contains("-->Inbox<!-- @expr-text-end:"));
+    assert!(result.contains("-->Sent (3)<!-- @expr-text-end:"));
+}
+
+#[test]
+fn test_component_with_into_props() {
+    #[component]
+    fn greeting(#[prop(into)] name: String, #[prop(into)] title: MaybeSignal<String>) {
+        tmpl! { <p>{title}, {name}</p> }
+    }
+
+    let data = std::rc::Rc::new(std::cell::RefCell::new(std::collections::HashMap::<
+        String,
+        serde_json::Value,
+    >::new()));
+
+    let first_name = "John";
+    let title = Signal::new("Dr".to_owned());
+
+    let result =
+        tmpl! { <Greeting name={first_name} title="Mr" /><Greeting name="Jane" title={title} /> };
+
+    assert!(result.contains("-->Mr<!-- @expr-text-end:"));
+    assert!(result.contains("-->John<!-- @expr-text-end:"));
+    assert!(result.contains("-->Dr<!-- @expr-text-end:"));
+    assert!(result.contains("-->Jane<!-- @expr-text-end:"));
+}
+
 #[test]
 fn test_same_component_multiple_times() {
     #[component]
@@ -301,6 +403,98 @@ fn test_component_with_named_and_unnamed_slot() {
     );
 }
 
+#[test]
+fn test_component_with_has_slot() {
+    #[component]
+    fn card(#[slot(default)] actions: Slot) {
+        let bordered = has_slot!("header");
+
+        tmpl! {
+            <div class:bordered={bordered}>
+                {#if has_slot!("header")}
+                    <header><#slot header /></header>
+                {/if}
+                {#if has_slot!()}
+                    <main><#slot /></main>
+                {/if}
+                {#if has_slot!("actions")}
+                    <footer><#slot actions {()} /></footer>
+                {/if}
+            </div>
+        }
+    }
+
+    let data = std::rc::Rc::new(std::cell::RefCell::new(std::collections::HashMap::<
+        String,
+        serde_json::Value,
+    >::new()));
+
+    let result = tmpl! {
+        <Card>
+            <#header><h1>Title</h1></#header>
+            <#actions let:()><button>Close</button></#actions>
+        </Card>
+    };
+
+    assert!(result.contains("<div class=\"bordered\">"));
+    assert!(result.contains(" --><header><h1>Title</h1></header>"));
+    assert!(!result.contains(" --><main>"));
+    assert!(result.contains(" --><footer><button>Close</button></footer>"));
+
+    let result = tmpl! { <Card><p>Body</p></Card> };
+
+    assert!(!result.contains("bordered"));
+    assert!(!result.contains(" --><header>"));
+    assert!(result.contains(" --><main><p>Body</p></main>"));
+    assert!(!result.contains(" --><footer>"));
+}
+
+#[test]
+fn test_component_with_typed_slots() {
+    #[derive(Clone)]
+    struct User {
+        name: String,
+        posts: usize,
+    }
+
+    #[component]
+    fn user_card(
+        #[prop] user: User,
+        #[slot] actions: Slot<User>,
+        #[slot(default)] footer: Slot<usize>,
+    ) {
+        tmpl! { <div><h2>{user.name.clone()}</h2><#slot actions {user.clone()} /><#slot footer {user.posts} /></div> }
+    }
+
+    let data = std::rc::Rc::new(std::cell::RefCell::new(std::collections::HashMap::<
+        String,
+        serde_json::Value,
+    >::new()));
+
+    let user = User {
+        name: "Jane".to_owned(),
+        posts: 3,
+    };
+    let label = "Edit";
+
+    let result = tmpl! {
+        <UserCard user={user.clone()}>
+            <#actions let:user><button>{label} {user.name}</button></#actions>
+            <#footer let:posts><p>{posts} posts</p></#footer>
+        </UserCard>
+        <UserCard user={user}>
+            <#actions let:User { name, .. }><a>{name}</a></#actions>
+        </UserCard>
+    };
+
+    assert!(result.starts_with("<div><h2><!-- @expr-text-begin:"));
+    assert!(result.contains("<button><!-- @expr-text-begin:"));
+    assert!(result.contains("-->Edit<!-- @expr-text-end:"));
+    assert!(result.contains("-->3<!-- @expr-text-end:"));
+    assert!(result.contains("<a><!-- @expr-text-begin:"));
+    assert!(result.ends_with("</a></div>"));
+}
+
 #[test]
 fn test_component_with_slot_passing_another_component() {
     #[component]
@@ -328,6 +522,186 @@ fn test_component_with_slot_passing_another_component() {
     assert_eq!(result, "<div><button>Click me</button></div>");
 }
 
+#[test]
+fn test_dynamic_component() {
+    #[component]
+    fn badge() {
+        tmpl! { <span class="badge"><#slot>New</#slot></span> }
+    }
+
+    #[component]
+    fn panel() {
+        tmpl! { <section><#slot header /><#slot /></section> }
+    }
+
+    #[component]
+    fn chart(#[prop] title: String) {
+        tmpl! { <figure>{title}</figure> }
+    }
+
+    let data = std::rc::Rc::new(std::cell::RefCell::new(std::collections::HashMap::<
+        String,
+        serde_json::Value,
+    >::new()));
+
+    let result = tmpl! { <div>{#component Badge::dynamic()}{/component}</div> };
+
+    assert!(result.starts_with("<div><template id=\""));
+    assert!(
+        result.contains(
+            "::Badge\" style=\"display:none\"><span class=\"badge\">New</span></template>"
+        )
+    );
+    assert!(result.contains(" --><span class=\"badge\">New</span><!-- @conditional-end:"));
+
+    let label = "Sold out";
+    let result = tmpl! {
+        {#component Panel::dynamic()}
+            <#header><h2>Stock</h2></#header>
+            <p>{label}</p>
+        {/component}
+    };
+
+    assert!(result.contains(" --><section><h2>Stock</h2><p><!-- @expr-text-begin:"));
+    assert!(result.contains("-->Sold out<!-- @expr-text-end:"));
+
+    let title = "Sales".to_owned();
+    let widget = signal!(DynamicComponent::new(move |_| {
+        Chart::builder().title(title.clone()).build()
+    }));
+
+    let result = tmpl! { {#component widget.get()}{/component} };
+
+    assert!(result.contains("::Chart\" style=\"display:none\"><figure>"));
+    assert!(result.contains("-->Sales<!-- @expr-text-end:"));
+}
+
+#[test]
+fn test_dynamic_component_switch() {
+    #[component]
+    fn counter() {
+        tmpl! { <button>{1 + 1}</button> }
+    }
+
+    #[component]
+    fn chart(#[prop] title: String) {
+        tmpl! { <figure>{title}</figure> }
+    }
+
+    /// Markup between the block's comments replaced by its template, as the client router
+    /// does when the expression changes
+    fn swap_in_template(html: &str, template_id: &str) -> String {
+        let counter = template_id.split('/').next().unwrap();
+        let template_start = format!("<template id=\"{template_id}\" style=\"display:none\">");
+        let template = &html[html.find(&template_start).unwrap() + template_start.len()..];
+        let template = &template[..template.find("</template>").unwrap()];
+        let begin = format!("<!-- @conditional-begin:{counter} -->");
+        let end = format!("<!-- @conditional-end:{counter} -->");
+        let begin_index = html.find(&begin).unwrap() + begin.len();
+        let end_index = html.find(&end).unwrap();
+
+        format!("{}{template}{}", &html[..begin_index], &html[end_index..])
+    }
+
+    let data = std::rc::Rc::new(std::cell::RefCell::new(std::collections::HashMap::<
+        String,
+        serde_json::Value,
+    >::new()));
+
+    let chart = DynamicComponent::new(|_| Chart::builder().title("Sales".to_owned()).build());
+    let widget = signal!(Counter::dynamic());
+
+    let result = tmpl! { <main>{#component widget.get() in [Counter::dynamic(), chart.clone()]}{/component}</main> };
+
+    let counter = result
+        .split("<!-- @conditional-begin:")
+        .nth(1)
+        .and_then(|rest| rest.split(' ').next())
+        .unwrap()
+        .to_owned();
+    let live = |html: &str| {
+        let begin = format!("<!-- @conditional-begin:{counter} -->");
+        let rest = &html[html.find(&begin).unwrap() + begin.len()..];
+        rest[..rest.find("<!-- @conditional-end:").unwrap()].to_owned()
+    };
+
+    assert!(live(&result).starts_with("<button>"));
+
+    // Switching renders the markup of the other candidate in the block
+    widget.set(chart.clone());
+    let template_id = format!("{counter}/{}", widget.get().key());
+    let switched = swap_in_template(&result, &template_id);
+
+    assert!(live(&switched).starts_with("<figure>"));
+    assert!(live(&switched).contains("-->Sales<!-- @expr-text-end:"));
+    assert!(switched.ends_with("</main>"));
+
+    // And back
+    widget.set(Counter::dynamic());
+    let template_id = format!("{counter}/{}", widget.get().key());
+    let switched_back = swap_in_template(&switched, &template_id);
+
+    assert_eq!(live(&switched_back), live(&result));
+
+    // Every candidate is rendered once, the current component even when it isn't listed
+    let result = tmpl! { {#component widget.get() in [chart.clone(), chart.clone()]}{/component} };
+
+    assert_eq!(result.matches("<template id=").count(), 2);
+    assert!(result.contains("::Counter\" style=\"display:none\"><button>"));
+}
+
+#[test]
+fn test_error_boundary() {
+    use apex_components::ErrorBoundary;
+
+    #[component]
+    fn operator(#[prop] symbol: String) {
+        if !matches!(symbol.as_str(), "+" | "-") {
+            panic!("unknown operator `{symbol}`");
+        }
+
+        tmpl! { <span>{symbol}</span> }
+    }
+
+    #[component]
+    fn error_message(#[prop] error: RenderError) {
+        let message = error.message().to_owned();
+
+        tmpl! { <p class="error">{message}</p> }
+    }
+
+    let data = std::rc::Rc::new(std::cell::RefCell::new(std::collections::HashMap::<
+        String,
+        serde_json::Value,
+    >::new()));
+
+    let fallback = ErrorFallback::new(|error| ErrorMessage::builder().error(error).build());
+
+    let result = tmpl! {
+        <div>
+            <ErrorBoundary fallback={fallback.clone()}><Operator symbol="+" /></ErrorBoundary>
+            <ErrorBoundary fallback={fallback}><Operator symbol="%" /></ErrorBoundary>
+        </div>
+    };
+
+    assert!(result.contains("<span><!-- @expr-text-begin:"));
+    assert!(result.contains("-->+<!-- @expr-text-end:"));
+    assert!(result.contains("<p class=\"error\"><!-- @expr-text-begin:"));
+    assert!(result.contains("-->unknown operator `%`<!-- @expr-text-end:"));
+
+    let errors = data.borrow().get("apex:errors").cloned().unwrap();
+    let errors = errors.as_object().unwrap();
+
+    // Only the failed boundary is sent to the browser, not the panic message
+    assert_eq!(errors.len(), 1);
+    assert!(errors.values().all(|failed| failed == true));
+    assert!(
+        !serde_json::to_string(&*data.borrow())
+            .unwrap()
+            .contains("unknown operator")
+    );
+}
+
 #[test]
 fn test_conditional_directive() {
     let result = tmpl! {
@@ -336,7 +710,7 @@ fn test_conditional_directive() {
         {/if}
     };
 
-    assert_eq!(result, "Hello, world!");
+    assert!(result.contains("-->Hello, world!<!-- @conditional-end:"));
 }
 
 #[test]
@@ -349,7 +723,11 @@ fn test_conditional_directive_with_else() {
         {/if}
     };
 
-    assert_eq!(result, "Hello, world 2!");
+    assert!(
+        result.contains("<template id=\"")
+            && result.contains("/1\" style=\"display:none\">Hello, world 2!</template>")
+    );
+    assert!(result.contains("-->Hello, world 2!<!-- @conditional-end:"));
 }
 
 #[test]
@@ -364,5 +742,287 @@ fn test_conditional_directive_with_else_if() {
         {/if}
     };
 
-    assert_eq!(result, "Hello, world 2!");
+    assert!(result.contains("/2\" style=\"display:none\">Hello, world 3!</template>"));
+    assert!(result.contains("-->Hello, world 2!<!-- @conditional-end:"));
+}
+
+#[test]
+fn test_class_directives() {
+    let is_active = true;
+    let is_disabled = false;
+
+    let result = tmpl! {
+        <div class="base" class:active={is_active} class:disabled={is_disabled}>Hello</div>
+    };
+
+    assert!(result.contains("<div class=\"base active\">Hello</div>"));
+    assert!(result.contains("<!-- @element:"));
+}
+
+#[test]
+fn test_class_directive_shorthand_without_base_class() {
+    let active = true;
+
+    let result = tmpl! {
+        <div class:active id="test">Hello</div>
+    };
+
+    assert!(result.contains("<div class=\"active\" id=\"test\">Hello</div>"));
+}
+
+#[test]
+fn test_class_directives_all_false() {
+    let active = false;
+
+    let result = tmpl! {
+        <div class:active>Hello</div>
+    };
+
+    assert!(result.contains("<div>Hello</div>"));
+}
+
+#[test]
+fn test_style_directives() {
+    let color = "red";
+    let width = 10;
+
+    let result = tmpl! {
+        <div style="display: flex" style:color={color} style:width={format!("{width}px")}>Hello</div>
+    };
+
+    assert!(result.contains("<div style=\"display: flex; color: red; width: 10px\">Hello</div>"));
+}
+
+#[test]
+fn test_spread_attributes() {
+    let attrs = apex::helpers::HtmlAttributes::new()
+        .attr("aria-label", "Close")
+        .attr("data-id", "1")
+        .attr("class", "extra");
+
+    let result = tmpl! {
+        <button class="button" {..attrs}>x</button>
+    };
+
+    assert!(
+        result.contains(
+            "<button aria-label=\"Close\" class=\"extra button\" data-id=\"1\">x</button>"
+        )
+    );
+}
+
+#[test]
+fn test_spread_attributes_are_overridden_by_explicit_attributes() {
+    let attrs = vec![("id", "spread"), ("title", "a \"quoted\" title")];
+
+    let result = tmpl! {
+        <div {..attrs} id="explicit"></div>
+    };
+
+    assert!(result.contains("<div id=\"explicit\" title=\"a &quot;quoted&quot; title\"></div>"));
+}
+
+#[test]
+fn test_spread_signal_attributes() {
+    let attrs = Signal::new(apex::helpers::HtmlAttributes::new().attr("data-state", "open"));
+
+    let result = tmpl! {
+        <div {..attrs}></div>
+    };
+
+    assert!(result.contains("<div data-state=\"open\"></div>"));
+}
+
+#[test]
+fn test_component_with_spread_attributes() {
+    #[component]
+    fn card(#[prop] title: &'static str, #[attrs] rest: apex::helpers::HtmlAttributes) {
+        tmpl! { <section class="card" {..rest}>{title}</section> }
+    }
+
+    let data = std::rc::Rc::new(std::cell::RefCell::new(std::collections::HashMap::<
+        String,
+        serde_json::Value,
+    >::new()));
+
+    let attrs = apex::helpers::HtmlAttributes::new().attr("id", "main");
+
+    let result = tmpl! { <Card title="Hello" {..attrs} /> };
+
+    assert!(result.contains("<section class=\"card\" id=\"main\">"));
+    assert!(result.contains("-->Hello<!-- @expr-text-end:"));
+}
+
+#[test]
+fn test_void_elements() {
+    assert_eq!(
+        tmpl! { <div><input type="text" disabled><br><img src="/a.png" alt="Logo"></div> },
+        "<div><input disabled type=\"text\"><br><img alt=\"Logo\" src=\"/a.png\"></div>"
+    );
+}
+
+#[test]
+fn test_self_closing_non_void_element() {
+    assert_eq!(
+        tmpl! { <div class="empty" /> },
+        "<div class=\"empty\"></div>"
+    );
+}
+
+#[test]
+fn test_comments() {
+    assert_eq!(
+        tmpl! {
+            <div>
+                <!-- stripped from the output -->
+                <!--! kept in the output -->
+                Hello
+            </div>
+        },
+        "<div><!-- kept in the output -->Hello</div>"
+    );
+}
+
+#[test]
+fn test_doctype() {
+    assert_eq!(
+        tmpl! {
+            <!DOCTYPE html>
+            <html><body>Hello</body></html>
+        },
+        "<!DOCTYPE html><html><body>Hello</body></html>"
+    );
+}
+
+#[test]
+fn test_pre_preserves_whitespace() {
+    assert_eq!(
+        tmpl! { <div>  collapsed   text  <pre>  kept   as  is  </pre></div> },
+        "<div>collapsed text <pre>  kept   as  is  </pre></div>"
+    );
+}
+
+#[test]
+fn test_textarea_preserves_whitespace() {
+    let value = "text";
+
+    let result = tmpl! { <textarea>  line   {value}  </textarea> };
+
+    // The element marker precedes the tag, its text is updated as a whole on the client
+    assert!(result.starts_with("<!-- @element:"));
+    assert!(result.ends_with(" --><textarea>  line   text  </textarea>"));
+}
+
+#[test]
+fn test_title_without_expression_markers() {
+    let page = "Home";
+
+    // `<title>` content is shown as is in the tab, so it can't hold the marker comments
+    let result = tmpl! { <title>{page} - Apex</title> };
+
+    assert!(result.starts_with("<!-- @element:"));
+    assert!(result.ends_with(" --><title>Home - Apex</title>"));
+    assert!(!result.contains("@expr-text"));
+
+    assert_eq!(tmpl! { <title>Apex</title> }, "<title>Apex</title>");
+}
+
+#[test]
+fn test_script_raw_text() {
+    let count = 3;
+
+    assert_eq!(
+        tmpl! {
+            <script>if (window.ready) { start(#{count}); }</script>
+        },
+        "<script>if (window.ready) { start(3); }</script>"
+    );
+}
+
+#[test]
+fn test_style_raw_text() {
+    assert_eq!(
+        tmpl! {
+            <style>.card { color: red; }</style>
+        },
+        "<style>.card { color: red; }</style>"
+    );
+}
+
+#[test]
+fn test_expression_with_nested_braces() {
+    let name = "John";
+    let admin = true;
+
+    let result = tmpl! {
+        <p>{format!("{} {{admin}}", name)} {if admin { "}" } else { "{" }}</p>
+    };
+
+    assert!(result.contains("-->John {admin}<!-- @expr-text-end:"));
+    assert!(result.contains("-->}<!-- @expr-text-end:"));
+}
+
+#[test]
+fn test_attribute_expression_with_nested_braces() {
+    let id = 7;
+
+    let result = tmpl! { <div id={format!("item-{}", { id + 1 })} /> };
+
+    assert!(result.ends_with(r#"<div id="item-8"></div>"#));
+}
+
+#[test]
+fn test_template_file() {
+    let name = "John";
+    let count = 3;
+
+    let result = tmpl!(file = "tests/templates/greeting.html");
+
+    assert!(result.starts_with(r#"<section class="greeting"><h1>Hello, <!-- @expr-text-begin:"#));
+    assert!(result.contains("-->John<!-- @expr-text-end:"));
+    assert!(result.contains("-->You have 3 new messages<!-- @expr-text-end:"));
+    assert!(result.ends_with("</p></section>"));
+}
+
+#[test]
+fn test_component_template_file() {
+    #[component(template = "tests/templates/card.html")]
+    fn card(#[prop] title: &'static str) {
+        let body = title.to_uppercase();
+    }
+
+    let data = std::rc::Rc::new(std::cell::RefCell::new(std::collections::HashMap::<
+        String,
+        serde_json::Value,
+    >::new()));
+
+    let result = tmpl! { <Card title="news" /> };
+
+    assert!(result.starts_with(r#"<div class="card"><h2>"#));
+    assert!(result.contains("-->news<!-- @expr-text-end:"));
+    assert!(result.contains("-->NEWS<!-- @expr-text-end:"));
+}
+
+#[test]
+fn test_allowed_lints() {
+    assert_eq!(
+        tmpl! { #![allow(unknown_elements)] <widget>Hello</widget> },
+        "<widget>Hello</widget>"
+    );
+}
+
+#[test]
+fn test_svg_elements() {
+    assert_eq!(
+        tmpl! { <svg viewBox="0 0 24 24"><path d="M0 0h24v24H0z" fill="none" /><foreignObject width="24" height="24"><p>Hi</p></foreignObject></svg> },
+        "<svg viewBox=\"0 0 24 24\"><path d=\"M0 0h24v24H0z\" fill=\"none\"></path><foreignObject height=\"24\" width=\"24\"><p>Hi</p></foreignObject></svg>"
+    );
+}
+
+#[test]
+fn test_mathml_elements() {
+    assert_eq!(
+        tmpl! { <math><mfrac><mi>x</mi><mn>2</mn></mfrac></math> },
+        "<math><mfrac><mi>x</mi><mn>2</mn></mfrac></math>"
+    );
 }
@@ -0,0 +1,17 @@
+use apex::prelude::*;
+
+#[component]
+fn badge(#[prop] label: &'static str, #[prop(default)] muted: bool) {
+    let muted = if muted { "muted" } else { "" };
+
+    tmpl! { <span class={muted}>{label}</span> }
+}
+
+fn main() {
+    let data = std::rc::Rc::new(std::cell::RefCell::new(std::collections::HashMap::<
+        String,
+        serde_json::Value,
+    >::new()));
+
+    let _ = tmpl! { <Badge muted={true} /> };
+}
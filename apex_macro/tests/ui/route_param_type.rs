@@ -0,0 +1,8 @@
+use apex::prelude::*;
+
+#[route(path = "/users/{id}")]
+fn user_page(params: HashMap<String, u32>) {
+    let _ = params;
+}
+
+fn main() {}
@@ -0,0 +1,15 @@
+use apex::prelude::*;
+
+#[component]
+fn card() {
+    tmpl! { <div><#slot header /><#slot /></div> }
+}
+
+fn main() {
+    let data = std::rc::Rc::new(std::cell::RefCell::new(std::collections::HashMap::<
+        String,
+        serde_json::Value,
+    >::new()));
+
+    let _ = tmpl! { <Card><#hedaer>Title</#hedaer></Card> };
+}
@@ -0,0 +1,19 @@
+use apex::prelude::*;
+
+#[component]
+fn user_row(#[prop] name: String, #[slot] actions: Slot<String>) {
+    tmpl! { <li>{name.clone()}<#slot actions {name.clone()} /></li> }
+}
+
+fn main() {
+    let data = std::rc::Rc::new(std::cell::RefCell::new(std::collections::HashMap::<
+        String,
+        serde_json::Value,
+    >::new()));
+
+    let _ = tmpl! {
+        <UserRow name={"Mike".to_owned()}>
+            <#actoins let:name><button>Edit {name}</button></#actoins>
+        </UserRow>
+    };
+}
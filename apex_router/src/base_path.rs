@@ -0,0 +1,82 @@
+//! Path the router is mounted at, when it's nested in an other router such as axum's.
+//!
+//! The routes, links and redirects of the app are written relative to it, the path is added
+//! when they're sent to the browser and removed from the paths the browser is at.
+
+/// Returns the path the router is mounted at, empty at the root of the site. On the server
+/// it's the one of the request being rendered, in the browser the one the page was rendered
+/// with.
+pub fn base_path() -> String {
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        crate::RequestContext::current()
+            .map(|request| request.base_path)
+            .unwrap_or_default()
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    {
+        web_sys::window()
+            .and_then(|window| js_sys::Reflect::get(&window, &"APEX_BASE_PATH".into()).ok())
+            .and_then(|base_path| base_path.as_string())
+            .unwrap_or_default()
+    }
+}
+
+/// Returns the path on the site of a path of the app: `/users` is `/app/users` when the router
+/// is mounted at `/app`. Other URLs are returned as is.
+pub fn with_base_path(path: &str) -> String {
+    prefix_path(&base_path(), path)
+}
+
+/// Returns the path of the app for a path on the site, the opposite of [`with_base_path`].
+pub fn strip_base_path(path: &str) -> String {
+    unprefix_path(&base_path(), path)
+}
+
+pub(crate) fn prefix_path(base_path: &str, path: &str) -> String {
+    if base_path.is_empty() || !path.starts_with('/') || path.starts_with("//") {
+        return path.to_owned();
+    }
+
+    match path.strip_prefix('/') {
+        Some("") => base_path.to_owned(),
+        Some(query) if query.starts_with('?') => format!("{base_path}{query}"),
+        _ => format!("{base_path}{path}"),
+    }
+}
+
+pub(crate) fn unprefix_path(base_path: &str, path: &str) -> String {
+    match path.strip_prefix(base_path) {
+        _ if base_path.is_empty() => path.to_owned(),
+        Some("") => "/".to_owned(),
+        Some(rest) if rest.starts_with('/') => rest.to_owned(),
+        _ => path.to_owned(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn prefix_paths_of_the_app() {
+        assert_eq!(prefix_path("", "/users"), "/users");
+        assert_eq!(prefix_path("/app", "/users?page=2"), "/app/users?page=2");
+        assert_eq!(prefix_path("/app", "/"), "/app");
+        assert_eq!(prefix_path("/app", "/?tab=1"), "/app?tab=1");
+        assert_eq!(
+            prefix_path("/app", "https://example.com/"),
+            "https://example.com/"
+        );
+        assert_eq!(prefix_path("/app", "//example.com/"), "//example.com/");
+    }
+
+    #[test]
+    fn unprefix_paths_on_the_site() {
+        assert_eq!(unprefix_path("", "/users"), "/users");
+        assert_eq!(unprefix_path("/app", "/app/users"), "/users");
+        assert_eq!(unprefix_path("/app", "/app"), "/");
+        assert_eq!(unprefix_path("/app", "/application"), "/application");
+    }
+}
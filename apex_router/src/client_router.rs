@@ -5,7 +5,9 @@ use std::{cell::RefCell, collections::HashMap, fmt, rc::Rc};
 use wasm_bindgen::JsCast;
 use wasm_bindgen::prelude::Closure;
 
-use crate::get_matched_path;
+use crate::{get_matched_path, strip_base_path, with_base_path};
+
+const HTML_NAMESPACE: &str = "http://www.w3.org/1999/xhtml";
 
 pub trait ApexClientRoute {
     fn path(&self) -> &'static str {
@@ -97,6 +99,178 @@ impl ApexClientRouter {
         }
     }
 
+    /// Show the route the server answered with in the outlet of `exclude_path`, following
+    /// the redirects of loaders and actions. The response is the JSON sent for requests
+    /// with `has_exclude`.
+    fn apply_route_response(
+        fetch_promise: js_sys::Promise,
+        path: String,
+        exclude_path: String,
+        push_history: bool,
+    ) {
+        let window = web_sys::window().expect("window not found");
+        let history = window.history().expect("history not found");
+        let document = window.document().expect("document not found");
+
+        let document_clone = document.clone();
+        let path_clone: String = path.clone();
+        let history_clone = history.clone();
+
+        let response_callback = {
+            let document_clone = document_clone.clone();
+            let path_clone = path_clone.clone();
+            let history_clone = history_clone.clone();
+
+            Closure::wrap(Box::new(move |response: wasm_bindgen::JsValue| {
+                let document = document_clone.clone();
+                let path = path_clone.clone();
+                let history = history_clone.clone();
+                let exclude_path = exclude_path.clone();
+
+                if let Ok(response) = response.dyn_into::<web_sys::Response>() {
+                    let text_promise = response.text().unwrap();
+                    let document_clone2 = document.clone();
+
+                    let text_callback = {
+                        let document_clone2 = document_clone2.clone();
+                        let path = path.clone();
+                        let history = history.clone();
+
+                        Closure::wrap(Box::new(move |json_text: wasm_bindgen::JsValue| {
+                            if let Some(json_str) = json_text.as_string() {
+                                if let Ok(json_obj) = js_sys::JSON::parse(&json_str) {
+                                    // Follow redirects returned by loaders, in the app
+                                    // when they stay on this site
+                                    if let Some(location) =
+                                        js_sys::Reflect::get(&json_obj, &"redirect".into())
+                                            .ok()
+                                            .and_then(|location| location.as_string())
+                                    {
+                                        let window = web_sys::window().expect("window not found");
+
+                                        if location.starts_with('/') {
+                                            let event_init = web_sys::CustomEventInit::new();
+                                            event_init.set_detail(&location.into());
+
+                                            if let Ok(custom_event) =
+                                                web_sys::CustomEvent::new_with_event_init_dict(
+                                                    "apex:navigate",
+                                                    &event_init,
+                                                )
+                                            {
+                                                let _ =
+                                                    document_clone2.dispatch_event(&custom_event);
+                                            }
+                                        } else {
+                                            let _ = window.location().set_href(&location);
+                                        }
+
+                                        return;
+                                    }
+
+                                    let html_value =
+                                        js_sys::Reflect::get(&json_obj, &"html".into())
+                                            .unwrap_or(wasm_bindgen::JsValue::from_str(""));
+
+                                    if let Ok(data_value) =
+                                        js_sys::Reflect::get(&json_obj, &"data".into())
+                                    {
+                                        let window = web_sys::window().expect("window not found");
+
+                                        // Get existing INIT_DATA or create new object
+                                        let existing_data =
+                                            js_sys::Reflect::get(&window, &"INIT_DATA".into())
+                                                .unwrap_or_else(|_| js_sys::Object::new().into());
+
+                                        // If existing_data is not an object, create a new one
+                                        let init_data = if existing_data.is_object() {
+                                            existing_data
+                                        } else {
+                                            js_sys::Object::new().into()
+                                        };
+
+                                        // Merge new data into existing INIT_DATA
+                                        if let Some(data_obj) =
+                                            data_value.dyn_ref::<js_sys::Object>()
+                                        {
+                                            let entries = js_sys::Object::entries(data_obj);
+                                            let length = js_sys::Array::length(&entries);
+
+                                            for i in 0..length {
+                                                if let Some(entry) =
+                                                    entries.get(i).dyn_ref::<js_sys::Array>()
+                                                {
+                                                    let key = entry.get(0);
+                                                    let value = entry.get(1);
+                                                    let _ = js_sys::Reflect::set(
+                                                        &init_data, &key, &value,
+                                                    );
+                                                }
+                                            }
+                                        }
+
+                                        let _ = js_sys::Reflect::set(
+                                            &window,
+                                            &"INIT_DATA".into(),
+                                            &init_data,
+                                        );
+                                    }
+
+                                    if let Some(html_text) = html_value.as_string() {
+                                        web_sys::console::log_1(
+                                            &format!("Exclude path: {exclude_path}").into(),
+                                        );
+
+                                        if push_history {
+                                            let _ = history.push_state_with_url(
+                                                &js_sys::Object::new(),
+                                                "",
+                                                Some(&with_base_path(&path)),
+                                            );
+                                        }
+
+                                        let event_init = web_sys::CustomEventInit::new();
+                                        let detail = js_sys::Object::new();
+
+                                        let _ = js_sys::Reflect::set(
+                                            &detail,
+                                            &"outlet_key".into(),
+                                            &exclude_path.to_string().into(),
+                                        );
+
+                                        let _ = js_sys::Reflect::set(
+                                            &detail,
+                                            &"outlet_content".into(),
+                                            &html_text.into(),
+                                        );
+
+                                        event_init.set_detail(&detail);
+
+                                        if let Ok(custom_event) =
+                                            web_sys::CustomEvent::new_with_event_init_dict(
+                                                "apex:rehydrate",
+                                                &event_init,
+                                            )
+                                        {
+                                            let _ = document_clone2.dispatch_event(&custom_event);
+                                        }
+                                    }
+                                }
+                            }
+                        })
+                            as Box<dyn FnMut(wasm_bindgen::JsValue)>)
+                    };
+
+                    let _ = text_promise.then(&text_callback);
+                    text_callback.forget();
+                }
+            }) as Box<dyn FnMut(wasm_bindgen::JsValue)>)
+        };
+
+        let _ = fetch_promise.then(&response_callback);
+        response_callback.forget();
+    }
+
     fn init(&self) {
         let window = web_sys::window().expect("window not found");
         let document = window.document().expect("document not found");
@@ -105,162 +279,16 @@ impl ApexClientRouter {
             Closure::wrap(Box::new(move |event: web_sys::CustomEvent| {
                 if let Some(path) = event.detail().as_string() {
                     let window = web_sys::window().expect("window not found");
-                    let history = window.history().expect("history not found");
-                    let document = window.document().expect("document not found");
                     let current_path = window.location().pathname().expect("pathname not found");
+                    let current_path = strip_base_path(&current_path);
                     let exclude_path = get_matched_path(&current_path, &path);
 
-                    let fetch_promise = window
-                        .fetch_with_str(&format!("{path}?has_exclude&exclude={exclude_path}&"));
-
-                    let document_clone = document.clone();
-                    let path_clone: String = path.clone();
-                    let history_clone = history.clone();
-
-                    let response_callback = {
-                        let document_clone = document_clone.clone();
-                        let path_clone = path_clone.clone();
-                        let history_clone = history_clone.clone();
-
-                        Closure::wrap(Box::new(move |response: wasm_bindgen::JsValue| {
-                            let document = document_clone.clone();
-                            let path = path_clone.clone();
-                            let history = history_clone.clone();
-                            let exclude_path = exclude_path.clone();
-
-                            if let Ok(response) = response.dyn_into::<web_sys::Response>() {
-                                let text_promise = response.text().unwrap();
-                                let document_clone2 = document.clone();
-
-                                let text_callback = {
-                                    let document_clone2 = document_clone2.clone();
-                                    let path = path.clone();
-                                    let history = history.clone();
-
-                                    Closure::wrap(Box::new(
-                                        move |json_text: wasm_bindgen::JsValue| {
-                                            if let Some(json_str) = json_text.as_string() {
-                                                if let Ok(json_obj) = js_sys::JSON::parse(&json_str)
-                                                {
-                                                    let html_value = js_sys::Reflect::get(
-                                                        &json_obj,
-                                                        &"html".into(),
-                                                    )
-                                                    .unwrap_or(wasm_bindgen::JsValue::from_str(""));
-
-                                                    if let Ok(data_value) = js_sys::Reflect::get(
-                                                        &json_obj,
-                                                        &"data".into(),
-                                                    ) {
-                                                        let window = web_sys::window()
-                                                            .expect("window not found");
-
-                                                        // Get existing INIT_DATA or create new object
-                                                        let existing_data = js_sys::Reflect::get(
-                                                            &window,
-                                                            &"INIT_DATA".into(),
-                                                        )
-                                                        .unwrap_or_else(|_| {
-                                                            js_sys::Object::new().into()
-                                                        });
-
-                                                        // If existing_data is not an object, create a new one
-                                                        let init_data = if existing_data.is_object()
-                                                        {
-                                                            existing_data
-                                                        } else {
-                                                            js_sys::Object::new().into()
-                                                        };
-
-                                                        // Merge new data into existing INIT_DATA
-                                                        if let Some(data_obj) =
-                                                            data_value.dyn_ref::<js_sys::Object>()
-                                                        {
-                                                            let entries =
-                                                                js_sys::Object::entries(data_obj);
-                                                            let length =
-                                                                js_sys::Array::length(&entries);
-
-                                                            for i in 0..length {
-                                                                if let Some(entry) = entries
-                                                                    .get(i)
-                                                                    .dyn_ref::<js_sys::Array>(
-                                                                ) {
-                                                                    let key = entry.get(0);
-                                                                    let value = entry.get(1);
-                                                                    let _ = js_sys::Reflect::set(
-                                                                        &init_data, &key, &value,
-                                                                    );
-                                                                }
-                                                            }
-                                                        }
-
-                                                        let _ = js_sys::Reflect::set(
-                                                            &window,
-                                                            &"INIT_DATA".into(),
-                                                            &init_data,
-                                                        );
-                                                    }
-
-                                                    if let Some(html_text) = html_value.as_string()
-                                                    {
-                                                        web_sys::console::log_1(
-                                                            &format!(
-                                                                "Exclude path: {exclude_path}"
-                                                            )
-                                                            .into(),
-                                                        );
-
-                                                        let _ = history.push_state_with_url(
-                                                            &js_sys::Object::new(),
-                                                            "",
-                                                            Some(&path),
-                                                        );
-
-                                                        let event_init =
-                                                            web_sys::CustomEventInit::new();
-                                                        let detail = js_sys::Object::new();
-
-                                                        let _ = js_sys::Reflect::set(
-                                                            &detail,
-                                                            &"outlet_key".into(),
-                                                            &exclude_path.to_string().into(),
-                                                        );
-
-                                                        let _ = js_sys::Reflect::set(
-                                                            &detail,
-                                                            &"outlet_content".into(),
-                                                            &html_text.into(),
-                                                        );
-
-                                                        event_init.set_detail(&detail);
-
-                                                        if let Ok(custom_event) =
-                                                            web_sys::CustomEvent::new_with_event_init_dict(
-                                                                "apex:rehydrate",
-                                                                &event_init,
-                                                            )
-                                                        {
-                                                            let _ = document_clone2
-                                                                .dispatch_event(&custom_event);
-                                                        }
-                                                    }
-                                                }
-                                            }
-                                        },
-                                    )
-                                        as Box<dyn FnMut(wasm_bindgen::JsValue)>)
-                                };
+                    let fetch_promise = window.fetch_with_str(&format!(
+                        "{}?has_exclude&exclude={exclude_path}&",
+                        with_base_path(&path)
+                    ));
 
-                                let _ = text_promise.then(&text_callback);
-                                text_callback.forget();
-                            }
-                        })
-                            as Box<dyn FnMut(wasm_bindgen::JsValue)>)
-                    };
-
-                    let _ = fetch_promise.then(&response_callback);
-                    response_callback.forget();
+                    Self::apply_route_response(fetch_promise, path, exclude_path, true);
                 }
             }) as Box<dyn FnMut(_)>)
         };
@@ -272,6 +300,75 @@ impl ApexClientRouter {
 
         navigate_callback.forget();
 
+        // Forms submitted by `Form` update the outlet of the parent of their route. The
+        // browser submits the forms of routes without a parent itself.
+        let submit_callback = {
+            let router = self.router.clone();
+
+            Closure::wrap(Box::new(move |event: web_sys::CustomEvent| {
+                let detail = event.detail();
+                let get_detail = |key: &str| js_sys::Reflect::get(&detail, &key.into()).ok();
+
+                let Some(action) = get_detail("action").and_then(|action| action.as_string())
+                else {
+                    return;
+                };
+
+                let body = get_detail("body")
+                    .and_then(|body| body.as_string())
+                    .unwrap_or_default();
+
+                let path = action.split('?').next().unwrap_or_default().to_owned();
+
+                let exclude_path = router.borrow().at(&path).ok().and_then(|route_match| {
+                    let parent_pattern = route_match.value.parent_pattern.as_ref()?.last()?;
+                    Some(get_matched_path(parent_pattern, &path))
+                });
+
+                let Some(exclude_path) = exclude_path else {
+                    if let Some(form) = get_detail("form")
+                        && let Ok(submit) = js_sys::Reflect::get(&form, &"submit".into())
+                        && let Ok(submit) = submit.dyn_into::<js_sys::Function>()
+                    {
+                        let _ = submit.call0(&form);
+                    }
+
+                    return;
+                };
+
+                let window = web_sys::window().expect("window not found");
+                let current_path = window.location().pathname().expect("pathname not found");
+                let current_path = strip_base_path(&current_path);
+
+                let request_init = web_sys::RequestInit::new();
+                request_init.set_method("POST");
+                request_init.set_body(&body.into());
+
+                if let Ok(headers) = web_sys::Headers::new() {
+                    let _ = headers.set("content-type", "application/x-www-form-urlencoded");
+                    request_init.set_headers(&headers);
+                }
+
+                let fetch_promise = window.fetch_with_str_and_init(
+                    &format!(
+                        "{}?has_exclude&exclude={exclude_path}&",
+                        with_base_path(&path)
+                    ),
+                    &request_init,
+                );
+
+                let push_history = path != current_path;
+                Self::apply_route_response(fetch_promise, path, exclude_path, push_history);
+            }) as Box<dyn FnMut(_)>)
+        };
+
+        let _ = document.add_event_listener_with_callback(
+            "apex:submit",
+            submit_callback.as_ref().unchecked_ref(),
+        );
+
+        submit_callback.forget();
+
         let rehydrate_callback = {
             let router = self.router.clone();
             let state = self.state.clone();
@@ -316,11 +413,8 @@ impl ApexClientRouter {
                     let window = web_sys::window().expect("window not found");
                     let document = window.document().expect("document not found");
 
-                    // Create a temporary div to parse the outlet content
-                    let temp_div = document
-                        .create_element("div")
-                        .expect("failed to create div");
-                    temp_div.set_inner_html(&outlet_content);
+                    // Parse the outlet content in the namespace of the element it goes into
+                    let temp_div = Self::parse_fragment(&document, end, &outlet_content);
 
                     // Remove all nodes between begin and end comments
                     let mut current_node = begin.next_sibling();
@@ -401,11 +495,8 @@ impl ApexClientRouter {
                 };
 
                 let template_content = template.inner_html();
-                // Create a temporary div to parse the outlet content
-                let temp_div = document
-                    .create_element("div")
-                    .expect("failed to create div");
-                temp_div.set_inner_html(&template_content);
+                // Parse the template content in the namespace of the element it goes into
+                let temp_div = Self::parse_fragment(&document, end, &template_content);
 
                 // Remove all nodes between begin and end comments
                 let mut current_node = begin.next_sibling();
@@ -462,6 +553,34 @@ impl ApexClientRouter {
         Self::hydrate_router(self.router.clone(), None, self.state.clone());
     }
 
+    /// Parse `html` inside an element like the parent of `anchor`, so content swapped into
+    /// `<svg>` or `<math>` gets the SVG or MathML namespace instead of becoming HTML
+    /// elements, while `<foreignObject>` content stays HTML
+    fn parse_fragment(
+        document: &web_sys::Document,
+        anchor: &web_sys::Node,
+        html: &str,
+    ) -> web_sys::Element {
+        let context = anchor
+            .parent_element()
+            .filter(|parent| parent.namespace_uri().as_deref() != Some(HTML_NAMESPACE))
+            .and_then(|parent| {
+                document
+                    .create_element_ns(parent.namespace_uri().as_deref(), &parent.local_name())
+                    .ok()
+            });
+
+        let context = match context {
+            Some(context) => context,
+            None => document
+                .create_element("div")
+                .expect("failed to create div"),
+        };
+
+        context.set_inner_html(html);
+        context
+    }
+
     fn cleanup_init_script() {
         let window = web_sys::window().expect("window not found");
         let document = window.document().expect("document not found");
@@ -478,7 +597,7 @@ impl ApexClientRouter {
     ) {
         apex_utils::reset_counters();
         let location = web_sys::window().expect("window not found").location();
-        let pathname = location.pathname().expect("pathname not found");
+        let pathname = strip_base_path(&location.pathname().expect("pathname not found"));
 
         if let Ok(route_matched) = router.borrow().at(&pathname) {
             if let Some(parent_patterns_chain) = route_matched.value.parent_pattern.as_ref() {
@@ -0,0 +1,90 @@
+//! Response produced by the server router, or by a loader that doesn't render its route.
+
+/// HTTP response with a status, headers and a body.
+///
+/// The server router returns one for every request, so the HTTP server only has to copy
+/// it into its own response type.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HttpResponse {
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    pub body: String,
+}
+
+impl HttpResponse {
+    /// Creates an empty response with the given status.
+    pub fn new(status: u16) -> Self {
+        Self {
+            status,
+            headers: Vec::new(),
+            body: String::new(),
+        }
+    }
+
+    /// Creates a `200 OK` HTML response.
+    pub fn html(body: impl Into<String>) -> Self {
+        Self::new(200)
+            .header("content-type", "text/html; charset=utf-8")
+            .body(body)
+    }
+
+    /// Creates a `200 OK` JSON response.
+    pub fn json(body: impl Into<String>) -> Self {
+        Self::new(200)
+            .header("content-type", "application/json")
+            .body(body)
+    }
+
+    /// Creates a `302 Found` redirect to `location`.
+    pub fn redirect(location: impl Into<String>) -> Self {
+        Self::new(302).header("location", location)
+    }
+
+    /// Creates a `404 Not Found` response.
+    pub fn not_found() -> Self {
+        Self::new(404)
+            .header("content-type", "text/html; charset=utf-8")
+            .body("Not Found")
+    }
+
+    /// Creates a `500 Internal Server Error` response. The body is generic, the error behind
+    /// it is logged where it's turned into this response as it may hold internal details.
+    pub fn server_error() -> Self {
+        Self::new(500)
+            .header("content-type", "text/plain; charset=utf-8")
+            .body("Internal Server Error")
+    }
+
+    /// Sets a header, replacing any header with the same name.
+    pub fn header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        let name = name.into();
+
+        self.headers
+            .retain(|(existing, _)| !existing.eq_ignore_ascii_case(&name));
+        self.headers.push((name, value.into()));
+        self
+    }
+
+    /// Sets the body.
+    pub fn body(mut self, body: impl Into<String>) -> Self {
+        self.body = body.into();
+        self
+    }
+
+    /// Returns the value of a header, matching its name case-insensitively.
+    pub fn header_value(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(existing, _)| existing.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Returns where the response redirects to, if it's a redirect.
+    pub fn location(&self) -> Option<&str> {
+        if (300..400).contains(&self.status) {
+            self.header_value("location")
+        } else {
+            None
+        }
+    }
+}
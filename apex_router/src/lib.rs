@@ -1,10 +1,43 @@
 #![allow(missing_docs)]
 
+mod base_path;
 pub mod client_router;
 mod get_matched_path;
+mod http_response;
 pub mod init_data;
+mod loader_result;
+mod middleware;
+mod path;
+mod request_context;
+#[cfg(not(target_arch = "wasm32"))]
+mod run_local;
+mod server_fn;
 mod server_router;
+#[cfg(feature = "tower")]
+mod service;
 
+pub use base_path::{base_path, strip_base_path, with_base_path};
 pub use client_router::{ApexClientRoute, ApexClientRouter};
 pub(crate) use get_matched_path::get_matched_path;
-pub use server_router::{ApexServerHandler, ApexServerRoute, ApexServerRouter};
+pub use http_response::HttpResponse;
+pub use loader_result::LoaderResult;
+pub use middleware::{ApexMiddleware, MiddlewareFuture};
+pub use path::Path;
+pub use request_context::{DEFAULT_MAX_BODY_SIZE, Extensions, RequestContext};
+#[cfg(not(target_arch = "wasm32"))]
+pub use run_local::run_local;
+#[cfg(not(target_arch = "wasm32"))]
+pub use server_fn::ServerFnRegistration;
+pub use server_fn::{
+    ApexServerFn, ApexServerFnHandler, ServerFnError, call_server_fn, handle_server_fn,
+};
+pub use server_router::{
+    ApexServerAction, ApexServerHandler, ApexServerRender, ApexServerRoute, ApexServerRouter,
+};
+#[cfg(feature = "tower")]
+pub use service::ApexService;
+
+// Used by `#[server]` to collect the functions at link time
+#[doc(hidden)]
+#[cfg(not(target_arch = "wasm32"))]
+pub use inventory;
@@ -0,0 +1,104 @@
+//! Result of a route loader: the data to render the route with, or a response sent
+//! instead.
+
+use crate::HttpResponse;
+
+/// Value returned by a route function that may not render its route.
+///
+/// `Ok` renders the route with the data, the other variants are sent as the response
+/// instead, to the browser or to the client router during navigation.
+#[derive(Debug, Clone, PartialEq)]
+pub enum LoaderResult<T> {
+    /// Success with data
+    Ok(T),
+    /// Redirect to URL
+    Redirect(String),
+    /// 404 Not Found
+    NotFound,
+    /// 500 Server Error
+    ServerError(String),
+    /// Custom response
+    Response(HttpResponse),
+}
+
+impl<T> LoaderResult<T> {
+    pub fn ok(data: T) -> Self {
+        Self::Ok(data)
+    }
+
+    pub fn redirect(location: impl Into<String>) -> Self {
+        Self::Redirect(location.into())
+    }
+
+    pub fn not_found() -> Self {
+        Self::NotFound
+    }
+
+    pub fn server_error(message: impl Into<String>) -> Self {
+        Self::ServerError(message.into())
+    }
+
+    pub fn response(response: HttpResponse) -> Self {
+        Self::Response(response)
+    }
+
+    /// The data, or the response to send instead of rendering the route.
+    pub fn into_response(self) -> Result<T, HttpResponse> {
+        match self {
+            Self::Ok(data) => Ok(data),
+            Self::Redirect(location) => Err(HttpResponse::redirect(location)),
+            Self::NotFound => Err(HttpResponse::not_found()),
+            Self::ServerError(message) => {
+                log::error!("route failed to load: {message}");
+
+                Err(HttpResponse::server_error())
+            }
+            Self::Response(response) => Err(response),
+        }
+    }
+}
+
+/// Errors returned by a route function become a server error, their message is logged.
+impl<T, E: std::fmt::Display> From<Result<T, E>> for LoaderResult<T> {
+    fn from(result: Result<T, E>) -> Self {
+        match result {
+            Ok(data) => Self::Ok(data),
+            Err(error) => Self::ServerError(error.to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn into_response() {
+        assert_eq!(LoaderResult::ok(42).into_response(), Ok(42));
+
+        let response = LoaderResult::<()>::redirect("/login")
+            .into_response()
+            .unwrap_err();
+        assert_eq!(response.status, 302);
+        assert_eq!(response.location(), Some("/login"));
+
+        let response = LoaderResult::<()>::not_found().into_response().unwrap_err();
+        assert_eq!(response.status, 404);
+
+        let response = LoaderResult::<()>::from(Err::<(), _>("<script>alert(1)</script>"))
+            .into_response()
+            .unwrap_err();
+        assert_eq!(response.status, 500);
+        assert_eq!(response.body, "Internal Server Error");
+        assert_eq!(
+            response.header_value("content-type"),
+            Some("text/plain; charset=utf-8")
+        );
+
+        let teapot = HttpResponse::new(418).body("I'm a teapot");
+        let response = LoaderResult::<()>::response(teapot.clone())
+            .into_response()
+            .unwrap_err();
+        assert_eq!(response, teapot);
+    }
+}
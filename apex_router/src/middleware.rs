@@ -0,0 +1,62 @@
+//! Middleware run around the requests handled by the server router, e.g. for
+//! authentication, logging or adding headers.
+
+use std::{future::Future, pin::Pin};
+
+use crate::{HttpResponse, RequestContext};
+
+/// Future returned by the hooks of a middleware.
+pub type MiddlewareFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
+
+/// Hooks run before and after a request is handled.
+///
+/// Middleware is added to the whole router with
+/// [`ApexServerRouter::add_middleware`](crate::ApexServerRouter::add_middleware), or to a
+/// route and its children with `#[route(middleware = [...])]`. The router's middleware runs
+/// first, then the middleware of the outermost parent route down to the matched route.
+/// The `after` hooks run in the reverse order.
+///
+/// An async function taking and returning the request is a middleware with a `before` hook:
+///
+/// ```rust,ignore
+/// async fn require_auth(request: RequestContext) -> Result<RequestContext, HttpResponse> {
+///     match request.cookie("session") {
+///         Some(_) => Ok(request),
+///         None => Err(HttpResponse::redirect("/login")),
+///     }
+/// }
+/// ```
+pub trait ApexMiddleware: Send + Sync {
+    /// Runs before the route is loaded. Returns the request given to the route, e.g. with
+    /// an extension added, or the response sent instead.
+    fn before(
+        &self,
+        request: RequestContext,
+    ) -> MiddlewareFuture<'_, Result<RequestContext, HttpResponse>> {
+        Box::pin(async move { Ok(request) })
+    }
+
+    /// Runs on the response, including the responses of the middleware that ran after this
+    /// one. Not run when this middleware's `before` hook returned a response.
+    fn after<'a>(
+        &'a self,
+        request: &'a RequestContext,
+        response: HttpResponse,
+    ) -> MiddlewareFuture<'a, HttpResponse> {
+        let _ = request;
+        Box::pin(async move { response })
+    }
+}
+
+impl<F, Fut> ApexMiddleware for F
+where
+    F: Fn(RequestContext) -> Fut + Send + Sync,
+    Fut: Future<Output = Result<RequestContext, HttpResponse>> + Send + 'static,
+{
+    fn before(
+        &self,
+        request: RequestContext,
+    ) -> MiddlewareFuture<'_, Result<RequestContext, HttpResponse>> {
+        Box::pin(self(request))
+    }
+}
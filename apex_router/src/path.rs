@@ -0,0 +1,78 @@
+//! Typed path parameters for route functions.
+
+use std::collections::HashMap;
+
+/// Path parameters deserialized into `T`, taken by route functions in place of the
+/// `HashMap<String, String>` of raw parameters:
+///
+/// ```rust,ignore
+/// #[derive(serde::Deserialize)]
+/// struct UserParams {
+///     id: u32,
+/// }
+///
+/// #[route(path = "/users/{id}", component = UserProfile)]
+/// fn user_page(Path(params): Path<UserParams>) -> LoaderResult<User> {
+///     ...
+/// }
+/// ```
+///
+/// When the parameters don't deserialize, e.g. `/users/abc` here, the route answers with
+/// `404 Not Found` without calling the route function.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Path<T>(pub T);
+
+impl<T: serde::de::DeserializeOwned> Path<T> {
+    /// Deserializes the raw path parameters. Values are parsed like query parameters, so
+    /// numbers, booleans and `Option`s work as fields.
+    pub fn from_params(
+        params: &HashMap<String, String>,
+    ) -> Result<Self, serde_urlencoded::de::Error> {
+        let encoded = serde_urlencoded::to_string(params)
+            .map_err(|error| serde::de::Error::custom(error.to_string()))?;
+
+        serde_urlencoded::from_str(&encoded).map(Path)
+    }
+}
+
+impl<T> std::ops::Deref for Path<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, PartialEq, serde::Deserialize)]
+    struct UserParams {
+        name: String,
+        age: u8,
+    }
+
+    #[test]
+    fn from_params() {
+        let params = HashMap::from([
+            ("name".to_owned(), "Mike Smith".to_owned()),
+            ("age".to_owned(), "30".to_owned()),
+        ]);
+
+        assert_eq!(
+            Path::<UserParams>::from_params(&params).unwrap(),
+            Path(UserParams {
+                name: "Mike Smith".to_owned(),
+                age: 30
+            })
+        );
+
+        let params = HashMap::from([
+            ("name".to_owned(), "Mike".to_owned()),
+            ("age".to_owned(), "thirty".to_owned()),
+        ]);
+
+        assert!(Path::<UserParams>::from_params(&params).is_err());
+    }
+}
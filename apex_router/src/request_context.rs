@@ -0,0 +1,279 @@
+//! Incoming HTTP request, given to route functions and to components rendered on the server.
+
+use std::{
+    any::{Any, TypeId},
+    cell::RefCell,
+    collections::HashMap,
+    net::SocketAddr,
+    sync::Arc,
+};
+
+/// Largest request body, in bytes, read by the servers running the router unless they're
+/// configured otherwise. Larger bodies are answered `413 Payload Too Large`.
+pub const DEFAULT_MAX_BODY_SIZE: usize = 2 * 1024 * 1024;
+
+thread_local! {
+    static CURRENT_REQUEST: RefCell<Option<RequestContext>> = const { RefCell::new(None) };
+}
+
+/// HTTP request handled by the server router.
+///
+/// A route function gets it by taking a second parameter, `request: RequestContext`.
+/// Components rendered on the server read it with [`RequestContext::current`].
+#[derive(Debug, Clone, Default)]
+pub struct RequestContext {
+    pub method: String,
+    /// Path and query, e.g. `/users?page=2`
+    pub uri: String,
+    pub headers: Vec<(String, String)>,
+    pub body: String,
+    pub remote_addr: Option<SocketAddr>,
+    /// Path the router is mounted at, e.g. `/app` when it's nested in an axum app, empty at
+    /// the root of the site. The `uri` is relative to it.
+    pub base_path: String,
+    pub extensions: Extensions,
+}
+
+impl RequestContext {
+    /// Creates a request without headers nor body.
+    pub fn new(method: impl Into<String>, uri: impl Into<String>) -> Self {
+        Self {
+            method: method.into(),
+            uri: uri.into(),
+            ..Default::default()
+        }
+    }
+
+    /// Adds a header.
+    pub fn header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+
+    /// Sets the body.
+    pub fn body(mut self, body: impl Into<String>) -> Self {
+        self.body = body.into();
+        self
+    }
+
+    /// Sets the address of the client.
+    pub fn remote_addr(mut self, remote_addr: SocketAddr) -> Self {
+        self.remote_addr = Some(remote_addr);
+        self
+    }
+
+    /// Sets the path the router is mounted at.
+    pub fn base_path(mut self, base_path: impl Into<String>) -> Self {
+        self.base_path = base_path.into().trim_end_matches('/').to_owned();
+        self
+    }
+
+    /// Adds a value to the extensions, e.g. a database pool shared by the loaders.
+    pub fn extension<T: Any + Send + Sync>(mut self, value: T) -> Self {
+        self.extensions.insert(value);
+        self
+    }
+
+    /// Returns the path, without the query.
+    pub fn path(&self) -> &str {
+        self.uri.split_once('?').map_or(&self.uri, |(path, _)| path)
+    }
+
+    /// Returns the raw query, without the leading `?`.
+    pub fn query_string(&self) -> &str {
+        self.uri.split_once('?').map_or("", |(_, query)| query)
+    }
+
+    /// Returns the decoded value of a query parameter, empty for a parameter without a value.
+    pub fn query_param(&self, name: &str) -> Option<String> {
+        serde_urlencoded::from_str::<Vec<(String, String)>>(self.query_string())
+            .ok()?
+            .into_iter()
+            .find_map(|(key, value)| (key == name).then_some(value))
+    }
+
+    /// Returns whether the request is a navigation of the client router, made with the
+    /// `has_exclude` query parameter and answered with JSON instead of the page.
+    pub fn is_navigation(&self) -> bool {
+        self.query_param("has_exclude").is_some()
+    }
+
+    /// Deserializes the query parameters.
+    pub fn query<T: serde::de::DeserializeOwned>(&self) -> Result<T, serde_urlencoded::de::Error> {
+        serde_urlencoded::from_str(self.query_string())
+    }
+
+    /// Deserializes a form body, as sent by `<form method="post">`.
+    pub fn form<T: serde::de::DeserializeOwned>(&self) -> Result<T, serde_urlencoded::de::Error> {
+        serde_urlencoded::from_str(&self.body)
+    }
+
+    /// Deserializes a JSON body.
+    pub fn json<T: serde::de::DeserializeOwned>(&self) -> Result<T, serde_json::Error> {
+        serde_json::from_str(&self.body)
+    }
+
+    /// Returns the value of a header, matching its name case-insensitively.
+    pub fn header_value(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(existing, _)| existing.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Returns the cookies sent with the request.
+    pub fn cookies(&self) -> HashMap<&str, &str> {
+        self.headers
+            .iter()
+            .filter(|(name, _)| name.eq_ignore_ascii_case("cookie"))
+            .flat_map(|(_, value)| value.split(';'))
+            .filter_map(|cookie| cookie.trim().split_once('='))
+            .collect()
+    }
+
+    /// Returns the value of a cookie.
+    pub fn cookie(&self, name: &str) -> Option<&str> {
+        self.cookies().get(name).copied()
+    }
+
+    /// Returns the request being rendered, on the server. `None` in the browser.
+    pub fn current() -> Option<RequestContext> {
+        CURRENT_REQUEST.with(|current| current.borrow().clone())
+    }
+
+    /// Runs `render` with this request as the current one.
+    #[doc(hidden)]
+    pub fn scope<T>(&self, render: impl FnOnce() -> T) -> T {
+        let previous = CURRENT_REQUEST.with(|current| current.replace(Some(self.clone())));
+        let result = render();
+        CURRENT_REQUEST.with(|current| *current.borrow_mut() = previous);
+
+        result
+    }
+}
+
+/// Values attached to a request by the server, looked up by type.
+#[derive(Clone, Default)]
+pub struct Extensions {
+    values: HashMap<TypeId, Arc<dyn Any + Send + Sync>>,
+}
+
+impl Extensions {
+    /// Inserts a value, replacing the value of the same type.
+    pub fn insert<T: Any + Send + Sync>(&mut self, value: T) {
+        self.values.insert(TypeId::of::<T>(), Arc::new(value));
+    }
+
+    /// Returns the value of the given type.
+    pub fn get<T: Any + Send + Sync>(&self) -> Option<&T> {
+        let value = self
+            .values
+            .get(&TypeId::of::<T>())
+            .and_then(|value| value.downcast_ref());
+
+        // Extensions of the `http` request given to the `tower` service, e.g. added by a layer
+        #[cfg(feature = "tower")]
+        let value = value.or_else(|| {
+            self.values
+                .get(&TypeId::of::<http::Extensions>())?
+                .downcast_ref::<http::Extensions>()?
+                .get::<T>()
+        });
+
+        value
+    }
+}
+
+impl std::fmt::Debug for Extensions {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Extensions")
+            .field("len", &self.values.len())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, PartialEq, serde::Deserialize)]
+    struct Pagination {
+        page: u32,
+        sort: Option<String>,
+    }
+
+    #[test]
+    fn request_context() {
+        let request = RequestContext::new("GET", "/users?page=2")
+            .header("Cookie", "session=abc; theme=dark")
+            .extension(42_u32);
+
+        assert_eq!(request.path(), "/users");
+        assert_eq!(
+            request.query::<Pagination>().unwrap(),
+            Pagination {
+                page: 2,
+                sort: None
+            }
+        );
+        assert_eq!(
+            request.header_value("cookie"),
+            Some("session=abc; theme=dark")
+        );
+        assert_eq!(request.cookie("theme"), Some("dark"));
+        assert_eq!(request.cookie("lang"), None);
+        assert_eq!(request.extensions.get::<u32>(), Some(&42));
+        assert_eq!(request.extensions.get::<u64>(), None);
+    }
+
+    #[test]
+    fn query_param() {
+        let request = RequestContext::new("GET", "/users?has_exclude&exclude=%2Fusers%2F1&");
+
+        assert_eq!(request.query_param("exclude").as_deref(), Some("/users/1"));
+        assert_eq!(request.query_param("has_exclude").as_deref(), Some(""));
+        assert!(request.is_navigation());
+
+        // Only the parameter itself counts, not a value or a name containing it
+        let request = RequestContext::new("GET", "/search?q=has_exclude&has_excluded=1");
+
+        assert_eq!(request.query_param("q").as_deref(), Some("has_exclude"));
+        assert_eq!(request.query_param("has_exclude"), None);
+        assert!(!request.is_navigation());
+    }
+
+    #[test]
+    fn request_body() {
+        let request = RequestContext::new("POST", "/users").body("page=2&sort=name");
+
+        assert_eq!(
+            request.form::<Pagination>().unwrap(),
+            Pagination {
+                page: 2,
+                sort: Some("name".to_owned())
+            }
+        );
+        assert!(request.json::<Pagination>().is_err());
+
+        let request = request.body(r#"{"page": 3}"#);
+
+        assert_eq!(
+            request.json::<Pagination>().unwrap(),
+            Pagination {
+                page: 3,
+                sort: None
+            }
+        );
+    }
+
+    #[test]
+    fn current_request() {
+        assert!(RequestContext::current().is_none());
+
+        let request = RequestContext::new("GET", "/about");
+        let path = request.scope(|| RequestContext::current().map(|request| request.uri));
+
+        assert_eq!(path.as_deref(), Some("/about"));
+        assert!(RequestContext::current().is_none());
+    }
+}
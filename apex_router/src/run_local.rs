@@ -0,0 +1,122 @@
+//! Runs route functions whose future isn't `Send`.
+
+use std::future::Future;
+use std::sync::OnceLock;
+
+use tokio::sync::{mpsc, oneshot};
+
+use crate::HttpResponse;
+
+/// Job run on the local worker, spawning its future on the worker's `LocalSet`
+type LocalJob = Box<dyn FnOnce() + Send>;
+
+/// Runs the future made by `make_future` on a worker thread with a `LocalSet`, so it may hold
+/// values that aren't `Send`, like an `Rc`, across `.await`s. Used by `#[route(local)]`.
+///
+/// The futures share the worker the way tasks share a runtime, none holds a thread while it
+/// waits. A panic of the future is resumed, a future that didn't finish is a server error.
+#[doc(hidden)]
+pub async fn run_local<F, Fut, T>(make_future: F) -> Result<T, HttpResponse>
+where
+    F: FnOnce() -> Fut + Send + 'static,
+    Fut: Future<Output = T> + 'static,
+    T: Send + 'static,
+{
+    let (output_sender, output_receiver) = oneshot::channel();
+
+    let job: LocalJob = Box::new(move || {
+        let task = tokio::task::spawn_local(async move { make_future().await });
+
+        tokio::task::spawn_local(async move {
+            let _ = output_sender.send(task.await);
+        });
+    });
+
+    if local_worker().send(job).is_err() {
+        log::error!("the local route worker isn't running");
+
+        return Err(HttpResponse::server_error());
+    }
+
+    match output_receiver.await {
+        Ok(Ok(output)) => Ok(output),
+        Ok(Err(error)) if error.is_panic() => std::panic::resume_unwind(error.into_panic()),
+        Ok(Err(_)) | Err(_) => {
+            log::error!("a local route function was cancelled before it finished");
+
+            Err(HttpResponse::server_error())
+        }
+    }
+}
+
+/// Sender of the jobs of the local worker, started on first use
+fn local_worker() -> &'static mpsc::UnboundedSender<LocalJob> {
+    static LOCAL_WORKER: OnceLock<mpsc::UnboundedSender<LocalJob>> = OnceLock::new();
+
+    LOCAL_WORKER.get_or_init(|| {
+        let (job_sender, mut job_receiver) = mpsc::unbounded_channel::<LocalJob>();
+
+        std::thread::Builder::new()
+            .name("apex-local-routes".to_owned())
+            .spawn(move || {
+                let runtime = tokio::runtime::Builder::new_current_thread()
+                    .enable_all()
+                    .build()
+                    .expect("failed to build the runtime of the local route worker");
+
+                tokio::task::LocalSet::new().block_on(&runtime, async move {
+                    while let Some(job) = job_receiver.recv().await {
+                        job();
+                    }
+                });
+            })
+            .expect("failed to start the local route worker");
+
+        job_sender
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn runs_futures_that_are_not_send() {
+        let output = run_local(|| async {
+            let value = std::rc::Rc::new(21);
+            tokio::task::yield_now().await;
+            *value * 2
+        })
+        .await;
+
+        assert_eq!(output, Ok(42));
+    }
+
+    #[tokio::test]
+    async fn runs_futures_concurrently() {
+        let (sender, receiver) = oneshot::channel::<i32>();
+
+        // The first future waits for the second one, which only finishes if they interleave
+        let (first, second) = tokio::join!(
+            run_local(|| async move { receiver.await.unwrap_or_default() * 2 }),
+            run_local(|| async move {
+                let value = std::rc::Rc::new(21);
+                let _ = sender.send(*value);
+            }),
+        );
+
+        assert_eq!(first, Ok(42));
+        assert_eq!(second, Ok(()));
+    }
+
+    #[tokio::test]
+    async fn resumes_panics() {
+        let result = tokio::spawn(run_local(|| async { panic!("route panicked") })).await;
+
+        let Err(error) = result else {
+            panic!("the panic wasn't resumed");
+        };
+
+        assert!(error.is_panic());
+    }
+}
@@ -0,0 +1,180 @@
+//! Server functions: async functions run by the server, called from the browser with `fetch`.
+
+use std::{future::Future, pin::Pin};
+
+use wasm_bindgen::JsCast;
+
+use crate::{HttpResponse, RequestContext};
+
+/// Type alias for the handlers of server function endpoints.
+///
+/// A handler takes the request, whose body holds the arguments of the function as JSON,
+/// and returns a future that resolves to the response holding the `Result` it returned.
+pub type ApexServerFnHandler =
+    Box<dyn Fn(RequestContext) -> Pin<Box<dyn Future<Output = HttpResponse> + Send>> + Send + Sync>;
+
+/// Endpoint of a `#[server]` function. The endpoints of every `#[server]` function linked
+/// into the server are registered by [`ApexServerRouter::new`](crate::ApexServerRouter::new),
+/// others with [`ApexServerRouter::register_server_fn`](crate::ApexServerRouter::register_server_fn).
+pub trait ApexServerFn: Send + Sync {
+    /// Returns the path the function is called at.
+    fn path(&self) -> &'static str;
+
+    /// Returns the handler running the function.
+    fn handler(&self) -> ApexServerFnHandler;
+}
+
+/// Endpoint submitted by `#[server]`, collected at link time for the server router.
+#[doc(hidden)]
+#[cfg(not(target_arch = "wasm32"))]
+pub struct ServerFnRegistration(pub &'static dyn ApexServerFn);
+
+#[cfg(not(target_arch = "wasm32"))]
+inventory::collect!(ServerFnRegistration);
+
+#[cfg(not(target_arch = "wasm32"))]
+impl std::fmt::Debug for ServerFnRegistration {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("ServerFnRegistration")
+            .field(&self.0.path())
+            .finish()
+    }
+}
+
+/// Error returned by a server function when it couldn't be called, e.g. because of the
+/// network. The error type of a `#[server]` function must implement `From<ServerFnError>`.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct ServerFnError {
+    message: String,
+}
+
+impl ServerFnError {
+    pub fn new(message: impl Into<String>) -> Self {
+        Self {
+            message: message.into(),
+        }
+    }
+
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+impl std::fmt::Display for ServerFnError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for ServerFnError {}
+
+/// Call the server function at `path` from the browser, with its arguments as a tuple.
+#[doc(hidden)]
+pub async fn call_server_fn<A, T, E>(path: &str, args: &A) -> Result<T, E>
+where
+    A: serde::Serialize,
+    T: serde::de::DeserializeOwned,
+    E: serde::de::DeserializeOwned + From<ServerFnError>,
+{
+    let js_error = |error: wasm_bindgen::JsValue| {
+        ServerFnError::new(
+            error
+                .as_string()
+                .unwrap_or_else(|| format!("failed to call `{path}`")),
+        )
+    };
+
+    let body =
+        serde_json::to_string(args).map_err(|error| ServerFnError::new(error.to_string()))?;
+
+    let request_init = web_sys::RequestInit::new();
+    request_init.set_method("POST");
+    request_init.set_body(&body.into());
+
+    let headers = web_sys::Headers::new().map_err(js_error)?;
+    headers
+        .set("content-type", "application/json")
+        .map_err(js_error)?;
+    request_init.set_headers(&headers);
+
+    let window = web_sys::window().ok_or_else(|| ServerFnError::new("window not found"))?;
+    let fetch_promise = window.fetch_with_str_and_init(&crate::with_base_path(path), &request_init);
+
+    let response = wasm_bindgen_futures::JsFuture::from(fetch_promise)
+        .await
+        .map_err(js_error)?
+        .dyn_into::<web_sys::Response>()
+        .map_err(js_error)?;
+
+    let text = wasm_bindgen_futures::JsFuture::from(response.text().map_err(js_error)?)
+        .await
+        .map_err(js_error)?
+        .as_string()
+        .unwrap_or_default();
+
+    if !response.ok() {
+        return Err(ServerFnError::new(format!(
+            "`{path}` answered with status {}: {text}",
+            response.status()
+        ))
+        .into());
+    }
+
+    serde_json::from_str::<Result<T, E>>(&text)
+        .map_err(|error| ServerFnError::new(error.to_string()))?
+}
+
+/// Run a server function for a request: deserialize its arguments from the body and
+/// serialize the `Result` it returns.
+#[doc(hidden)]
+pub async fn handle_server_fn<A, R, F>(
+    request: RequestContext,
+    server_fn: impl FnOnce(A) -> F,
+) -> HttpResponse
+where
+    A: serde::de::DeserializeOwned,
+    R: serde::Serialize,
+    F: Future<Output = R>,
+{
+    let args = match request.json::<A>() {
+        Ok(args) => args,
+        Err(error) => return HttpResponse::new(400).body(error.to_string()),
+    };
+
+    match serde_json::to_string(&server_fn(args).await) {
+        Ok(body) => HttpResponse::json(body),
+        Err(error) => {
+            log::error!(
+                "failed to serialize the result of server function `{}`: {error}",
+                request.path()
+            );
+
+            HttpResponse::server_error()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn handle_server_fn_serializes_the_result() {
+        let divide = |(a, b): (i32, i32)| async move {
+            a.checked_div(b)
+                .ok_or_else(|| ServerFnError::new("division by zero"))
+        };
+
+        let response =
+            handle_server_fn(RequestContext::new("POST", "/").body("[6, 3]"), divide).await;
+        assert_eq!(response.status, 200);
+        assert_eq!(response.body, r#"{"Ok":2}"#);
+
+        let response =
+            handle_server_fn(RequestContext::new("POST", "/").body("[6, 0]"), divide).await;
+        assert_eq!(response.body, r#"{"Err":{"message":"division by zero"}}"#);
+
+        let response = handle_server_fn(RequestContext::new("POST", "/").body("[6]"), divide).await;
+        assert_eq!(response.status, 400);
+    }
+}
@@ -4,34 +4,60 @@
 //! handling route registration, hierarchical route matching, and request processing.
 
 use matchit::{Match, Router};
-use std::{collections::HashMap, future::Future, pin::Pin};
+use std::{collections::HashMap, future::Future, pin::Pin, sync::Arc};
 
-use crate::get_matched_path;
+use crate::{
+    ApexMiddleware, ApexServerFn, ApexServerFnHandler, HttpResponse, RequestContext,
+    base_path::prefix_path, get_matched_path,
+};
 
-/// Type alias for server-side route handlers.
+/// Path the endpoints of the `#[server]` functions are under.
+const SERVER_FN_PATH_PREFIX: &str = "/apex/server/";
+
+/// Renders a route once its loader ran, returning its HTML and route data. It's given the
+/// data of the action that handled the request, if any.
 ///
-/// A server handler is a boxed closure that takes route parameters as a HashMap
-/// and returns a pinned future that resolves to an HTML string response.
-pub type ApexServerHandler = Box<
+/// Routes of a request are rendered one after the other, in the order of the route chain,
+/// so the ids the components generate are the same as in the browser.
+pub type ApexServerRender = Box<
+    dyn FnOnce(HashMap<String, serde_json::Value>) -> (String, HashMap<String, serde_json::Value>)
+        + Send,
+>;
+
+/// Type alias for server-side route actions, handling the requests other than `GET`.
+///
+/// An action takes the request and returns a future that resolves to its data, added to
+/// the route data before the route is rendered, or to the response sent instead, e.g. a
+/// redirect once a form is handled.
+pub type ApexServerAction = Box<
     dyn Fn(
-            HashMap<String, String>,
+            RequestContext,
         ) -> Pin<
             Box<
-                dyn Future<
-                        Output = (
-                            String,
-                            std::rc::Rc<
-                                std::cell::RefCell<
-                                    std::collections::HashMap<String, serde_json::Value>,
-                                >,
-                            >,
-                        ),
-                    > + Send,
+                dyn Future<Output = Result<HashMap<String, serde_json::Value>, HttpResponse>>
+                    + Send,
             >,
         > + Send
         + Sync,
 >;
 
+/// Type alias for server-side route handlers.
+///
+/// A server handler is a boxed closure that takes route parameters as a HashMap
+/// and the request, and returns a pinned future that runs the loader of the route.
+/// It resolves to the render of the route, or to the response sent instead when its
+/// loader redirects or fails. The loaders of the route chain run concurrently, so the
+/// future must be `Send` and doesn't render anything. Route functions whose future isn't
+/// `Send` are run with [`run_local`](crate::run_local), using `#[route(local)]`.
+pub type ApexServerHandler = Box<
+    dyn Fn(
+            HashMap<String, String>,
+            RequestContext,
+        ) -> Pin<Box<dyn Future<Output = Result<ApexServerRender, HttpResponse>> + Send>>
+        + Send
+        + Sync,
+>;
+
 /// Trait defining the interface for server-side routes.
 ///
 /// This trait must be implemented by all route types that can be registered
@@ -49,19 +75,28 @@ pub trait ApexServerRoute: Send + Sync {
     /// Returns the handler function for this route.
     ///
     /// The handler receives route parameters extracted from the URL and returns
-    /// a future that resolves to an HTML string response. Defaults to an empty
-    /// response if not overridden.
+    /// a future that resolves to the render of the route, or to the response sent
+    /// instead. Defaults to an empty route if not overridden.
     fn handler(&self) -> ApexServerHandler {
-        Box::new(|_: HashMap<String, String>| {
+        Box::new(|_: HashMap<String, String>, _: RequestContext| {
             Box::pin(async {
-                (
-                    "".to_owned(),
-                    std::rc::Rc::new(std::cell::RefCell::new(HashMap::new())),
-                )
+                let render: ApexServerRender = Box::new(|_| (String::new(), HashMap::new()));
+                Ok(render)
             })
         })
     }
 
+    /// Returns the action handling the requests other than `GET`, e.g. form submissions.
+    /// Routes without an action answer them with `405 Method Not Allowed`.
+    fn action(&self) -> Option<ApexServerAction> {
+        None
+    }
+
+    /// Returns the middleware run around the requests of this route and of its children.
+    fn middleware(&self) -> Vec<Arc<dyn ApexMiddleware>> {
+        Vec::new()
+    }
+
     /// Returns child routes for hierarchical routing.
     ///
     /// Child routes are nested under this route's path and are processed
@@ -81,6 +116,10 @@ struct RouteChain {
     parent_pattern: Option<Vec<String>>,
     /// The handler function that processes requests for this route.
     handler: ApexServerHandler,
+    /// The action handling the requests other than `GET` for this route.
+    action: Option<ApexServerAction>,
+    /// The middleware of this route and of its parents, outermost first.
+    middleware: Vec<Arc<dyn ApexMiddleware>>,
 }
 
 impl std::fmt::Debug for RouteChain {
@@ -88,6 +127,11 @@ impl std::fmt::Debug for RouteChain {
         f.debug_struct("RouteChain")
             .field("parent_path", &self.parent_pattern)
             .field("handler", &"<ApexServerHandler>")
+            .field(
+                "action",
+                &self.action.as_ref().map(|_| "<ApexServerAction>"),
+            )
+            .field("middleware", &self.middleware.len())
             .finish()
     }
 }
@@ -100,18 +144,25 @@ impl std::fmt::Debug for RouteChain {
 pub struct ApexServerRouter {
     /// Internal router instance that handles path matching and route storage.
     router: Router<RouteChain>,
+    /// Endpoints of the registered `#[server]` functions, by path.
+    server_fns: HashMap<&'static str, ApexServerFnHandler>,
+    /// Middleware run around every request.
+    middleware: Vec<Arc<dyn ApexMiddleware>>,
 }
 
 impl std::fmt::Debug for ApexServerRouter {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("ApexServerRouter")
             .field("router", &"<Router<RouteChain>>")
+            .field("server_fns", &self.server_fns.keys().collect::<Vec<_>>())
+            .field("middleware", &self.middleware.len())
             .finish()
     }
 }
 
 impl ApexServerRouter {
-    /// Creates a new server router with the given root route.
+    /// Creates a new server router with the given root route, and the endpoints of every
+    /// `#[server]` function linked into the server.
     ///
     /// # Arguments
     ///
@@ -125,10 +176,17 @@ impl ApexServerRouter {
     pub fn new(route: &dyn ApexServerRoute) -> Self {
         let mut r = Self {
             router: Router::new(),
+            server_fns: HashMap::new(),
+            middleware: Vec::new(),
         };
 
         r.mount_root_route(route);
 
+        #[cfg(not(target_arch = "wasm32"))]
+        for registration in inventory::iter::<crate::ServerFnRegistration> {
+            r.register_server_fn(registration.0);
+        }
+
         r
     }
 
@@ -161,15 +219,30 @@ impl ApexServerRouter {
         &mut self,
         route: &dyn ApexServerRoute,
         parent_pattern: Option<Vec<String>>,
+    ) {
+        self.mount_route_with_middleware(route, parent_pattern, Vec::new());
+    }
+
+    /// Mounts a route like [`mount_route`](Self::mount_route), with the middleware of its
+    /// parents. Its own middleware runs after theirs, and is inherited by its children.
+    fn mount_route_with_middleware(
+        &mut self,
+        route: &dyn ApexServerRoute,
+        parent_pattern: Option<Vec<String>>,
+        mut middleware: Vec<Arc<dyn ApexMiddleware>>,
     ) {
         let path = route.path();
 
         let handler = route.handler();
         let children = route.children();
 
+        middleware.extend(route.middleware());
+
         let route_chain = RouteChain {
             parent_pattern: parent_pattern.clone(),
             handler,
+            action: route.action(),
+            middleware: middleware.clone(),
         };
 
         let mut parent_path = parent_pattern.unwrap_or_default();
@@ -188,14 +261,44 @@ impl ApexServerRouter {
         parent_path.push(route_path);
 
         for child in children.iter() {
-            self.mount_route(child.as_ref(), parent_path.clone().into());
+            self.mount_route_with_middleware(
+                child.as_ref(),
+                parent_path.clone().into(),
+                middleware.clone(),
+            );
         }
     }
 
+    /// Registers the endpoint of a server function, called with `POST` requests. The
+    /// `#[server]` functions are already registered by [`new`](Self::new), this adds
+    /// endpoints implementing [`ApexServerFn`] by hand.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,ignore
+    /// router.register_server_fn(&HealthCheck);
+    /// ```
+    pub fn register_server_fn(&mut self, server_fn: &dyn ApexServerFn) {
+        self.server_fns
+            .insert(server_fn.path(), server_fn.handler());
+    }
+
+    /// Adds a middleware run around every request, before the middleware of the routes.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,ignore
+    /// router.add_middleware(log_request);
+    /// ```
+    pub fn add_middleware(&mut self, middleware: impl ApexMiddleware + 'static) {
+        self.middleware.push(Arc::new(middleware));
+    }
+
     /// Handles an incoming HTTP request by matching the path and executing handlers.
     ///
-    /// This method performs hierarchical route resolution, executing parent route
-    /// handlers before child handlers to build the complete HTML response. The
+    /// This method performs hierarchical route resolution: the loaders of the matched
+    /// route and of its parents run concurrently, then the routes are rendered from the
+    /// outermost parent to the matched route to build the complete HTML response. The
     /// method supports outlet-based content composition for nested layouts.
     ///
     /// # Arguments
@@ -205,106 +308,274 @@ impl ApexServerRouter {
     ///
     /// # Returns
     ///
-    /// Returns `Some(String)` containing either:
-    /// - JSON response with `data` and `html` fields if query contains `has_exclude`
-    /// - HTML response string otherwise
+    /// Returns an [`HttpResponse`] containing either:
+    /// - JSON with `data`, `html` and `status` fields if the query has a `has_exclude` parameter,
+    ///   or with a `redirect` field when a loader redirects
+    /// - The HTML page otherwise
+    /// - The response of a loader that redirected, failed or returned its own response
     ///
-    /// Returns `None` if no route is found for the given path.
+    /// Returns a `404 Not Found` response if no route is found for the given path.
     ///
     /// # Examples
     ///
     /// ```rust,ignore
     /// let response = router.handle_request("/users/123", "").await;
     /// ```
-    pub async fn handle_request(&self, path: &str, query: &str) -> Option<String> {
-        apex_utils::reset_counters();
-        let mut data = HashMap::<String, serde_json::Value>::new();
+    pub async fn handle_request(&self, path: &str, query: &str) -> HttpResponse {
+        let uri = if query.is_empty() {
+            path.to_owned()
+        } else {
+            format!("{path}?{query}")
+        };
 
-        let has_exclude = query.contains("has_exclude");
+        self.handle(RequestContext::new("GET", uri)).await
+    }
 
-        let exclude_path = query
-            .split('&')
-            .find(|s| s.starts_with("exclude="))
-            .and_then(|s| s.split('=').nth(1))
-            .unwrap_or("")
-            .replace("%2F", "/"); // Handle URL-encoded slashes
+    /// Handles a request like [`handle_request`](Self::handle_request), giving the route
+    /// functions and the components access to the whole request.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,ignore
+    /// let request = RequestContext::new("GET", "/users/123").header("cookie", "session=abc");
+    /// let response = router.handle(request).await;
+    /// ```
+    pub async fn handle(&self, request: RequestContext) -> HttpResponse {
+        let has_exclude = request.is_navigation();
 
-        if let Ok(route_match) = self.router.at(path) {
-            let mut html = String::new();
+        // Server functions only run the router's middleware
+        let route_middleware = match self.router.at(request.path()) {
+            Ok(route_match) if !self.server_fns.contains_key(request.path()) => {
+                route_match.value.middleware.as_slice()
+            }
+            _ => &[],
+        };
 
-            if let Some(parent_patterns_chain) = route_match.value.parent_pattern.as_ref() {
-                for parent_pattern in parent_patterns_chain.iter() {
-                    let matched_path = get_matched_path(parent_pattern, path);
+        let middleware: Vec<&dyn ApexMiddleware> = self
+            .middleware
+            .iter()
+            .chain(route_middleware)
+            .map(|middleware| middleware.as_ref())
+            .collect();
 
-                    if matched_path == exclude_path {
-                        continue;
-                    }
+        let mut request = request;
+        let mut response = None;
+        let mut ran = 0;
 
-                    if let Ok(parent_route_match) = self.router.at(&matched_path) {
-                        self.update_html(parent_route_match, &mut html, &mut data)
-                            .await;
-                    }
+        for middleware in &middleware {
+            match middleware.before(request.clone()).await {
+                Ok(next_request) => request = next_request,
+                Err(early_response) => {
+                    response = Some(Self::respond(early_response, has_exclude));
+                    break;
                 }
             }
 
-            self.update_html(route_match, &mut html, &mut data).await;
+            ran += 1;
+        }
+
+        let mut response = match response {
+            Some(response) => response,
+            None => self.handle_route(&request).await,
+        };
+
+        for middleware in middleware[..ran].iter().rev() {
+            response = middleware.after(&request, response).await;
+        }
+
+        // Redirects within the app go to its path on the site, the client router follows the
+        // ones it gets during navigation itself
+        if !has_exclude && let Some(location) = response.location() {
+            let location = prefix_path(&request.base_path, location);
+            response = response.header("location", location);
+        }
+
+        // Responses without a content type are the page, or JSON during navigation
+        if !response.body.is_empty() && response.header_value("content-type").is_none() {
+            let content_type = if has_exclude {
+                "application/json"
+            } else {
+                "text/html; charset=utf-8"
+            };
+
+            response = response.header("content-type", content_type);
+        }
+
+        response
+    }
+
+    /// Handles a request once the `before` hooks of its middleware ran.
+    async fn handle_route(&self, request: &RequestContext) -> HttpResponse {
+        let path = request.path();
+
+        if let Some(server_fn) = self.server_fns.get(path) {
+            if !request.method.eq_ignore_ascii_case("POST") {
+                return HttpResponse::new(405)
+                    .header("allow", "POST")
+                    .body("Method Not Allowed");
+            }
+
+            return server_fn(request.clone()).await;
+        }
+
+        // Calls to a function the server doesn't have, e.g. from a client built from other
+        // sources, aren't rendered as a route
+        if let Some(server_fn) = path.strip_prefix(SERVER_FN_PATH_PREFIX) {
+            log::warn!(
+                "no server function `{server_fn}`, is the client built from the same sources?"
+            );
+
+            return HttpResponse::not_found();
+        }
+
+        let mut data = HashMap::<String, serde_json::Value>::new();
+
+        let has_exclude = request.is_navigation();
+        let exclude_path = request.query_param("exclude").unwrap_or_default();
+
+        let Ok(route_match) = self.router.at(path) else {
+            return Self::respond(HttpResponse::not_found(), has_exclude);
+        };
+
+        // Submissions are handled by the action of the route, then the route is rendered again
+        let mut action_data = HashMap::new();
 
-            if has_exclude {
-                let json_response = serde_json::json!({
-                    "data": data,
-                    "html": html
-                });
+        if !matches!(request.method.to_ascii_uppercase().as_str(), "GET" | "HEAD") {
+            let Some(action) = route_match.value.action.as_ref() else {
+                let response = HttpResponse::new(405)
+                    .header("allow", "GET")
+                    .body("Method Not Allowed");
 
-                return Some(serde_json::to_string(&json_response).unwrap_or_else(|_| {
+                return Self::respond(response, has_exclude);
+            };
+
+            match action(request.clone()).await {
+                Ok(data) => action_data = data,
+                Err(response) => return Self::respond(response, has_exclude),
+            }
+        }
+
+        // Parents already shown by the client, during a navigation, aren't loaded again
+        let parent_paths: Vec<String> = route_match
+            .value
+            .parent_pattern
+            .iter()
+            .flatten()
+            .map(|parent_pattern| get_matched_path(parent_pattern, path))
+            .filter(|matched_path| *matched_path != exclude_path)
+            .collect();
+
+        let mut route_matches: Vec<_> = parent_paths
+            .iter()
+            .filter_map(|matched_path| self.router.at(matched_path).ok())
+            .collect();
+
+        route_matches.push(route_match);
+
+        let loaded_routes = futures_util::future::join_all(
+            route_matches
+                .iter()
+                .map(|route_match| Self::load_route(route_match, request)),
+        )
+        .await;
+
+        // The outermost route that didn't load answers the request
+        let renders = match loaded_routes.into_iter().collect::<Result<Vec<_>, _>>() {
+            Ok(renders) => renders,
+            Err(response) => return Self::respond(response, has_exclude),
+        };
+
+        // The ids of the page start over for each render, which doesn't `.await` so no other
+        // render runs on this thread in between
+        apex_utils::reset_counters();
+        let mut html = String::new();
+
+        for (route_match, render) in route_matches.iter().zip(renders) {
+            Self::update_html(route_match, render, &action_data, &mut html, &mut data);
+        }
+
+        if has_exclude {
+            let json_response = serde_json::json!({
+                "data": data,
+                "html": html,
+                "status": 200
+            });
+
+            return HttpResponse::json(serde_json::to_string(&json_response).unwrap_or_else(
+                |_| {
                     format!(
-                        r#"{{"data": {{}}, "html": "{}"}}"#,
+                        r#"{{"data": {{}}, "html": "{}", "status": 200}}"#,
                         html.replace('"', r#"\""#)
                     )
-                }));
-            }
+                },
+            ));
+        }
 
-            let json_data = serde_json::json!(data);
-            let init_data_script = format!(
-                r#"<script id="apex-init-data">window.INIT_DATA = {};</script>"#,
-                serde_json::to_string(&json_data).unwrap_or_else(|_| "{}".to_owned())
-            );
+        let json_data = serde_json::json!(data);
+        let mut init_data_script = format!(
+            r#"<script id="apex-init-data">window.INIT_DATA = {};"#,
+            serde_json::to_string(&json_data).unwrap_or_else(|_| "{}".to_owned())
+        );
+
+        // The client router adds the path the router is mounted at to the paths it requests
+        if !request.base_path.is_empty() {
+            let base_path = serde_json::Value::from(request.base_path.as_str())
+                .to_string()
+                .replace('<', "\\u003c");
+            init_data_script.push_str(&format!("window.APEX_BASE_PATH = {base_path};"));
+        }
 
-            // Inject the init data script into the HTML
-            if !init_data_script.is_empty() {
-                // Try to inject before closing </head> tag first
-                if let Some(head_pos) = html.find("</head>") {
-                    html.insert_str(head_pos, &init_data_script);
-                } else if let Some(body_pos) = html.find("</body>") {
-                    // If no </head> tag, inject before closing </body> tag
-                    html.insert_str(body_pos, &init_data_script);
-                } else {
-                    // If no head or body tags, append to the end
-                    html.push_str(&init_data_script);
-                }
+        init_data_script.push_str("</script>");
+
+        // Inject the init data script into the HTML
+        if !init_data_script.is_empty() {
+            // Try to inject before closing </head> tag first
+            if let Some(head_pos) = html.find("</head>") {
+                html.insert_str(head_pos, &init_data_script);
+            } else if let Some(body_pos) = html.find("</body>") {
+                // If no </head> tag, inject before closing </body> tag
+                html.insert_str(body_pos, &init_data_script);
+            } else {
+                // If no head or body tags, append to the end
+                html.push_str(&init_data_script);
             }
+        }
+
+        HttpResponse::html(html)
+    }
+
+    /// Response sent instead of the page. During navigation the client router gets it as
+    /// JSON, following redirects itself and showing the body of other responses.
+    fn respond(response: HttpResponse, has_exclude: bool) -> HttpResponse {
+        if !has_exclude {
+            return response;
+        }
 
-            return Some(html);
+        let json_response = match response.location() {
+            Some(location) => serde_json::json!({
+                "redirect": location,
+                "status": response.status
+            }),
+            None => serde_json::json!({
+                "data": {},
+                "html": response.body,
+                "status": response.status
+            }),
         };
 
-        None
+        HttpResponse::json(json_response.to_string())
     }
 
-    /// Updates the HTML response by executing a route handler and composing content.
-    ///
-    /// This method extracts route parameters, executes the route handler, and
-    /// integrates the result into the existing HTML response. It handles both
-    /// outlet-based composition (for nested layouts) and simple concatenation.
+    /// Runs the loader of a matched route.
     ///
     /// # Arguments
     ///
     /// * `route_match` - The matched route containing handler and parameters
-    /// * `html` - Mutable reference to the HTML response being built
-    async fn update_html(
-        &self,
-        route_match: Match<'_, '_, &RouteChain>,
-        html: &mut String,
-        data: &mut HashMap<String, serde_json::Value>,
-    ) {
+    /// * `request` - The request, given to the handler
+    fn load_route(
+        route_match: &Match<'_, '_, &RouteChain>,
+        request: &RequestContext,
+    ) -> Pin<Box<dyn Future<Output = Result<ApexServerRender, HttpResponse>> + Send>> {
         let handler = route_match.value.handler.as_ref();
 
         let params_map: HashMap<String, String> = route_match
@@ -313,6 +584,29 @@ impl ApexServerRouter {
             .map(|(k, v)| (k.to_owned(), v.to_owned()))
             .collect();
 
+        handler(params_map, request.clone())
+    }
+
+    /// Updates the HTML response by rendering a loaded route and composing content.
+    ///
+    /// This method renders the route and integrates the result into the existing
+    /// HTML response. It handles both outlet-based composition (for nested layouts)
+    /// and simple concatenation.
+    ///
+    /// # Arguments
+    ///
+    /// * `route_match` - The matched route
+    /// * `render` - The render of the route, returned by its loader
+    /// * `action_data` - The data of the action that handled the request
+    /// * `html` - Mutable reference to the HTML response being built
+    /// * `data` - The route data of the response
+    fn update_html(
+        route_match: &Match<'_, '_, &RouteChain>,
+        render: ApexServerRender,
+        action_data: &HashMap<String, serde_json::Value>,
+        html: &mut String,
+        data: &mut HashMap<String, serde_json::Value>,
+    ) {
         let parent_path: String = route_match
             .value
             .parent_pattern
@@ -320,9 +614,19 @@ impl ApexServerRouter {
             .unwrap_or_default()
             .join("");
 
-        let (child_html, child_data) = handler(params_map).await;
+        let (child_html, child_data) = render(action_data.clone());
 
-        data.extend(child_data.borrow().clone());
+        // Objects shared by the routes, like the errors caught by error boundaries, are merged
+        for (key, value) in child_data {
+            match (data.get_mut(&key), value) {
+                (Some(serde_json::Value::Object(existing)), serde_json::Value::Object(value)) => {
+                    existing.extend(value);
+                }
+                (_, value) => {
+                    data.insert(key, value);
+                }
+            }
+        }
 
         if html.contains("<!-- @outlet-begin -->") && html.contains("<!-- @outlet-end -->") {
             Self::replace_outlet_content(&parent_path, html, &child_html);
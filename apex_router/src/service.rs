@@ -0,0 +1,366 @@
+//! `tower` service running the server router, to mount it in an other server such as axum.
+
+use std::{
+    convert::Infallible,
+    future::Future,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+};
+
+use bytes::Bytes;
+use http_body_util::{BodyExt, Full, LengthLimitError, Limited};
+
+use crate::{ApexServerRouter, DEFAULT_MAX_BODY_SIZE, HttpResponse, RequestContext};
+
+/// `tower` service handling requests with an [`ApexServerRouter`].
+///
+/// The extensions of the `http` request, e.g. a database pool added by a layer, are
+/// available to the loaders and actions through [`RequestContext::extensions`].
+#[derive(Debug, Clone)]
+pub struct ApexService {
+    router: Arc<ApexServerRouter>,
+    max_body_size: usize,
+}
+
+impl ApexService {
+    /// Creates a service for the router.
+    pub fn new(router: ApexServerRouter) -> Self {
+        Self {
+            router: Arc::new(router),
+            max_body_size: DEFAULT_MAX_BODY_SIZE,
+        }
+    }
+
+    /// Sets the largest request body read, in bytes, larger ones are answered
+    /// `413 Payload Too Large`. Defaults to [`DEFAULT_MAX_BODY_SIZE`].
+    pub fn max_body_size(mut self, max_body_size: usize) -> Self {
+        self.max_body_size = max_body_size;
+        self
+    }
+}
+
+impl ApexServerRouter {
+    /// Turns the router into a `tower` service.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,ignore
+    /// let service = ApexServerRouter::new(&RootPageRoute::new()).into_service();
+    /// ```
+    pub fn into_service(self) -> ApexService {
+        ApexService::new(self)
+    }
+
+    /// Turns the router into an axum router, to be nested in the app:
+    ///
+    /// ```rust,ignore
+    /// let app = axum::Router::new()
+    ///     .route("/api/health", get(health))
+    ///     .nest("/app", ApexServerRouter::new(&RootPageRoute::new()).into_axum_router())
+    ///     .layer(Extension(pool));
+    /// ```
+    ///
+    /// The routes and links of the app are written without the path it's nested at: it's
+    /// added to the links, redirects, client navigations and server function calls of its
+    /// pages, see [`with_base_path`](crate::with_base_path). The remote address is read from
+    /// axum's `ConnectInfo`, when the app is served with `into_make_service_with_connect_info`.
+    #[cfg(feature = "axum")]
+    pub fn into_axum_router<S: Clone + Send + Sync + 'static>(self) -> axum::Router<S> {
+        axum::Router::new().fallback_service(self.into_service())
+    }
+}
+
+impl<B> tower_service::Service<http::Request<B>> for ApexService
+where
+    B: http_body::Body + Send + 'static,
+    B::Data: Send,
+    B::Error: Into<Box<dyn std::error::Error + Send + Sync>>,
+{
+    type Response = http::Response<Full<Bytes>>;
+    type Error = Infallible;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Infallible>> + Send>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, request: http::Request<B>) -> Self::Future {
+        let router = Arc::clone(&self.router);
+        let max_body_size = self.max_body_size;
+
+        Box::pin(async move {
+            let (parts, body) = request.into_parts();
+
+            let body = match Limited::new(body, max_body_size).collect().await {
+                Ok(body) => body,
+                Err(error) if error.is::<LengthLimitError>() => {
+                    return Ok(into_http_response(
+                        HttpResponse::new(413).body("Payload Too Large"),
+                    ));
+                }
+                Err(_) => {
+                    return Ok(into_http_response(
+                        HttpResponse::new(400).body("Bad Request"),
+                    ));
+                }
+            };
+
+            let uri = parts
+                .uri
+                .path_and_query()
+                .map_or_else(|| parts.uri.path().to_owned(), ToString::to_string);
+
+            let mut context = RequestContext::new(parts.method.as_str(), uri)
+                .body(String::from_utf8_lossy(&body.to_bytes()));
+
+            for (name, value) in &parts.headers {
+                if let Ok(value) = value.to_str() {
+                    context = context.header(name.as_str(), value);
+                }
+            }
+
+            #[cfg(feature = "axum")]
+            {
+                use axum::extract::{ConnectInfo, OriginalUri};
+
+                if let Some(OriginalUri(original_uri)) = parts.extensions.get::<OriginalUri>() {
+                    context =
+                        context.base_path(nested_base_path(original_uri.path(), parts.uri.path()));
+                }
+
+                if let Some(ConnectInfo(remote_addr)) =
+                    parts.extensions.get::<ConnectInfo<std::net::SocketAddr>>()
+                {
+                    context = context.remote_addr(*remote_addr);
+                }
+            }
+
+            context.extensions.insert(parts.extensions);
+
+            Ok(into_http_response(router.handle(context).await))
+        })
+    }
+}
+
+/// Path a router is nested at, the original path of the request without the path the router
+/// is given: `/app` for `/app/users` handled as `/users`
+#[cfg(feature = "axum")]
+fn nested_base_path<'a>(original_path: &'a str, path: &str) -> &'a str {
+    let base_path = original_path.strip_suffix(path).unwrap_or_default();
+
+    // `/app` and `/app/` are both handled as `/`
+    if path == "/" && base_path.is_empty() {
+        original_path.trim_end_matches('/')
+    } else {
+        base_path
+    }
+}
+
+fn into_http_response(response: HttpResponse) -> http::Response<Full<Bytes>> {
+    let mut builder = http::Response::builder().status(response.status);
+
+    for (name, value) in &response.headers {
+        builder = builder.header(name, value);
+    }
+
+    builder
+        .body(Full::new(Bytes::from(response.body)))
+        .unwrap_or_else(|_| {
+            let mut response =
+                http::Response::new(Full::new(Bytes::from_static(b"Invalid response")));
+            *response.status_mut() = http::StatusCode::INTERNAL_SERVER_ERROR;
+            response
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ApexServerRender, ApexServerRoute};
+    use std::collections::HashMap;
+    use tower_service::Service;
+
+    #[derive(Clone)]
+    struct Greeting(&'static str);
+
+    struct GreetingRoute;
+
+    impl ApexServerRoute for GreetingRoute {
+        fn handler(&self) -> crate::ApexServerHandler {
+            Box::new(|_: HashMap<String, String>, request: RequestContext| {
+                Box::pin(async move {
+                    let greeting = request.extensions.get::<Greeting>().map_or("", |g| g.0);
+                    let html = format!("<p>{greeting} {}</p>", request.body);
+                    let render: ApexServerRender = Box::new(move |_| (html, HashMap::new()));
+
+                    Ok(render)
+                })
+            })
+        }
+    }
+
+    /// Renders the base path and the remote address, redirects requests with a `login` query
+    struct RequestRoute;
+
+    impl ApexServerRoute for RequestRoute {
+        fn handler(&self) -> crate::ApexServerHandler {
+            Box::new(|_: HashMap<String, String>, request: RequestContext| {
+                Box::pin(async move {
+                    if request.query_param("login").is_some() {
+                        return Err(HttpResponse::redirect("/login"));
+                    }
+
+                    // Rendered like the routes of the macro, with the request as the current one
+                    let render: ApexServerRender = Box::new(move |_| {
+                        let html = request.scope(|| {
+                            format!(
+                                "<p>{} {:?}</p>",
+                                crate::with_base_path("/"),
+                                request.remote_addr
+                            )
+                        });
+
+                        (html, HashMap::new())
+                    });
+
+                    Ok(render)
+                })
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn service_passes_extensions() {
+        let mut service = ApexServerRouter::new(&GreetingRoute).into_service();
+
+        let mut request = http::Request::get("/")
+            .body(Full::new(Bytes::from("world")))
+            .unwrap();
+        request.extensions_mut().insert(Greeting("Hello"));
+
+        let response = service.call(request).await.unwrap();
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+
+        assert!(String::from_utf8_lossy(&body).starts_with("<p>Hello world</p>"));
+
+        let request = http::Request::get("/missing")
+            .body(Full::new(Bytes::new()))
+            .unwrap();
+        let response = service.call(request).await.unwrap();
+
+        assert_eq!(response.status(), http::StatusCode::NOT_FOUND);
+
+        // The content type is set like with `apex::serve`
+        let request = http::Request::post("/")
+            .body(Full::new(Bytes::new()))
+            .unwrap();
+        let response = service.call(request).await.unwrap();
+
+        assert_eq!(response.status(), http::StatusCode::METHOD_NOT_ALLOWED);
+        assert_eq!(
+            response.headers()["content-type"],
+            "text/html; charset=utf-8"
+        );
+
+        let request = http::Request::post("/?has_exclude")
+            .body(Full::new(Bytes::new()))
+            .unwrap();
+        let response = service.call(request).await.unwrap();
+
+        assert_eq!(response.headers()["content-type"], "application/json");
+    }
+
+    #[tokio::test]
+    async fn service_rejects_large_bodies() {
+        let mut service = ApexServerRouter::new(&GreetingRoute)
+            .into_service()
+            .max_body_size(4);
+
+        let request = http::Request::get("/")
+            .body(Full::new(Bytes::from("world")))
+            .unwrap();
+        let response = service.call(request).await.unwrap();
+
+        assert_eq!(response.status(), http::StatusCode::PAYLOAD_TOO_LARGE);
+
+        let request = http::Request::get("/")
+            .body(Full::new(Bytes::from("all")))
+            .unwrap();
+        let response = service.call(request).await.unwrap();
+
+        assert_eq!(response.status(), http::StatusCode::OK);
+    }
+
+    #[cfg(feature = "axum")]
+    #[tokio::test]
+    async fn nested_axum_router() {
+        let mut app: axum::Router = axum::Router::new()
+            .nest(
+                "/app",
+                ApexServerRouter::new(&GreetingRoute).into_axum_router(),
+            )
+            .layer(axum::Extension(Greeting("Hi")));
+
+        let request = http::Request::get("/app")
+            .body(axum::body::Body::from("axum"))
+            .unwrap();
+        let response = app.call(request).await.unwrap();
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+
+        assert!(String::from_utf8_lossy(&body).starts_with("<p>Hi axum</p>"));
+    }
+
+    #[cfg(feature = "axum")]
+    #[tokio::test]
+    async fn nested_axum_router_base_path() {
+        let mut app: axum::Router = axum::Router::new().nest(
+            "/app",
+            ApexServerRouter::new(&RequestRoute).into_axum_router(),
+        );
+
+        let remote_addr: std::net::SocketAddr = "127.0.0.1:4000".parse().unwrap();
+
+        let mut request = http::Request::get("/app")
+            .body(axum::body::Body::empty())
+            .unwrap();
+        request
+            .extensions_mut()
+            .insert(axum::extract::ConnectInfo(remote_addr));
+
+        let response = app.call(request).await.unwrap();
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let body = String::from_utf8_lossy(&body);
+
+        // The pages link to the app at its path, the client router gets it from the page
+        assert!(body.starts_with("<p>/app Some(127.0.0.1:4000)</p>"));
+        assert!(body.contains(r#"window.APEX_BASE_PATH = "/app";"#));
+
+        // Redirects within the app go to its path on the site
+        let request = http::Request::get("/app?login")
+            .body(axum::body::Body::empty())
+            .unwrap();
+        let response = app.call(request).await.unwrap();
+
+        assert_eq!(response.headers()["location"], "/app/login");
+
+        // The client router adds it to the redirects it gets during navigation itself
+        let request = http::Request::get("/app?login&has_exclude")
+            .body(axum::body::Body::empty())
+            .unwrap();
+        let response = app.call(request).await.unwrap();
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+
+        assert!(String::from_utf8_lossy(&body).contains(r#""redirect":"/login""#));
+    }
+
+    #[cfg(feature = "axum")]
+    #[test]
+    fn nested_base_paths() {
+        assert_eq!(nested_base_path("/app/users", "/users"), "/app");
+        assert_eq!(nested_base_path("/app", "/"), "/app");
+        assert_eq!(nested_base_path("/app/", "/"), "/app");
+        assert_eq!(nested_base_path("/users", "/users"), "");
+        assert_eq!(nested_base_path("/", "/"), "");
+    }
+}
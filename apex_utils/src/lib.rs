@@ -1,22 +1,33 @@
 #![allow(missing_docs)]
 
-use std::sync::atomic::{AtomicUsize, Ordering};
+use std::cell::Cell;
 
-// Global counters for runtime ID generation
-static RUNTIME_TEXT_NODE_COUNTER: AtomicUsize = AtomicUsize::new(0);
-static RUNTIME_ELEMENT_COUNTER: AtomicUsize = AtomicUsize::new(0);
-static RUNTIME_CONDITIONAL_COUNTER: AtomicUsize = AtomicUsize::new(0);
+// Counters for runtime ID generation. A page is rendered synchronously on one thread, so
+// renders running at the same time on other threads don't share them
+thread_local! {
+    static RUNTIME_TEXT_NODE_COUNTER: Cell<usize> = const { Cell::new(0) };
+    static RUNTIME_ELEMENT_COUNTER: Cell<usize> = const { Cell::new(0) };
+    static RUNTIME_CONDITIONAL_COUNTER: Cell<usize> = const { Cell::new(0) };
+}
+
+fn next_counter(counter: &'static std::thread::LocalKey<Cell<usize>>) -> usize {
+    counter.with(|counter| {
+        let value = counter.get();
+        counter.set(value + 1);
+        value
+    })
+}
 
 pub fn next_text_node_counter() -> usize {
-    RUNTIME_TEXT_NODE_COUNTER.fetch_add(1, Ordering::SeqCst)
+    next_counter(&RUNTIME_TEXT_NODE_COUNTER)
 }
 
 pub fn next_element_counter() -> usize {
-    RUNTIME_ELEMENT_COUNTER.fetch_add(1, Ordering::SeqCst)
+    next_counter(&RUNTIME_ELEMENT_COUNTER)
 }
 
 pub fn next_conditional_counter() -> usize {
-    RUNTIME_CONDITIONAL_COUNTER.fetch_add(1, Ordering::SeqCst)
+    next_counter(&RUNTIME_CONDITIONAL_COUNTER)
 }
 
 pub fn reset_counters() {
@@ -26,25 +37,25 @@ pub fn reset_counters() {
 }
 
 pub fn get_text_node_counter() -> usize {
-    RUNTIME_TEXT_NODE_COUNTER.load(Ordering::SeqCst)
+    RUNTIME_TEXT_NODE_COUNTER.with(Cell::get)
 }
 
 pub fn get_element_counter() -> usize {
-    RUNTIME_ELEMENT_COUNTER.load(Ordering::SeqCst)
+    RUNTIME_ELEMENT_COUNTER.with(Cell::get)
 }
 
 pub fn get_conditional_counter() -> usize {
-    RUNTIME_CONDITIONAL_COUNTER.load(Ordering::SeqCst)
+    RUNTIME_CONDITIONAL_COUNTER.with(Cell::get)
 }
 
 pub fn reset_text_node_counter(value: Option<usize>) {
-    RUNTIME_TEXT_NODE_COUNTER.store(value.unwrap_or(0), Ordering::SeqCst);
+    RUNTIME_TEXT_NODE_COUNTER.with(|counter| counter.set(value.unwrap_or(0)));
 }
 
 pub fn reset_element_counter(value: Option<usize>) {
-    RUNTIME_ELEMENT_COUNTER.store(value.unwrap_or(0), Ordering::SeqCst);
+    RUNTIME_ELEMENT_COUNTER.with(|counter| counter.set(value.unwrap_or(0)));
 }
 
 pub fn reset_conditional_counter(value: Option<usize>) {
-    RUNTIME_CONDITIONAL_COUNTER.store(value.unwrap_or(0), Ordering::SeqCst);
+    RUNTIME_CONDITIONAL_COUNTER.with(|counter| counter.set(value.unwrap_or(0)));
 }
@@ -9,22 +9,8 @@ pub fn button(
     #[prop(default)] onmousedown: EventHandler<apex::web_sys::MouseEvent>,
     #[prop(default)] onmouseup: EventHandler<apex::web_sys::MouseEvent>,
 ) {
-    let mut classes = vec!["button"];
-
-    if wide {
-        classes.push("button-wide");
-    }
-
-    if primary {
-        classes.push("button-primary");
-    }
-
-    if secondary {
-        classes.push("button-secondary");
-    }
-
     tmpl! {
-        <button type="button" class={classes.join(" ")} onclick={onclick} onmousedown={onmousedown} onmouseup={onmouseup}>
+        <button type="button" class="button" class:button-wide={wide} class:button-primary={primary} class:button-secondary={secondary} onclick={onclick} onmousedown={onmousedown} onmouseup={onmouseup}>
             <span class="button-symbol">
                 <#slot />
             </span>
@@ -8,11 +8,12 @@ pub(crate) struct AboutLoaderData {
 }
 
 #[route(component = About, path = "/about")]
-pub fn about_page(_params: HashMap<String, String>) -> AboutLoaderData {
-    AboutLoaderData {
-        name: "Mike".to_owned(),
-        age: 30,
-    }
+pub async fn about_page(_params: HashMap<String, String>) -> Result<AboutLoaderData, sqlx::Error> {
+    let pool = sqlx::SqlitePool::connect("sqlite::memory:").await?;
+
+    let (name, age): (String, u8) = sqlx::query_as("SELECT 'Mike', 30").fetch_one(&pool).await?;
+
+    Ok(AboutLoaderData { name, age })
 }
 
 #[component]
@@ -9,19 +9,17 @@ pub(crate) struct LoaderData {
     pub age: u8,
 }
 
+#[derive(serde::Deserialize)]
+pub(crate) struct RootParams {
+    pub name: String,
+    pub age: u8,
+}
+
 #[route(component = Layout, path = "/{name}/{age}", children = [CalculatorPageRoute, AboutPageRoute])]
-pub fn root_page(params: HashMap<String, String>) -> LoaderData {
+pub fn root_page(Path(params): Path<RootParams>) -> LoaderData {
     LoaderData {
-        name: params
-            .get("name")
-            .unwrap_or(&"Anonymous".to_owned())
-            .to_owned(),
-
-        age: params
-            .get("age")
-            .unwrap_or(&"0".to_owned())
-            .parse::<u8>()
-            .unwrap_or(0),
+        name: params.name,
+        age: params.age,
     }
 }
 